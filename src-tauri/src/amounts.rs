@@ -0,0 +1,283 @@
+//! Parsing of the amounts found in bank exports, straight to cents.
+//!
+//! Québec and French banks write `1 234,56`, `-1.234,56 $` or `1234,56-`, English ones
+//! `1,234.56` or `(12.00)` for a debit. The decimal separator is the last of `,` and `.`
+//! when both appear; a lone separator followed by exactly three digits (`1.234`,
+//! `1,234`) groups thousands. Spaces (including non-breaking ones) and apostrophes group
+//! thousands too. Currency symbols and three-letter codes around the number are ignored.
+//!
+//! Every failure names the cell and the reason, rather than yielding NaN.
+
+use crate::money::Money;
+
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥'];
+const MINUS_SIGNS: &[char] = &['-', '\u{2212}'];
+
+fn is_group_space(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '\u{a0}' | '\u{202f}' | '\'' | '’')
+}
+
+/// Trims spaces, currency symbols and a currency code (`CAD`) from both ends.
+fn trim_decorations(value: &str) -> &str {
+    let is_code = |code: &[u8]| code.len() == 3 && code.iter().all(u8::is_ascii_uppercase);
+    let mut value = value;
+    loop {
+        let trimmed =
+            value.trim_matches(|c: char| is_group_space(c) || CURRENCY_SYMBOLS.contains(&c));
+        // Letters are ASCII, so these byte counts are char boundaries
+        let bytes = trimmed.as_bytes();
+        let leading = bytes.iter().take_while(|b| b.is_ascii_alphabetic()).count();
+        let trailing = bytes
+            .iter()
+            .rev()
+            .take_while(|b| b.is_ascii_alphabetic())
+            .count();
+        let trimmed = if is_code(&bytes[..leading]) {
+            &trimmed[leading..]
+        } else if is_code(&bytes[bytes.len() - trailing..]) {
+            &trimmed[..bytes.len() - trailing]
+        } else {
+            trimmed
+        };
+        if trimmed == value {
+            return value;
+        }
+        value = trimmed;
+    }
+}
+
+/// Reads an amount such as `-1 234,56 $`, `(12.00)` or `1234,56-`.
+pub fn parse_amount(cell: &str) -> Result<Money, String> {
+    let invalid = |reason: &str| format!("Invalid amount \"{}\": {}", cell.trim(), reason);
+    let mut value = trim_decorations(cell);
+    if value.is_empty() {
+        return Err(invalid("empty"));
+    }
+
+    let mut negative = false;
+    if let Some(inner) = value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        negative = true;
+        value = trim_decorations(inner);
+    }
+    if let Some(rest) = value.strip_prefix(MINUS_SIGNS) {
+        if negative {
+            return Err(invalid("more than one sign"));
+        }
+        negative = true;
+        value = trim_decorations(rest);
+    } else if let Some(rest) = value.strip_prefix('+') {
+        value = trim_decorations(rest);
+    }
+    if let Some(rest) = value.strip_suffix(MINUS_SIGNS) {
+        if negative {
+            return Err(invalid("more than one sign"));
+        }
+        negative = true;
+        value = trim_decorations(rest);
+    }
+
+    if let Some(c) = value
+        .chars()
+        .find(|&c| !c.is_ascii_digit() && c != ',' && c != '.' && !is_group_space(c))
+    {
+        return Err(invalid(&format!("unexpected character '{}'", c)));
+    }
+    if !value.bytes().any(|b| b.is_ascii_digit()) {
+        return Err(invalid("no digits"));
+    }
+
+    // The decimal separator, if any: the last of ',' and '.', unless it appears several
+    // times or a lone one follows units and is followed by exactly three digits
+    let decimal = value.rfind([',', '.']).filter(|&i| {
+        let separator = value.as_bytes()[i];
+        let occurrences = value.bytes().filter(|&b| b == separator).count();
+        let decimals = value.len() - i - 1;
+        let other_before = value[..i].contains(if separator == b',' { '.' } else { ',' });
+        let no_units = value[..i].trim_start_matches('0').is_empty();
+        occurrences == 1 && (decimals != 3 || other_before || no_units)
+    });
+    let (integer, decimals) = match decimal {
+        Some(i) => (&value[..i], &value[i + 1..]),
+        None => (value, ""),
+    };
+
+    // Thousands groups: 1 to 3 digits, then exactly 3
+    let groups: Vec<&str> = integer
+        .split(|c: char| c == ',' || c == '.' || is_group_space(c))
+        .collect();
+    let misplaced = groups.iter().enumerate().any(|(i, group)| {
+        if i == 0 {
+            group.len() > 3 && groups.len() > 1 || group.is_empty() && groups.len() > 1
+        } else {
+            group.len() != 3
+        }
+    });
+    if misplaced {
+        return Err(invalid("misplaced thousands separator"));
+    }
+    if decimal.is_some() && decimals.is_empty() {
+        return Err(invalid("no digits after the decimal separator"));
+    }
+    if !decimals.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid("unexpected character in the decimals"));
+    }
+    if decimals.len() > 2 && decimals[2..].bytes().any(|b| b != b'0') {
+        return Err(invalid("more than 2 decimals"));
+    }
+
+    let digits = groups.concat();
+    let units: i64 = if digits.is_empty() {
+        0
+    } else {
+        digits.parse().map_err(|_| invalid("too large"))?
+    };
+    let fraction = format!("{:0<2}", decimals.get(..2).unwrap_or(decimals));
+    let cents = units
+        .checked_mul(100)
+        .and_then(|c| c.checked_add(fraction.parse::<i64>().unwrap_or(0)))
+        .filter(|c| Money::from_decimal(*c as f64 / 100.0).is_some())
+        .ok_or_else(|| invalid("too large"))?;
+    Ok(Money::from_cents(if negative { -cents } else { cents }))
+}
+
+fn parse_optional(cell: &str) -> Result<Option<Money>, String> {
+    if trim_decorations(cell).is_empty() {
+        Ok(None)
+    } else {
+        parse_amount(cell).map(Some)
+    }
+}
+
+/// Reads a debit/credit column pair, one of which is usually empty: debits are
+/// expenses whatever their sign in the file, credits keep theirs.
+pub fn parse_debit_credit(debit: &str, credit: &str) -> Result<Money, String> {
+    let debit = parse_optional(debit).map_err(|e| format!("Debit: {}", e))?;
+    let credit = parse_optional(credit).map_err(|e| format!("Credit: {}", e))?;
+    match (debit, credit) {
+        (None, None) => Err("No amount in the debit or credit column".to_string()),
+        (Some(debit), None) => Ok(-debit.abs()),
+        (None, Some(credit)) => Ok(credit),
+        (Some(debit), Some(credit)) if credit.is_zero() => Ok(-debit.abs()),
+        (Some(debit), Some(credit)) if debit.is_zero() => Ok(credit),
+        (Some(debit), Some(credit)) => Err(format!(
+            "Both the debit ({}) and credit ({}) columns have an amount",
+            debit, credit
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cents(cell: &str) -> i64 {
+        parse_amount(cell)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .cents()
+    }
+
+    #[test]
+    fn reads_grouped_thousands() {
+        assert_eq!(cents("1 234,56"), 123456);
+        assert_eq!(cents("1\u{a0}234,56"), 123456);
+        assert_eq!(cents("1\u{202f}234,56"), 123456);
+        assert_eq!(cents("1,234.56"), 123456);
+        assert_eq!(cents("1.234,56"), 123456);
+        assert_eq!(cents("1'234.56"), 123456);
+        assert_eq!(cents("1,234,567"), 123456700);
+        assert_eq!(cents("1.234"), 123400);
+        assert_eq!(cents("1,234"), 123400);
+        assert_eq!(cents("12.345"), 1234500);
+        // Not thousands: no units before the separator, or not three digits after it
+        assert_eq!(cents("0,120"), 12);
+        assert_eq!(cents("12,5"), 1250);
+        assert_eq!(cents("1234.5600"), 123456);
+    }
+
+    #[test]
+    fn reads_signs() {
+        assert_eq!(cents("(12.34)"), -1234);
+        assert_eq!(cents("( 12,34 $ )"), -1234);
+        assert_eq!(cents("-12.34"), -1234);
+        assert_eq!(cents("\u{2212}12,34"), -1234);
+        assert_eq!(cents("1234,56-"), -123456);
+        assert_eq!(cents("12.34 -"), -1234);
+        assert_eq!(cents("+12.34"), 1234);
+    }
+
+    #[test]
+    fn ignores_currency_symbols_and_codes() {
+        assert_eq!(cents("-1 234,56 $"), -123456);
+        assert_eq!(cents("$1,234.56"), 123456);
+        assert_eq!(cents("-$12.34"), -1234);
+        assert_eq!(cents("€ 12,34"), 1234);
+        assert_eq!(cents("12,34 €-"), -1234);
+        assert_eq!(cents("CAD 12.34"), 1234);
+        assert_eq!(cents("12.34 USD"), 1234);
+        assert_eq!(cents("£0.99"), 99);
+    }
+
+    #[test]
+    fn names_the_cell_and_reason_on_garbage() {
+        let err = |cell: &str| parse_amount(cell).unwrap_err();
+        assert_eq!(err("  "), "Invalid amount \"\": empty");
+        assert_eq!(err("$"), "Invalid amount \"$\": empty");
+        assert_eq!(
+            err("abc"),
+            "Invalid amount \"abc\": unexpected character 'a'"
+        );
+        assert_eq!(
+            err("12,34 CA"),
+            "Invalid amount \"12,34 CA\": unexpected character 'C'"
+        );
+        assert_eq!(err("-5-"), "Invalid amount \"-5-\": more than one sign");
+        assert_eq!(
+            err("(-12.34)"),
+            "Invalid amount \"(-12.34)\": more than one sign"
+        );
+        assert_eq!(err(",."), "Invalid amount \",.\": no digits");
+        assert_eq!(
+            err("12 34,56"),
+            "Invalid amount \"12 34,56\": misplaced thousands separator"
+        );
+        assert_eq!(
+            err("1,23,456.00"),
+            "Invalid amount \"1,23,456.00\": misplaced thousands separator"
+        );
+        assert_eq!(
+            err("12.345.6"),
+            "Invalid amount \"12.345.6\": misplaced thousands separator"
+        );
+        assert_eq!(
+            err("0,123"),
+            "Invalid amount \"0,123\": more than 2 decimals"
+        );
+        assert_eq!(
+            err("1.2345"),
+            "Invalid amount \"1.2345\": more than 2 decimals"
+        );
+        assert_eq!(
+            err("99999999999999999999"),
+            "Invalid amount \"99999999999999999999\": too large"
+        );
+    }
+
+    #[test]
+    fn reads_debit_and_credit_columns() {
+        assert_eq!(parse_debit_credit("12,34", "").unwrap().cents(), -1234);
+        assert_eq!(parse_debit_credit("-12,34", "").unwrap().cents(), -1234);
+        assert_eq!(parse_debit_credit("", "12,34").unwrap().cents(), 1234);
+        assert_eq!(parse_debit_credit("0,00", "5,00").unwrap().cents(), 500);
+        assert_eq!(
+            parse_debit_credit(" ", "$").unwrap_err(),
+            "No amount in the debit or credit column"
+        );
+        assert_eq!(
+            parse_debit_credit("x", "").unwrap_err(),
+            "Debit: Invalid amount \"x\": unexpected character 'x'"
+        );
+        assert!(parse_debit_credit("1,00", "2,00")
+            .unwrap_err()
+            .starts_with("Both the debit"));
+    }
+}
@@ -0,0 +1,382 @@
+use chrono::{Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::profile_commands::open_active_profile_db;
+use crate::money::Money;
+
+/// Where a statement balance comes from.
+const STATEMENT_ORIGINS: &[&str] = &["ofx", "camt", "manual"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountBalance {
+    pub source_id: i64,
+    pub name: String,
+    pub account_type: String,
+    pub currency: String,
+    pub is_closed: bool,
+    pub opening_balance: Money,
+    pub opening_balance_date: Option<String>,
+    /// Balance at the end of `as_of`.
+    pub balance: Money,
+    pub as_of: String,
+    pub transaction_count: i64,
+    /// Most recent statement balance on or before `as_of`, compared with the books.
+    pub last_statement: Option<StatementCheck>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningBalanceRow {
+    pub transaction_id: i64,
+    pub date: String,
+    pub description: String,
+    pub amount: Money,
+    /// Balance after this transaction.
+    pub balance: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementCheck {
+    pub id: i64,
+    pub source_id: i64,
+    pub file_id: Option<i64>,
+    pub balance_date: String,
+    pub origin: String,
+    pub currency: Option<String>,
+    pub statement_balance: Money,
+    pub computed_balance: Money,
+    /// Statement minus computed balance.
+    pub difference: Money,
+    pub matches: bool,
+}
+
+struct Account {
+    name: String,
+    account_type: String,
+    currency: String,
+    is_closed: bool,
+    opening_balance: Money,
+    opening_balance_date: Option<String>,
+}
+
+pub(crate) fn validate_date(value: &str) -> Result<(), String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| format!("Invalid date: {}", value))
+}
+
+fn today() -> String {
+    Local::now().date_naive().format("%Y-%m-%d").to_string()
+}
+
+fn read_account(conn: &Connection, source_id: i64) -> Result<Account, String> {
+    conn.query_row(
+        "SELECT name, account_type, currency, is_closed, opening_balance, opening_balance_date
+         FROM import_sources WHERE id = ?1",
+        [source_id],
+        |row| {
+            Ok(Account {
+                name: row.get(0)?,
+                account_type: row.get(1)?,
+                currency: row.get(2)?,
+                is_closed: row.get(3)?,
+                opening_balance: row.get(4)?,
+                opening_balance_date: row.get(5)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| format!("Cannot read account: {}", e))?
+    .ok_or_else(|| format!("Account not found: {}", source_id))
+}
+
+/// Transactions that move the account's balance: split children are left out since
+/// their parent carries the amount, and so is anything before the opening balance date.
+pub(crate) const BALANCE_FILTER: &str = "t.source_id = ?1 AND t.parent_transaction_id IS NULL
+     AND (s.opening_balance_date IS NULL OR t.date >= s.opening_balance_date)";
+
+/// Balance of an account at the end of `date` (`YYYY-MM-DD`). The opening balance is the
+/// balance at the start of its date.
+pub(crate) fn balance_at(conn: &Connection, source_id: i64, date: &str) -> Result<Money, String> {
+    validate_date(date)?;
+    let account = read_account(conn, source_id)?;
+    if account
+        .opening_balance_date
+        .as_deref()
+        .is_some_and(|opening| date < opening)
+    {
+        return Ok(Money::ZERO);
+    }
+    let movements: Money = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(SUM(t.amount), 0)
+                 FROM transactions t JOIN import_sources s ON s.id = t.source_id
+                 WHERE {} AND t.date <= ?2",
+                BALANCE_FILTER
+            ),
+            params![source_id, date],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Cannot compute balance: {}", e))?;
+    Ok(account.opening_balance + movements)
+}
+
+/// Transactions of an account between `date_from` and `date_to` (inclusive), each with
+/// the balance after it. Same-day transactions are ordered by id.
+pub(crate) fn running_balances(
+    conn: &Connection,
+    source_id: i64,
+    date_from: Option<&str>,
+    date_to: Option<&str>,
+) -> Result<Vec<RunningBalanceRow>, String> {
+    let account = read_account(conn, source_id)?;
+    if let Some(to) = date_to {
+        validate_date(to)?;
+    }
+    // Transactions before the opening date do not count, so a range starting on or
+    // before it starts from the opening balance
+    let starts_at_opening = match (date_from, account.opening_balance_date.as_deref()) {
+        (None, _) => true,
+        (Some(from), Some(opening)) => from <= opening,
+        (Some(_), None) => false,
+    };
+    let mut balance = match date_from {
+        Some(from) if !starts_at_opening => {
+            let day_before = NaiveDate::parse_from_str(from, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.pred_opt())
+                .ok_or_else(|| format!("Invalid date: {}", from))?;
+            balance_at(conn, source_id, &day_before.format("%Y-%m-%d").to_string())?
+        }
+        Some(from) => {
+            validate_date(from)?;
+            account.opening_balance
+        }
+        None => account.opening_balance,
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT t.id, t.date, t.description, t.amount
+             FROM transactions t JOIN import_sources s ON s.id = t.source_id
+             WHERE {} AND (?2 IS NULL OR t.date >= ?2) AND (?3 IS NULL OR t.date <= ?3)
+             ORDER BY t.date, t.id",
+            BALANCE_FILTER
+        ))
+        .map_err(|e| format!("Cannot read transactions: {}", e))?;
+    let rows = stmt
+        .query_map(params![source_id, date_from, date_to], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Money>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read transactions: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read transactions: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(transaction_id, date, description, amount)| {
+            balance += amount;
+            RunningBalanceRow {
+                transaction_id,
+                date,
+                description,
+                amount,
+                balance,
+            }
+        })
+        .collect())
+}
+
+fn statement_checks(
+    conn: &Connection,
+    where_sql: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<StatementCheck>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, source_id, file_id, balance_date, origin, currency, balance
+             FROM statement_balances WHERE {}
+             ORDER BY balance_date DESC, id DESC",
+            where_sql
+        ))
+        .map_err(|e| format!("Cannot read statement balances: {}", e))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Money>(6)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read statement balances: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read statement balances: {}", e))?;
+
+    rows.into_iter()
+        .map(
+            |(id, source_id, file_id, balance_date, origin, currency, statement_balance)| {
+                let computed_balance = balance_at(conn, source_id, &balance_date)?;
+                let difference = statement_balance - computed_balance;
+                Ok(StatementCheck {
+                    id,
+                    source_id,
+                    file_id,
+                    balance_date,
+                    origin,
+                    currency,
+                    statement_balance,
+                    computed_balance,
+                    difference,
+                    matches: difference.is_zero(),
+                })
+            },
+        )
+        .collect()
+}
+
+/// Balance of every account at the end of `as_of` (defaults to today).
+#[tauri::command]
+pub fn get_account_balances(
+    app: tauri::AppHandle,
+    as_of: Option<String>,
+) -> Result<Vec<AccountBalance>, String> {
+    let as_of = as_of.unwrap_or_else(today);
+    validate_date(&as_of)?;
+    let conn = open_active_profile_db(&app)?;
+
+    let mut stmt = conn
+        .prepare("SELECT id FROM import_sources ORDER BY is_closed, name")
+        .map_err(|e| format!("Cannot read accounts: {}", e))?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, i64>(0))
+        .map_err(|e| format!("Cannot read accounts: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read accounts: {}", e))?;
+
+    ids.into_iter()
+        .map(|source_id| {
+            let account = read_account(&conn, source_id)?;
+            let transaction_count: i64 = conn
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*)
+                         FROM transactions t JOIN import_sources s ON s.id = t.source_id
+                         WHERE {} AND t.date <= ?2",
+                        BALANCE_FILTER
+                    ),
+                    params![source_id, as_of],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Cannot count transactions: {}", e))?;
+            let last_statement = statement_checks(
+                &conn,
+                "source_id = ?1 AND balance_date <= ?2",
+                &[&source_id, &as_of],
+            )?
+            .into_iter()
+            .next();
+            Ok(AccountBalance {
+                source_id,
+                balance: balance_at(&conn, source_id, &as_of)?,
+                name: account.name,
+                account_type: account.account_type,
+                currency: account.currency,
+                is_closed: account.is_closed,
+                opening_balance: account.opening_balance,
+                opening_balance_date: account.opening_balance_date,
+                as_of: as_of.clone(),
+                transaction_count,
+                last_statement,
+            })
+        })
+        .collect()
+}
+
+/// Balance of one account at the end of `date`.
+#[tauri::command]
+pub fn get_account_balance_at(
+    app: tauri::AppHandle,
+    source_id: i64,
+    date: String,
+) -> Result<Money, String> {
+    let conn = open_active_profile_db(&app)?;
+    balance_at(&conn, source_id, &date)
+}
+
+/// Transactions of one account with the balance after each of them.
+#[tauri::command]
+pub fn get_running_balances(
+    app: tauri::AppHandle,
+    source_id: i64,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<RunningBalanceRow>, String> {
+    let conn = open_active_profile_db(&app)?;
+    running_balances(&conn, source_id, date_from.as_deref(), date_to.as_deref())
+}
+
+/// Stores the balance reported by a statement (OFX `LEDGERBAL`, camt `CLBD`, or typed in)
+/// for the end of `balance_date`. A second balance for the same date and origin replaces
+/// the first. Returns the check against the computed balance.
+#[tauri::command]
+pub fn record_statement_balance(
+    app: tauri::AppHandle,
+    source_id: i64,
+    balance_date: String,
+    balance: Money,
+    origin: String,
+    currency: Option<String>,
+    file_id: Option<i64>,
+) -> Result<StatementCheck, String> {
+    validate_date(&balance_date)?;
+    if !STATEMENT_ORIGINS.contains(&origin.as_str()) {
+        return Err(format!("Unknown statement origin: {}", origin));
+    }
+    let conn = open_active_profile_db(&app)?;
+    read_account(&conn, source_id)?;
+    conn.execute(
+        "INSERT INTO statement_balances (source_id, file_id, balance_date, balance, currency, origin)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(source_id, balance_date, origin) DO UPDATE SET
+           file_id = excluded.file_id, balance = excluded.balance, currency = excluded.currency",
+        params![source_id, file_id, balance_date, balance, currency, origin],
+    )
+    .map_err(|e| format!("Cannot save statement balance: {}", e))?;
+
+    statement_checks(
+        &conn,
+        "source_id = ?1 AND balance_date = ?2 AND origin = ?3",
+        &[&source_id, &balance_date, &origin],
+    )?
+    .into_iter()
+    .next()
+    .ok_or_else(|| "Statement balance not saved".to_string())
+}
+
+/// Statement balances of an account, newest first, each compared with the books.
+#[tauri::command]
+pub fn get_statement_checks(
+    app: tauri::AppHandle,
+    source_id: i64,
+) -> Result<Vec<StatementCheck>, String> {
+    let conn = open_active_profile_db(&app)?;
+    statement_checks(&conn, "source_id = ?1", &[&source_id])
+}
+
+#[tauri::command]
+pub fn delete_statement_balance(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    let conn = open_active_profile_db(&app)?;
+    conn.execute("DELETE FROM statement_balances WHERE id = ?1", [id])
+        .map_err(|e| format!("Cannot delete statement balance: {}", e))?;
+    Ok(())
+}
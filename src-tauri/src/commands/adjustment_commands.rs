@@ -0,0 +1,349 @@
+use chrono::{Local, NaiveDate};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::profile_commands::open_active_profile_db;
+use crate::money::Money;
+use crate::recurrence::RecurrenceRule;
+
+/// Rule assumed for adjustments flagged recurring before rules were stored.
+const LEGACY_RECURRENCE_RULE: &str = "FREQ=MONTHLY";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OccurrenceEntry {
+    pub category_id: i64,
+    pub category_name: String,
+    pub category_color: String,
+    pub category_type: String,
+    pub amount: Money,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AdjustmentOccurrence {
+    pub adjustment_id: i64,
+    pub name: String,
+    /// Date produced by the rule, which identifies the occurrence in exceptions.
+    pub occurrence_date: String,
+    /// Date the occurrence applies to, after a possible override.
+    pub date: String,
+    /// True when an exception moved the occurrence or replaced its amounts.
+    pub is_overridden: bool,
+    pub entries: Vec<OccurrenceEntry>,
+}
+
+struct AdjustmentRow {
+    id: i64,
+    name: String,
+    date: String,
+    is_recurring: bool,
+    recurrence_rule: Option<String>,
+}
+
+struct ExceptionRow {
+    id: i64,
+    occurrence_date: NaiveDate,
+    is_cancelled: bool,
+    override_date: Option<NaiveDate>,
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", value))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Entries with their category, keyed by adjustment id (or exception id).
+fn read_entries(
+    conn: &Connection,
+    sql: &str,
+) -> Result<HashMap<i64, Vec<OccurrenceEntry>>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Cannot read adjustment entries: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                OccurrenceEntry {
+                    category_id: row.get(1)?,
+                    category_name: row.get(2)?,
+                    category_color: row.get(3)?,
+                    category_type: row.get(4)?,
+                    amount: row.get(5)?,
+                    description: row.get(6)?,
+                },
+            ))
+        })
+        .map_err(|e| format!("Cannot read adjustment entries: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read adjustment entries: {}", e))?;
+
+    let mut entries: HashMap<i64, Vec<OccurrenceEntry>> = HashMap::new();
+    for (owner_id, entry) in rows {
+        entries.entry(owner_id).or_default().push(entry);
+    }
+    Ok(entries)
+}
+
+/// Expands every adjustment into its occurrences between `from` and `to` (inclusive),
+/// applying cancelled and overridden occurrences. One-off adjustments yield a single
+/// occurrence on their date.
+pub(crate) fn expand_adjustments(
+    conn: &Connection,
+    from: Option<NaiveDate>,
+    to: NaiveDate,
+) -> Result<Vec<AdjustmentOccurrence>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name, date, is_recurring, recurrence_rule FROM adjustments")
+        .map_err(|e| format!("Cannot read adjustments: {}", e))?;
+    let adjustments = stmt
+        .query_map([], |row| {
+            Ok(AdjustmentRow {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                date: row.get(2)?,
+                is_recurring: row.get(3)?,
+                recurrence_rule: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("Cannot read adjustments: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read adjustments: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT adjustment_id, id, occurrence_date, is_cancelled, override_date
+             FROM adjustment_exceptions",
+        )
+        .map_err(|e| format!("Cannot read adjustment exceptions: {}", e))?;
+    let mut exceptions: HashMap<i64, Vec<ExceptionRow>> = HashMap::new();
+    for row in stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, bool>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read adjustment exceptions: {}", e))?
+    {
+        let (adjustment_id, id, occurrence_date, is_cancelled, override_date) =
+            row.map_err(|e| format!("Cannot read adjustment exceptions: {}", e))?;
+        exceptions
+            .entry(adjustment_id)
+            .or_default()
+            .push(ExceptionRow {
+                id,
+                occurrence_date: parse_date(&occurrence_date)?,
+                is_cancelled,
+                override_date: override_date.as_deref().map(parse_date).transpose()?,
+            });
+    }
+
+    let entries = read_entries(
+        conn,
+        "SELECT ae.adjustment_id, ae.category_id, c.name, COALESCE(c.color, '#9ca3af'),
+                c.type, ae.amount, ae.description
+         FROM adjustment_entries ae
+         JOIN categories c ON c.id = ae.category_id
+         ORDER BY ae.id",
+    )?;
+    let exception_entries = read_entries(
+        conn,
+        "SELECT ee.exception_id, ee.category_id, c.name, COALESCE(c.color, '#9ca3af'),
+                c.type, ee.amount, ee.description
+         FROM adjustment_exception_entries ee
+         JOIN categories c ON c.id = ee.category_id
+         ORDER BY ee.id",
+    )?;
+
+    let mut occurrences = Vec::new();
+    for adjustment in adjustments {
+        let start = parse_date(&adjustment.date)?;
+        let own_exceptions = exceptions.remove(&adjustment.id).unwrap_or_default();
+        // An occurrence after `to` can be moved into the range by an override
+        let limit = own_exceptions
+            .iter()
+            .filter(|e| e.override_date.is_some())
+            .map(|e| e.occurrence_date)
+            .fold(to, NaiveDate::max);
+
+        let dates = if adjustment.is_recurring {
+            let rule = adjustment
+                .recurrence_rule
+                .as_deref()
+                .filter(|r| !r.trim().is_empty())
+                .unwrap_or(LEGACY_RECURRENCE_RULE);
+            RecurrenceRule::parse(rule)
+                .map_err(|e| format!("Adjustment \"{}\": {}", adjustment.name, e))?
+                .occurrences(start, limit)
+        } else {
+            vec![start]
+        };
+
+        for occurrence_date in dates {
+            let exception = own_exceptions
+                .iter()
+                .find(|e| e.occurrence_date == occurrence_date);
+            if exception.is_some_and(|e| e.is_cancelled) {
+                continue;
+            }
+            let date = exception
+                .and_then(|e| e.override_date)
+                .unwrap_or(occurrence_date);
+            if date > to || from.is_some_and(|from| date < from) {
+                continue;
+            }
+            let override_entries = exception.and_then(|e| exception_entries.get(&e.id));
+            occurrences.push(AdjustmentOccurrence {
+                adjustment_id: adjustment.id,
+                name: adjustment.name.clone(),
+                occurrence_date: format_date(occurrence_date),
+                date: format_date(date),
+                is_overridden: exception.is_some(),
+                entries: override_entries
+                    .or_else(|| entries.get(&adjustment.id))
+                    .cloned()
+                    .unwrap_or_default(),
+            });
+        }
+    }
+    occurrences.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then(a.adjustment_id.cmp(&b.adjustment_id))
+    });
+    Ok(occurrences)
+}
+
+/// Occurrences of the active profile's adjustments between `date_from` and `date_to`
+/// (`YYYY-MM-DD`, inclusive; `date_to` defaults to today), so that budgets and reports
+/// can count recurring adjustments in every period they apply to.
+#[tauri::command]
+pub fn get_adjustment_occurrences(
+    app: tauri::AppHandle,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<AdjustmentOccurrence>, String> {
+    let from = date_from.as_deref().map(parse_date).transpose()?;
+    let to = match date_to {
+        Some(to) => parse_date(&to)?,
+        None => Local::now().date_naive(),
+    };
+
+    let conn = open_active_profile_db(&app)?;
+    expand_adjustments(&conn, from, to)
+}
+
+/// Validates a recurrence rule and returns its first `count` occurrences from
+/// `start_date`, for the adjustment form to preview.
+#[tauri::command]
+pub fn preview_recurrence_rule(
+    rule: String,
+    start_date: String,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let rule = RecurrenceRule::parse(&rule)?;
+    let start = parse_date(&start_date)?;
+    let count = count.unwrap_or(5);
+    let limit = start
+        .checked_add_signed(chrono::Duration::days(366 * 50))
+        .unwrap_or(NaiveDate::MAX);
+    Ok(rule
+        .occurrences(start, limit)
+        .into_iter()
+        .take(count)
+        .map(format_date)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::migrated_test_db;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    /// A monthly adjustment from 2025-01-10 (Loyer, -100) whose February occurrence is
+    /// cancelled, March one moved and re-categorised, and May one moved back into April,
+    /// plus a one-off adjustment on 2025-02-01.
+    fn fixture() -> Connection {
+        let conn = migrated_test_db();
+        conn.execute_batch(
+            "INSERT INTO adjustments (id, name, date, is_recurring, recurrence_rule)
+             VALUES (1, 'Rent', '2025-01-10', 1, 'FREQ=MONTHLY'),
+                    (2, 'Refund', '2025-02-01', 0, NULL);
+             INSERT INTO adjustment_exceptions (id, adjustment_id, occurrence_date, is_cancelled, override_date)
+             VALUES (1, 1, '2025-02-10', 1, NULL),
+                    (2, 1, '2025-03-10', 0, '2025-03-12'),
+                    (3, 1, '2025-05-10', 0, '2025-04-30');",
+        )
+        .unwrap();
+        let money = |value: f64| Money::from_decimal(value).unwrap();
+        for (adjustment_id, category_id, amount) in [(1, 20, -100.0), (2, 310, 40.0)] {
+            conn.execute(
+                "INSERT INTO adjustment_entries (adjustment_id, category_id, amount)
+                 VALUES (?1, ?2, ?3)",
+                rusqlite::params![adjustment_id, category_id, money(amount)],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO adjustment_exception_entries (exception_id, category_id, amount)
+             VALUES (2, 310, ?1)",
+            [money(-50.0)],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn applies_cancelled_and_overridden_occurrences() {
+        let conn = fixture();
+        let occurrences =
+            expand_adjustments(&conn, Some(date("2025-01-15")), date("2025-04-30")).unwrap();
+        let summary: Vec<(&str, &str, bool, i64)> = occurrences
+            .iter()
+            .map(|o| {
+                (
+                    o.occurrence_date.as_str(),
+                    o.date.as_str(),
+                    o.is_overridden,
+                    o.entries[0].category_id,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2025-02-01", "2025-02-01", false, 310),
+                ("2025-03-10", "2025-03-12", true, 310),
+                ("2025-04-10", "2025-04-10", false, 20),
+                ("2025-05-10", "2025-04-30", true, 20),
+            ]
+        );
+        assert_eq!(occurrences[1].entries[0].amount, Money::from_cents(-5000));
+        // An override without its own entries keeps the adjustment's amounts
+        assert_eq!(occurrences[3].entries[0].amount, Money::from_cents(-10000));
+    }
+
+    #[test]
+    fn reports_invalid_rules_with_the_adjustment_name() {
+        let conn = fixture();
+        conn.execute(
+            "UPDATE adjustments SET recurrence_rule = 'FREQ=HOURLY' WHERE id = 1",
+            [],
+        )
+        .unwrap();
+        let err = expand_adjustments(&conn, None, date("2025-04-30")).unwrap_err();
+        assert_eq!(err, "Adjustment \"Rent\": Unsupported frequency: HOURLY");
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Serialize;
+
+use crate::amounts;
+use crate::money::Money;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportAmount {
+    /// The signed amount, expenses negative, or `None` when a cell cannot be read.
+    pub amount: Option<Money>,
+    /// Which cell could not be read, and why.
+    pub error: Option<String>,
+}
+
+/// Reads the amount cells of an imported file's rows: `[amount]` per row in `single`
+/// mode, `[debit, credit]` in `debit_credit` mode. In single mode, `positive_expense`
+/// files write expenses as positive amounts and are negated.
+#[tauri::command]
+pub fn parse_import_amounts(
+    rows: Vec<Vec<String>>,
+    amount_mode: String,
+    sign_convention: String,
+) -> Result<Vec<ImportAmount>, String> {
+    let debit_credit = match amount_mode.as_str() {
+        "single" => false,
+        "debit_credit" => true,
+        _ => return Err(format!("Unsupported amount mode: {}", amount_mode)),
+    };
+    let negate = match sign_convention.as_str() {
+        "negative_expense" => false,
+        "positive_expense" => true,
+        _ => return Err(format!("Unsupported sign convention: {}", sign_convention)),
+    };
+    fn cell(row: &[String], i: usize) -> &str {
+        row.get(i).map(String::as_str).unwrap_or_default()
+    }
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let result = if debit_credit {
+                amounts::parse_debit_credit(cell(row, 0), cell(row, 1))
+            } else {
+                amounts::parse_amount(cell(row, 0)).map(|a| if negate { -a } else { a })
+            };
+            match result {
+                Ok(amount) => ImportAmount {
+                    amount: Some(amount),
+                    error: None,
+                },
+                Err(e) => ImportAmount {
+                    amount: None,
+                    error: Some(e),
+                },
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,447 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::export_import_commands::{decrypt_payload, encrypt_payload, local_recipients};
+use super::profile_commands::{
+    chrono_now, new_profile_ids, read_profiles_config, write_profiles_config, Profile,
+};
+use crate::database::{self, migrations};
+
+/// Backups are SREF files of version 2: a manifest and the database, which are then
+/// wrapped in a password (SREF version 1) or age envelope.
+const BACKUP_MAGIC: &[u8; 4] = b"SREF";
+const BACKUP_VERSION: u8 = 0x02;
+const BACKUP_HEADER_LEN: usize = 4 + 1 + 4; // magic + version + manifest length
+
+/// Tables that must be present for a snapshot to be accepted as a profile database.
+const REQUIRED_TABLES: &[&str] = &[
+    "import_sources",
+    "imported_files",
+    "categories",
+    "suppliers",
+    "keywords",
+    "transactions",
+    "adjustments",
+    "adjustment_entries",
+    "budget_entries",
+    "budget_templates",
+    "budget_template_entries",
+    "user_preferences",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u8,
+    pub app_version: String,
+    pub created_at: String,
+    pub profile_name: String,
+    pub db_filename: String,
+    pub schema_version: i64,
+    pub db_size: u64,
+    pub db_sha256: String,
+    pub table_counts: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreResult {
+    pub profile: Profile,
+    pub manifest: BackupManifest,
+    pub created_profile: bool,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+fn count_tables(conn: &rusqlite::Connection) -> Result<BTreeMap<String, i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '_sqlx_%'
+             ORDER BY name",
+        )
+        .map_err(|e| format!("Cannot list tables: {}", e))?;
+    let tables: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Cannot list tables: {}", e))?
+        .filter_map(Result::ok)
+        .collect();
+
+    let mut counts = BTreeMap::new();
+    for table in tables {
+        let count: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| {
+                row.get(0)
+            })
+            .map_err(|e| format!("Cannot count rows in {}: {}", table, e))?;
+        counts.insert(table, count);
+    }
+    Ok(counts)
+}
+
+/// Takes a consistent snapshot of a profile database and returns its bytes together with
+/// the describing manifest.
+pub(crate) fn snapshot_profile(
+    app: &tauri::AppHandle,
+    profile: &Profile,
+) -> Result<(BackupManifest, Vec<u8>), String> {
+    let conn = database::open_profile_db(app, &profile.db_filename)?;
+
+    let tmp_path =
+        database::get_db_dir(app)?.join(format!("{}.snapshot-tmp", profile.db_filename));
+    database::snapshot_into(&conn, &tmp_path)?;
+    drop(conn);
+
+    let result = (|| {
+        let snapshot = database::open_db(&tmp_path)?;
+        let schema_version = database::get_schema_version(&snapshot)?;
+        let table_counts = count_tables(&snapshot)?;
+        drop(snapshot);
+
+        let db_bytes =
+            fs::read(&tmp_path).map_err(|e| format!("Cannot read snapshot: {}", e))?;
+        let manifest = BackupManifest {
+            format_version: BACKUP_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: chrono_now(),
+            profile_name: profile.name.clone(),
+            db_filename: profile.db_filename.clone(),
+            schema_version,
+            db_size: db_bytes.len() as u64,
+            db_sha256: sha256_hex(&db_bytes),
+            table_counts,
+        };
+        Ok((manifest, db_bytes))
+    })();
+
+    let _ = fs::remove_file(&tmp_path);
+    result
+}
+
+/// Serializes a manifest and database snapshot into an (unencrypted) SREF version 2
+/// container.
+pub(crate) fn pack_backup(manifest: &BackupManifest, db_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let manifest_json =
+        serde_json::to_vec(manifest).map_err(|e| format!("JSON error: {}", e))?;
+    let mut output = Vec::with_capacity(BACKUP_HEADER_LEN + manifest_json.len() + db_bytes.len());
+    output.extend_from_slice(BACKUP_MAGIC);
+    output.push(BACKUP_VERSION);
+    output.extend_from_slice(&(manifest_json.len() as u32).to_le_bytes());
+    output.extend_from_slice(&manifest_json);
+    output.extend_from_slice(db_bytes);
+    Ok(output)
+}
+
+/// Parses a backup container and checks the snapshot against its manifest.
+pub(crate) fn unpack_backup(data: &[u8]) -> Result<(BackupManifest, &[u8]), String> {
+    if data.len() < BACKUP_HEADER_LEN {
+        return Err("Not a valid profile backup".to_string());
+    }
+    match (&data[0..4], data[4]) {
+        (magic, BACKUP_VERSION) if magic == BACKUP_MAGIC => {}
+        (magic, version) if magic == BACKUP_MAGIC => {
+            return Err(format!("Unsupported backup version: {}", version));
+        }
+        _ => return Err("Not a valid profile backup".to_string()),
+    }
+
+    let manifest_len = u32::from_le_bytes([data[5], data[6], data[7], data[8]]) as usize;
+    let manifest_end = BACKUP_HEADER_LEN + manifest_len;
+    if data.len() < manifest_end {
+        return Err("Backup is truncated".to_string());
+    }
+
+    let manifest: BackupManifest = serde_json::from_slice(&data[BACKUP_HEADER_LEN..manifest_end])
+        .map_err(|e| format!("Invalid backup manifest: {}", e))?;
+    let db_bytes = &data[manifest_end..];
+
+    if db_bytes.len() as u64 != manifest.db_size {
+        return Err("Backup is truncated — database size does not match the manifest".to_string());
+    }
+    if sha256_hex(db_bytes) != manifest.db_sha256 {
+        return Err("Backup is corrupted — database checksum does not match".to_string());
+    }
+
+    Ok((manifest, db_bytes))
+}
+
+/// Refuses a snapshot taken by a newer version of the app, whose schema this version
+/// cannot open.
+pub(crate) fn check_backup_schema(manifest: &BackupManifest) -> Result<(), String> {
+    let latest = migrations::latest_version();
+    if manifest.schema_version > latest {
+        return Err(format!(
+            "This backup was made by a newer version of the app (schema version {}, this version supports up to {}); update the app to restore it",
+            manifest.schema_version, latest
+        ));
+    }
+    Ok(())
+}
+
+/// Verifies that a database file on disk is a sound profile database.
+pub(crate) fn validate_profile_db(path: &Path) -> Result<(), String> {
+    let conn = database::open_db(path)?;
+    database::check_integrity(&conn)?;
+
+    for table in REQUIRED_TABLES {
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [table],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Cannot inspect database: {}", e))?;
+        if !exists {
+            return Err(format!("Database is missing the \"{}\" table", table));
+        }
+    }
+    Ok(())
+}
+
+/// Validates `db_bytes` and installs them as `db_filename`, replacing any existing file.
+/// The previous database is kept until the new one is in place.
+pub(crate) fn install_profile_db(
+    app: &tauri::AppHandle,
+    db_filename: &str,
+    db_bytes: &[u8],
+) -> Result<(), String> {
+    let db_dir = database::get_db_dir(app)?;
+    fs::create_dir_all(&db_dir).map_err(|e| format!("Cannot create database dir: {}", e))?;
+
+    let target = db_dir.join(db_filename);
+    let incoming = db_dir.join(format!("{}.restore-tmp", db_filename));
+    fs::write(&incoming, db_bytes).map_err(|e| format!("Cannot write database: {}", e))?;
+
+    if let Err(e) = validate_profile_db(&incoming) {
+        let _ = fs::remove_file(&incoming);
+        return Err(e);
+    }
+
+    // Snapshots are plaintext: re-encrypt them when restoring over an encrypted profile
+    let incoming = if target.exists() && database::encryption::is_file_encrypted(&target)? {
+        let encrypted = db_dir.join(format!("{}.restore-enc", db_filename));
+        let result = (|| {
+            let key = database::encryption::key_for_db(db_filename).ok_or_else(|| {
+                "Unlock the encrypted profile before restoring into it".to_string()
+            })?;
+            let conn = database::open_db(&incoming)?;
+            database::encryption::export_with_key(&conn, &encrypted, &key)
+        })();
+        let _ = fs::remove_file(&incoming);
+        result?;
+        encrypted
+    } else {
+        incoming
+    };
+
+    // Fold the WAL into the current database first, so that setting it aside keeps every
+    // committed change
+    if target.exists() {
+        // Encrypted databases are keyed on open once unlocked
+        let conn = database::open_db(&target)?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .map_err(|e| format!("Cannot checkpoint current database: {}", e))?;
+    }
+
+    let previous = db_dir.join(format!("{}.pre-restore", db_filename));
+    if target.exists() {
+        fs::rename(&target, &previous)
+            .map_err(|e| format!("Cannot move current database aside: {}", e))?;
+    }
+
+    if let Err(e) = fs::rename(&incoming, &target) {
+        if previous.exists() {
+            let _ = fs::rename(&previous, &target);
+        }
+        return Err(format!("Cannot install restored database: {}", e));
+    }
+    // The sidecars belonged to the previous database, checkpointed above
+    database::remove_sidecar_files(&target)?;
+
+    if previous.exists() {
+        let _ = fs::remove_file(&previous);
+    }
+    Ok(())
+}
+
+/// Snapshots a profile and writes it as an encrypted backup file. Without a password or
+/// recipients, the backup is encrypted to this installation's own key.
+pub(crate) fn write_backup_file(
+    app: &tauri::AppHandle,
+    profile: &Profile,
+    file_path: &Path,
+    password: Option<&str>,
+    recipients: &[String],
+) -> Result<BackupManifest, String> {
+    let (manifest, db_bytes) = snapshot_profile(app, profile)?;
+    let container = pack_backup(&manifest, &db_bytes)?;
+
+    let has_password = password.is_some_and(|p| !p.is_empty());
+    let recipients = if recipients.is_empty() && !has_password {
+        local_recipients(app)?
+    } else {
+        recipients.to_vec()
+    };
+
+    let bytes = encrypt_payload(container, password, &recipients)?;
+    fs::write(file_path, bytes).map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(manifest)
+}
+
+/// Writes an encrypted full backup of a profile database.
+#[tauri::command]
+pub fn backup_profile(
+    app: tauri::AppHandle,
+    profile_id: String,
+    file_path: String,
+    password: Option<String>,
+    recipients: Option<Vec<String>>,
+) -> Result<BackupManifest, String> {
+    let config = read_profiles_config(&app)?;
+    let profile = config
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile not found: {}", profile_id))?;
+
+    let recipients: Vec<String> = recipients
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| !r.trim().is_empty())
+        .collect();
+    write_backup_file(
+        &app,
+        profile,
+        Path::new(&file_path),
+        password.as_deref(),
+        &recipients,
+    )
+}
+
+/// Reads the manifest of a backup file without restoring it.
+#[tauri::command]
+pub fn read_backup_manifest(
+    app: tauri::AppHandle,
+    file_path: String,
+    password: Option<String>,
+    identity_path: Option<String>,
+) -> Result<BackupManifest, String> {
+    let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let container = decrypt_payload(&app, bytes, password, identity_path)?;
+    let (manifest, _) = unpack_backup(&container)?;
+    Ok(manifest)
+}
+
+/// Restores a backup into an existing profile (`target_profile_id`) or, when none is
+/// given, into a new profile registered in `profiles.json`. The frontend must close its
+/// connection to the target database before calling this.
+#[tauri::command]
+pub fn restore_profile(
+    app: tauri::AppHandle,
+    file_path: String,
+    password: Option<String>,
+    identity_path: Option<String>,
+    target_profile_id: Option<String>,
+    new_profile_name: Option<String>,
+) -> Result<RestoreResult, String> {
+    let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let container = decrypt_payload(&app, bytes, password, identity_path)?;
+    let (manifest, db_bytes) = unpack_backup(&container)?;
+    check_backup_schema(&manifest)?;
+
+    let mut config = read_profiles_config(&app)?;
+
+    if let Some(target_id) = target_profile_id {
+        let profile = config
+            .profiles
+            .iter()
+            .find(|p| p.id == target_id)
+            .cloned()
+            .ok_or_else(|| format!("Profile not found: {}", target_id))?;
+        install_profile_db(&app, &profile.db_filename, db_bytes)?;
+        return Ok(RestoreResult {
+            profile,
+            manifest,
+            created_profile: false,
+        });
+    }
+
+    let (id, db_filename) = new_profile_ids();
+    install_profile_db(&app, &db_filename, db_bytes)?;
+
+    let profile = Profile {
+        id,
+        name: new_profile_name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| manifest.profile_name.clone()),
+        color: "#4A90A4".to_string(),
+        pin_hash: None,
+        db_filename,
+        created_at: chrono_now(),
+        db_key_salt: None,
+    };
+    config.profiles.push(profile.clone());
+    write_profiles_config(&app, &config)?;
+
+    Ok(RestoreResult {
+        profile,
+        manifest,
+        created_profile: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(db_bytes: &[u8], schema_version: i64) -> BackupManifest {
+        BackupManifest {
+            format_version: BACKUP_VERSION,
+            app_version: "0.0.0".to_string(),
+            created_at: "0".to_string(),
+            profile_name: "Test".to_string(),
+            db_filename: "test.db".to_string(),
+            schema_version,
+            db_size: db_bytes.len() as u64,
+            db_sha256: sha256_hex(db_bytes),
+            table_counts: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn unpack_checks_the_snapshot_against_the_manifest() {
+        let packed = pack_backup(&manifest(b"database", 1), b"database").unwrap();
+        let (read, db_bytes) = unpack_backup(&packed).unwrap();
+        assert_eq!(
+            (read.profile_name.as_str(), db_bytes),
+            ("Test", &b"database"[..])
+        );
+
+        let mut corrupted = packed.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(unpack_backup(&corrupted).unwrap_err().contains("corrupted"));
+        assert!(unpack_backup(&packed[..packed.len() - 1])
+            .unwrap_err()
+            .contains("truncated"));
+
+        let mut other = packed;
+        other[0..4].copy_from_slice(b"SRBK");
+        assert_eq!(
+            unpack_backup(&other).unwrap_err(),
+            "Not a valid profile backup"
+        );
+    }
+
+    #[test]
+    fn newer_schema_is_refused() {
+        let latest = migrations::latest_version();
+        assert!(check_backup_schema(&manifest(b"", latest)).is_ok());
+        let err = check_backup_schema(&manifest(b"", latest + 1)).unwrap_err();
+        assert!(err.contains("newer version"), "{}", err);
+    }
+}
@@ -0,0 +1,410 @@
+use chrono::{DateTime, Datelike, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::backup_commands::write_backup_file;
+use super::export_import_commands::{local_recipients, parse_recipients};
+use super::profile_commands::{read_profiles_config, Profile};
+use crate::database;
+
+const SETTINGS_KEY: &str = "backup_schedule";
+const HISTORY_KEY: &str = "backup_history";
+const HISTORY_LIMIT: usize = 100;
+const BACKUP_EXTENSION: &str = "srbk";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Serializes backup runs so the background thread, the exit hook and manual runs never
+/// write to the same folder at once.
+static BACKUP_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupFrequency {
+    OnStart,
+    OnExit,
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTrigger {
+    Startup,
+    Interval,
+    Exit,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub folder: Option<String>,
+    pub frequency: BackupFrequency,
+    pub keep_daily: u32,
+    pub keep_weekly: u32,
+    pub keep_monthly: u32,
+    /// Public keys the backups are also encrypted to, so they can be restored without
+    /// this computer's key.
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        BackupSettings {
+            enabled: false,
+            folder: None,
+            frequency: BackupFrequency::Daily,
+            keep_daily: 7,
+            keep_weekly: 4,
+            keep_monthly: 12,
+            recipients: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupHistoryEntry {
+    pub created_at: String,
+    pub trigger: BackupTrigger,
+    pub success: bool,
+    pub file_path: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub deleted_by_retention: Vec<String>,
+    pub error: Option<String>,
+}
+
+fn read_settings(conn: &rusqlite::Connection) -> Result<BackupSettings, String> {
+    match database::get_preference(conn, SETTINGS_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid backup settings: {}", e)),
+        None => Ok(BackupSettings::default()),
+    }
+}
+
+fn read_history(conn: &rusqlite::Connection) -> Result<Vec<BackupHistoryEntry>, String> {
+    match database::get_preference(conn, HISTORY_KEY)? {
+        // A damaged history must never prevent backups from running
+        Some(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn append_history(conn: &rusqlite::Connection, entry: BackupHistoryEntry) -> Result<(), String> {
+    let mut history = read_history(conn)?;
+    history.push(entry);
+    if history.len() > HISTORY_LIMIT {
+        history.drain(0..history.len() - HISTORY_LIMIT);
+    }
+    let json = serde_json::to_string(&history).map_err(|e| format!("JSON error: {}", e))?;
+    database::set_preference(conn, HISTORY_KEY, &json)
+}
+
+fn last_success(history: &[BackupHistoryEntry]) -> Option<DateTime<Local>> {
+    history
+        .iter()
+        .rev()
+        .filter(|h| h.success)
+        .find_map(|h| DateTime::parse_from_rfc3339(&h.created_at).ok())
+        .map(|dt| dt.with_timezone(&Local))
+}
+
+fn is_due(
+    settings: &BackupSettings,
+    trigger: BackupTrigger,
+    last: Option<DateTime<Local>>,
+    now: DateTime<Local>,
+) -> bool {
+    if trigger == BackupTrigger::Manual {
+        return true;
+    }
+    match settings.frequency {
+        BackupFrequency::OnStart => trigger == BackupTrigger::Startup,
+        BackupFrequency::OnExit => trigger == BackupTrigger::Exit,
+        BackupFrequency::Daily | BackupFrequency::Weekly => {
+            if trigger == BackupTrigger::Exit {
+                return false;
+            }
+            let period = if settings.frequency == BackupFrequency::Daily {
+                chrono::Duration::days(1)
+            } else {
+                chrono::Duration::weeks(1)
+            };
+            last.is_none_or(|last| now - last >= period)
+        }
+    }
+}
+
+/// File name prefix shared by every backup of a profile, derived from its database file
+/// so that it stays stable across profile renames.
+fn backup_prefix(profile: &Profile) -> String {
+    let stem = Path::new(&profile.db_filename)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    format!("simplresult_{}_", stem)
+}
+
+fn list_backups(folder: &Path, prefix: &str) -> Result<Vec<(PathBuf, NaiveDateTime)>, String> {
+    let entries = fs::read_dir(folder).map_err(|e| format!("Cannot read backup folder: {}", e))?;
+    let mut backups = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(stamp) = name
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(&format!(".{}", BACKUP_EXTENSION)))
+        else {
+            continue;
+        };
+        if let Ok(taken_at) = NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT) {
+            backups.push((path, taken_at));
+        }
+    }
+    Ok(backups)
+}
+
+/// Grandfather-father-son retention: keeps the newest backup of each of the last
+/// `keep_daily` days, `keep_weekly` ISO weeks and `keep_monthly` months, and returns
+/// every other backup for deletion.
+fn select_expired(
+    backups: &[(PathBuf, NaiveDateTime)],
+    settings: &BackupSettings,
+) -> Vec<PathBuf> {
+    let mut sorted: Vec<&(PathBuf, NaiveDateTime)> = backups.iter().collect();
+    sorted.sort_by_key(|b| std::cmp::Reverse(b.1));
+
+    let mut keep: HashSet<&PathBuf> = HashSet::new();
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut months = HashSet::new();
+
+    for (path, taken_at) in &sorted {
+        let date = taken_at.date();
+        if days.len() < settings.keep_daily as usize && days.insert(date) {
+            keep.insert(path);
+        }
+        let week = date.iso_week();
+        if weeks.len() < settings.keep_weekly as usize && weeks.insert((week.year(), week.week()))
+        {
+            keep.insert(path);
+        }
+        if months.len() < settings.keep_monthly as usize
+            && months.insert((date.year(), date.month()))
+        {
+            keep.insert(path);
+        }
+    }
+
+    // Never delete the most recent backup, whatever the policy says
+    if let Some((newest, _)) = sorted.first() {
+        keep.insert(newest);
+    }
+
+    sorted
+        .into_iter()
+        .filter(|(path, _)| !keep.contains(path))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+fn run_backup(
+    app: &tauri::AppHandle,
+    profile: &Profile,
+    settings: &BackupSettings,
+    now: DateTime<Local>,
+) -> Result<(PathBuf, u64, Vec<String>), String> {
+    let folder = settings
+        .folder
+        .as_deref()
+        .filter(|f| !f.is_empty())
+        .ok_or_else(|| "No backup folder configured".to_string())?;
+    let folder = Path::new(folder);
+    fs::create_dir_all(folder).map_err(|e| format!("Cannot create backup folder: {}", e))?;
+
+    let prefix = backup_prefix(profile);
+    let file_path = folder.join(format!(
+        "{}{}.{}",
+        prefix,
+        now.format(TIMESTAMP_FORMAT),
+        BACKUP_EXTENSION
+    ));
+    let mut recipients = local_recipients(app)?;
+    recipients.extend(settings.recipients.iter().cloned());
+    let manifest = write_backup_file(app, profile, &file_path, None, &recipients)?;
+
+    let mut deleted = Vec::new();
+    for expired in select_expired(&list_backups(folder, &prefix)?, settings) {
+        if fs::remove_file(&expired).is_ok() {
+            deleted.push(expired.to_string_lossy().to_string());
+        }
+    }
+
+    Ok((file_path, manifest.db_size, deleted))
+}
+
+/// Runs the backup of one profile if its schedule says it is due, and records the outcome
+/// in the profile's history. Returns `None` when nothing was due.
+fn backup_profile_if_due(
+    app: &tauri::AppHandle,
+    profile: &Profile,
+    trigger: BackupTrigger,
+) -> Result<Option<BackupHistoryEntry>, String> {
+    // A locked encrypted profile cannot even be read until the user unlocks it
+    if profile.db_key_salt.is_some() && !database::encryption::is_unlocked(&profile.db_filename)
+    {
+        return Ok(None);
+    }
+    let conn = database::open_profile_db(app, &profile.db_filename)?;
+    let settings = read_settings(&conn)?;
+    if !settings.enabled && trigger != BackupTrigger::Manual {
+        return Ok(None);
+    }
+
+    let now = Local::now();
+    if !is_due(&settings, trigger, last_success(&read_history(&conn)?), now) {
+        return Ok(None);
+    }
+
+    let entry = match run_backup(app, profile, &settings, now) {
+        Ok((path, size, deleted)) => BackupHistoryEntry {
+            created_at: now.to_rfc3339(),
+            trigger,
+            success: true,
+            file_path: Some(path.to_string_lossy().to_string()),
+            size_bytes: Some(size),
+            deleted_by_retention: deleted,
+            error: None,
+        },
+        Err(e) => BackupHistoryEntry {
+            created_at: now.to_rfc3339(),
+            trigger,
+            success: false,
+            file_path: None,
+            size_bytes: None,
+            deleted_by_retention: Vec::new(),
+            error: Some(e),
+        },
+    };
+    append_history(&conn, entry.clone())?;
+    Ok(Some(entry))
+}
+
+/// Checks every profile's schedule and runs the backups that are due. Errors are logged
+/// rather than returned since this runs unattended.
+pub fn run_scheduled_backups(app: &tauri::AppHandle, trigger: BackupTrigger) {
+    let _guard = BACKUP_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let config = match read_profiles_config(app) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Scheduled backup skipped: {}", e);
+            return;
+        }
+    };
+
+    for profile in &config.profiles {
+        match backup_profile_if_due(app, profile, trigger) {
+            Ok(Some(entry)) if !entry.success => eprintln!(
+                "Scheduled backup of profile \"{}\" failed: {}",
+                profile.name,
+                entry.error.unwrap_or_default()
+            ),
+            Ok(_) => {}
+            Err(e) => eprintln!("Scheduled backup of profile \"{}\" failed: {}", profile.name, e),
+        }
+    }
+}
+
+/// Starts the background thread that runs startup backups, then re-checks daily and
+/// weekly schedules every hour.
+pub fn start_backup_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        run_scheduled_backups(&app, BackupTrigger::Startup);
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            run_scheduled_backups(&app, BackupTrigger::Interval);
+        }
+    });
+}
+
+fn find_profile(app: &tauri::AppHandle, profile_id: &str) -> Result<Profile, String> {
+    read_profiles_config(app)?
+        .profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile not found: {}", profile_id))
+}
+
+#[tauri::command]
+pub fn get_backup_settings(
+    app: tauri::AppHandle,
+    profile_id: String,
+) -> Result<BackupSettings, String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let conn = database::open_profile_db(&app, &profile.db_filename)?;
+    read_settings(&conn)
+}
+
+#[tauri::command]
+pub fn save_backup_settings(
+    app: tauri::AppHandle,
+    profile_id: String,
+    settings: BackupSettings,
+) -> Result<(), String> {
+    if settings.enabled && settings.folder.as_deref().is_none_or(|f| f.trim().is_empty()) {
+        return Err("A backup folder is required to enable automatic backups".to_string());
+    }
+    if settings.keep_daily + settings.keep_weekly + settings.keep_monthly == 0 {
+        return Err("The retention policy must keep at least one backup".to_string());
+    }
+    let settings = BackupSettings {
+        recipients: settings
+            .recipients
+            .iter()
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty())
+            .collect(),
+        ..settings
+    };
+    parse_recipients(&settings.recipients)?;
+
+    let profile = find_profile(&app, &profile_id)?;
+    let conn = database::open_profile_db(&app, &profile.db_filename)?;
+    let json = serde_json::to_string(&settings).map_err(|e| format!("JSON error: {}", e))?;
+    database::set_preference(&conn, SETTINGS_KEY, &json)
+}
+
+/// Returns the backup history of a profile, most recent first.
+#[tauri::command]
+pub fn get_backup_history(
+    app: tauri::AppHandle,
+    profile_id: String,
+) -> Result<Vec<BackupHistoryEntry>, String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let conn = database::open_profile_db(&app, &profile.db_filename)?;
+    let mut history = read_history(&conn)?;
+    history.reverse();
+    Ok(history)
+}
+
+/// Runs a backup of the profile right away, using its configured folder and retention.
+#[tauri::command]
+pub fn run_backup_now(
+    app: tauri::AppHandle,
+    profile_id: String,
+) -> Result<BackupHistoryEntry, String> {
+    let _guard = BACKUP_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let profile = find_profile(&app, &profile_id)?;
+    let entry = backup_profile_if_due(&app, &profile, BackupTrigger::Manual)?
+        .ok_or_else(|| "Backup was not run".to_string())?;
+    match entry.error {
+        Some(ref e) => Err(e.clone()),
+        None => Ok(entry),
+    }
+}
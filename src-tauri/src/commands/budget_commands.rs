@@ -0,0 +1,1164 @@
+use chrono::{Datelike, Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::adjustment_commands::expand_adjustments;
+use super::profile_commands::open_active_profile_db;
+use crate::money::Money;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvelopeMonth {
+    pub month: u32,
+    /// Left (negative when overspent) at the end of the previous month.
+    pub carried_in: Money,
+    pub budgeted: Money,
+    /// Money moved in, less money moved out.
+    pub transferred: Money,
+    /// Expenses less refunds, in the base currency, adjustments included.
+    pub spent: Money,
+    /// Carried in + budgeted + transferred - spent.
+    pub available: Money,
+}
+
+/// An expense category's envelope receives its monthly budget and the money moved into
+/// it, and gives out what is moved out of it or spent. When the category rolls over
+/// (`categories.budget_rollover`), what is left at the end of a month, or overspent, is
+/// carried into the next one from the first month it was funded; otherwise every month
+/// starts from zero.
+#[derive(Debug, Clone, Serialize)]
+pub struct Envelope {
+    pub category_id: i64,
+    pub category_name: String,
+    pub category_color: String,
+    pub rollover: bool,
+    /// January to December.
+    pub months: Vec<EnvelopeMonth>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetTransfer {
+    pub id: i64,
+    pub year: i64,
+    pub month: i64,
+    pub from_category_id: i64,
+    pub from_category_name: String,
+    pub to_category_id: i64,
+    pub to_category_name: String,
+    pub amount: Money,
+    pub notes: Option<String>,
+    /// The transfer this one undoes.
+    pub reversed_transfer_id: Option<i64>,
+    /// True once another transfer has undone this one.
+    pub is_reversed: bool,
+    pub created_at: String,
+}
+
+pub(crate) struct NewTransfer {
+    pub year: i64,
+    pub month: i64,
+    pub from_category_id: i64,
+    pub to_category_id: i64,
+    pub amount: Money,
+    pub notes: Option<String>,
+    /// The transfer this one undoes.
+    pub reversed_transfer_id: Option<i64>,
+}
+
+/// How a forecast proposes a category's monthly budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastMethod {
+    /// One of the others for each category, picked from its history.
+    Auto,
+    Average,
+    Median,
+    /// Each month as the same month a year earlier, for seasonal spending.
+    SameMonthLastYear,
+    /// The bills paid every month at a steady amount.
+    Recurring,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecurringBill {
+    /// Supplier name, or the transaction description.
+    pub description: String,
+    /// Latest monthly amount, positive like budget entries.
+    pub amount: Money,
+    /// Months of the history it was paid in.
+    pub months_seen: u32,
+    pub last_date: String,
+}
+
+/// Budget proposed for a category, with the figures it is based on so that it can be
+/// reviewed before being applied. Amounts are positive for expenses, like budget entries.
+#[derive(Debug, Clone, Serialize)]
+pub struct ForecastLine {
+    pub category_id: i64,
+    pub category_name: String,
+    pub category_color: String,
+    pub category_type: String,
+    /// Method used, never `Auto`.
+    pub method: ForecastMethod,
+    /// Proposed budget, January to December.
+    pub months: Vec<Money>,
+    /// Budget already entered, January to December.
+    pub current: Vec<Money>,
+    /// Monthly average and median over the history.
+    pub average: Money,
+    pub median: Money,
+    /// Months of the history with any activity in the category.
+    pub active_months: u32,
+    /// Whether the last twelve months vary widely, in step with the year before.
+    pub seasonal: bool,
+    pub recurring: Vec<RecurringBill>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetForecast {
+    pub year: i64,
+    /// First and last months of the history, as `YYYY-MM`.
+    pub history_from: String,
+    pub history_to: String,
+    pub lines: Vec<ForecastLine>,
+}
+
+struct EnvelopeCategory {
+    id: i64,
+    name: String,
+    color: String,
+    rollover: bool,
+}
+
+/// Months counted from year 0, so that consecutive months differ by one.
+fn period(year: i64, month: i64) -> i64 {
+    year * 12 + month - 1
+}
+
+fn period_start(period: i64) -> String {
+    format!(
+        "{:04}-{:02}-01",
+        period.div_euclid(12),
+        period.rem_euclid(12) + 1
+    )
+}
+
+/// Amounts per category and period, from rows of (category, year, month, amount).
+fn read_amounts(
+    conn: &Connection,
+    sql: &str,
+    params: &[&dyn rusqlite::ToSql],
+    what: &str,
+) -> Result<HashMap<(i64, i64), Money>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Cannot read {}: {}", what, e))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Money>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read {}: {}", what, e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read {}: {}", what, e))?;
+    let mut amounts: HashMap<(i64, i64), Money> = HashMap::new();
+    for (category_id, year, month, amount) in rows {
+        *amounts
+            .entry((category_id, period(year, month)))
+            .or_default() += amount;
+    }
+    Ok(amounts)
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", date))
+}
+
+/// Transactions (in the base currency) and adjustment occurrences per category and
+/// period, between two dates included.
+fn read_actuals(
+    conn: &Connection,
+    date_from: &str,
+    date_to: &str,
+) -> Result<HashMap<(i64, i64), Money>, String> {
+    let mut actuals = read_amounts(
+        conn,
+        "SELECT category_id, CAST(strftime('%Y', date) AS INTEGER),
+                CAST(strftime('%m', date) AS INTEGER), SUM(COALESCE(base_amount, amount))
+         FROM transactions
+         WHERE category_id IS NOT NULL AND date BETWEEN ?1 AND ?2
+         GROUP BY 1, 2, 3",
+        &[&date_from, &date_to],
+        "transactions",
+    )?;
+    for occurrence in expand_adjustments(conn, Some(parse_date(date_from)?), parse_date(date_to)?)?
+    {
+        let date = parse_date(&occurrence.date)?;
+        let p = period(i64::from(date.year()), i64::from(date.month()));
+        for entry in occurrence.entries {
+            *actuals.entry((entry.category_id, p)).or_default() += entry.amount;
+        }
+    }
+    Ok(actuals)
+}
+
+/// Envelopes of the active expense categories for `year`, leaving out those with
+/// nothing budgeted, moved, spent or carried in during the year.
+pub(crate) fn compute_envelopes(conn: &Connection, year: i64) -> Result<Vec<Envelope>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, COALESCE(color, '#9ca3af'), budget_rollover FROM categories
+             WHERE type = 'expense' AND is_active = 1 AND is_inputable = 1
+             ORDER BY sort_order, name",
+        )
+        .map_err(|e| format!("Cannot read categories: {}", e))?;
+    let categories = stmt
+        .query_map([], |row| {
+            Ok(EnvelopeCategory {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                rollover: row.get(3)?,
+            })
+        })
+        .map_err(|e| format!("Cannot read categories: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read categories: {}", e))?;
+
+    let budgeted = read_amounts(
+        conn,
+        "SELECT category_id, year, month, amount FROM budget_entries WHERE year <= ?1",
+        &[&year],
+        "budget entries",
+    )?;
+    let transferred = read_amounts(
+        conn,
+        "SELECT to_category_id, year, month, amount FROM budget_transfers WHERE year <= ?1
+         UNION ALL
+         SELECT from_category_id, year, month, -amount FROM budget_transfers WHERE year <= ?1",
+        &[&year],
+        "budget transfers",
+    )?;
+
+    // A rolling envelope starts the first month it is funded
+    let mut funded_from: HashMap<i64, i64> = HashMap::new();
+    for &(category_id, p) in budgeted.keys().chain(transferred.keys()) {
+        let first = funded_from.entry(category_id).or_insert(p);
+        *first = (*first).min(p);
+    }
+    let first_month = period(year, 1);
+    let last_month = period(year, 12);
+    let from = categories
+        .iter()
+        .filter(|c| c.rollover)
+        .filter_map(|c| funded_from.get(&c.id).copied())
+        .fold(first_month, i64::min);
+
+    let date_to = format!("{:04}-12-31", year);
+    let actuals = read_actuals(conn, &period_start(from), &date_to)?;
+
+    let mut envelopes = Vec::new();
+    for category in categories {
+        let funded = funded_from
+            .get(&category.id)
+            .copied()
+            .filter(|_| category.rollover);
+        let start = funded.unwrap_or(first_month).min(first_month);
+        let mut available = Money::ZERO;
+        let mut months = Vec::new();
+        for p in start..=last_month {
+            let carried_in = if funded.is_some_and(|f| p > f) {
+                available
+            } else {
+                Money::ZERO
+            };
+            let get = |amounts: &HashMap<(i64, i64), Money>| {
+                amounts.get(&(category.id, p)).copied().unwrap_or_default()
+            };
+            let budgeted = get(&budgeted);
+            let transferred = get(&transferred);
+            // Expenses are negative
+            let spent = -get(&actuals);
+            available = carried_in + budgeted + transferred - spent;
+            if p >= first_month {
+                months.push(EnvelopeMonth {
+                    month: (p - first_month + 1) as u32,
+                    carried_in,
+                    budgeted,
+                    transferred,
+                    spent,
+                    available,
+                });
+            }
+        }
+        if months
+            .iter()
+            .any(|m| !m.available.is_zero() || !m.spent.is_zero())
+        {
+            envelopes.push(Envelope {
+                category_id: category.id,
+                category_name: category.name,
+                category_color: category.color,
+                rollover: category.rollover,
+                months,
+            });
+        }
+    }
+    Ok(envelopes)
+}
+
+fn check_envelope_category(conn: &Connection, category_id: i64) -> Result<String, String> {
+    let (name, category_type, is_inputable): (String, String, bool) = conn
+        .query_row(
+            "SELECT name, type, is_inputable FROM categories WHERE id = ?1",
+            [category_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Cannot read category: {}", e))?
+        .ok_or_else(|| format!("Category not found: {}", category_id))?;
+    if category_type != "expense" {
+        return Err(format!("{} is not an expense category", name));
+    }
+    // Parent categories only group others and have no envelope of their own
+    if !is_inputable {
+        return Err(format!("{} does not have an envelope", name));
+    }
+    Ok(name)
+}
+
+fn read_transfers(
+    conn: &Connection,
+    where_sql: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<BudgetTransfer>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT t.id, t.year, t.month, t.from_category_id, f.name, t.to_category_id, c.name,
+                    t.amount, t.notes, t.reversed_transfer_id,
+                    EXISTS (SELECT 1 FROM budget_transfers r WHERE r.reversed_transfer_id = t.id),
+                    t.created_at
+             FROM budget_transfers t
+             JOIN categories f ON f.id = t.from_category_id
+             JOIN categories c ON c.id = t.to_category_id
+             WHERE {}
+             ORDER BY t.id DESC",
+            where_sql
+        ))
+        .map_err(|e| format!("Cannot read budget transfers: {}", e))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok(BudgetTransfer {
+                id: row.get(0)?,
+                year: row.get(1)?,
+                month: row.get(2)?,
+                from_category_id: row.get(3)?,
+                from_category_name: row.get(4)?,
+                to_category_id: row.get(5)?,
+                to_category_name: row.get(6)?,
+                amount: row.get(7)?,
+                notes: row.get(8)?,
+                reversed_transfer_id: row.get(9)?,
+                is_reversed: row.get(10)?,
+                created_at: row.get(11)?,
+            })
+        })
+        .map_err(|e| format!("Cannot read budget transfers: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read budget transfers: {}", e));
+    rows
+}
+
+/// Records a transfer between two envelopes, refused when the source envelope does not
+/// have `amount` available that month. The check and the insert share an immediate
+/// transaction, so that two concurrent transfers cannot both spend the same money.
+pub(crate) fn move_money(
+    conn: &mut Connection,
+    transfer: NewTransfer,
+) -> Result<BudgetTransfer, String> {
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    let saved = insert_transfer(&tx, transfer)?;
+    tx.commit()
+        .map_err(|e| format!("Cannot save budget transfer: {}", e))?;
+    Ok(saved)
+}
+
+fn insert_transfer(conn: &Connection, transfer: NewTransfer) -> Result<BudgetTransfer, String> {
+    let NewTransfer {
+        year,
+        month,
+        from_category_id,
+        to_category_id,
+        amount,
+        ..
+    } = transfer;
+    if !(1..=12).contains(&month) {
+        return Err(format!("Invalid month: {}", month));
+    }
+    if amount <= Money::ZERO {
+        return Err("The amount to move must be positive".to_string());
+    }
+    if from_category_id == to_category_id {
+        return Err("Cannot move money to the same envelope".to_string());
+    }
+    let from_name = check_envelope_category(conn, from_category_id)?;
+    check_envelope_category(conn, to_category_id)?;
+
+    // Undoing a transfer takes back money that was moved in, even if it has been spent
+    // since, so it may leave the envelope overspent
+    if transfer.reversed_transfer_id.is_none() {
+        let available = compute_envelopes(conn, year)?
+            .into_iter()
+            .find(|e| e.category_id == from_category_id)
+            .and_then(|e| e.months.into_iter().find(|m| i64::from(m.month) == month))
+            .map(|m| m.available)
+            .unwrap_or_default();
+        if amount > available {
+            return Err(format!(
+                "Only {} available in {} for {}-{:02}",
+                available, from_name, year, month
+            ));
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO budget_transfers
+           (year, month, from_category_id, to_category_id, amount, notes, reversed_transfer_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            year,
+            month,
+            from_category_id,
+            to_category_id,
+            amount,
+            transfer.notes.filter(|n| !n.trim().is_empty()),
+            transfer.reversed_transfer_id,
+        ],
+    )
+    .map_err(|e| format!("Cannot save budget transfer: {}", e))?;
+    let id = conn.last_insert_rowid();
+    read_transfers(conn, "t.id = ?1", &[&id])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Budget transfer not saved".to_string())
+}
+
+/// Undoes a transfer by recording the opposite one, so that the history keeps both.
+pub(crate) fn reverse_transfer(conn: &mut Connection, id: i64) -> Result<BudgetTransfer, String> {
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    let transfer = read_transfers(&tx, "t.id = ?1", &[&id])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Budget transfer not found: {}", id))?;
+    if transfer.is_reversed {
+        return Err("This transfer has already been undone".to_string());
+    }
+    if transfer.reversed_transfer_id.is_some() {
+        return Err("A transfer that undoes another cannot be undone".to_string());
+    }
+    let saved = insert_transfer(
+        &tx,
+        NewTransfer {
+            year: transfer.year,
+            month: transfer.month,
+            from_category_id: transfer.to_category_id,
+            to_category_id: transfer.from_category_id,
+            amount: transfer.amount,
+            notes: None,
+            reversed_transfer_id: Some(transfer.id),
+        },
+    )?;
+    tx.commit()
+        .map_err(|e| format!("Cannot save budget transfer: {}", e))?;
+    Ok(saved)
+}
+
+/// Spending is seasonal when its standard deviation over a year exceeds this share of
+/// its mean...
+const SEASONAL_VARIATION: f64 = 0.5;
+/// ...and its months correlate at least this much with those of the year before.
+const SEASONAL_CORRELATION: f64 = 0.5;
+/// An average further than this share from the median is skewed by unusual months.
+const SKEWED_AVERAGE: f64 = 0.25;
+/// Recurring bills covering this share of the average make up the budget on their own.
+const RECURRING_SHARE: f64 = 0.9;
+/// A bill is recurring once paid this many months in a row at a steady amount...
+const RECURRING_MIN_MONTHS: usize = 3;
+/// ...within this share of its latest amount...
+const RECURRING_TOLERANCE: f64 = 0.1;
+/// ...and paid in at least this share of the months since it first appeared.
+const RECURRING_COVERAGE: f64 = 0.8;
+
+fn average(values: &[Money]) -> Money {
+    let total: Money = values.iter().copied().sum();
+    Money::from_cents((total.cents() as f64 / values.len().max(1) as f64).round() as i64)
+}
+
+fn median(values: &[Money]) -> Money {
+    let mut sorted = values.to_vec();
+    sorted.sort();
+    match sorted.len() {
+        0 => Money::ZERO,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => average(&sorted[n / 2 - 1..=n / 2]),
+    }
+}
+
+/// Whether the months of `year` vary widely, in step with those of `year_before`, so that
+/// a one-off expense does not make a category seasonal.
+fn is_seasonal(year_before: &[Money], year: &[Money]) -> bool {
+    let stats = |values: &[Money]| {
+        let values: Vec<f64> = values.iter().map(|v| v.to_decimal()).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let deviation =
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt();
+        (values, mean, deviation)
+    };
+    let (before, before_mean, before_deviation) = stats(year_before);
+    let (current, mean, deviation) = stats(year);
+    if mean <= 0.0 || deviation <= mean * SEASONAL_VARIATION || before_deviation == 0.0 {
+        return false;
+    }
+    let covariance = before
+        .iter()
+        .zip(&current)
+        .map(|(b, c)| (b - before_mean) * (c - mean))
+        .sum::<f64>()
+        / current.len() as f64;
+    covariance / (before_deviation * deviation) >= SEASONAL_CORRELATION
+}
+
+/// Rounded to the dollar, as budgets are usually entered; never negative.
+fn budget_amount(amount: Money) -> Money {
+    Money::from_cents((amount.cents().max(0) as f64 / 100.0).round() as i64 * 100)
+}
+
+/// Groups a description with its variants that differ only by case, spacing or
+/// reference numbers.
+fn bill_key(description: &str) -> String {
+    description
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c.is_ascii_digit())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Bills of each category paid month after month at a steady amount, up to the last
+/// month of the history.
+fn detect_recurring_bills(
+    conn: &Connection,
+    date_from: &str,
+    date_to: &str,
+    last_period: i64,
+    signs: &HashMap<i64, i64>,
+) -> Result<HashMap<i64, Vec<RecurringBill>>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.category_id, COALESCE(s.name, t.description), t.date,
+                    COALESCE(t.base_amount, t.amount)
+             FROM transactions t
+             LEFT JOIN suppliers s ON s.id = t.supplier_id
+             WHERE t.category_id IS NOT NULL AND t.date BETWEEN ?1 AND ?2
+             ORDER BY t.date, t.id",
+        )
+        .map_err(|e| format!("Cannot read transactions: {}", e))?;
+    let rows = stmt
+        .query_map(params![date_from, date_to], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Money>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read transactions: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read transactions: {}", e))?;
+
+    struct Payments {
+        description: String,
+        last_date: String,
+        by_period: Vec<(i64, Money)>,
+    }
+    let mut groups: HashMap<(i64, String), Payments> = HashMap::new();
+    for (category_id, description, date, amount) in rows {
+        let Some(&sign) = signs.get(&category_id) else {
+            continue;
+        };
+        let day = parse_date(&date)?;
+        let p = period(i64::from(day.year()), i64::from(day.month()));
+        let payments = groups
+            .entry((category_id, bill_key(&description)))
+            .or_insert_with(|| Payments {
+                description: String::new(),
+                last_date: String::new(),
+                by_period: Vec::new(),
+            });
+        let amount = if sign < 0 { -amount } else { amount };
+        match payments.by_period.last_mut() {
+            Some((last, total)) if *last == p => *total += amount,
+            _ => payments.by_period.push((p, amount)),
+        }
+        payments.description = description;
+        payments.last_date = date;
+    }
+
+    let mut bills: HashMap<i64, Vec<RecurringBill>> = HashMap::new();
+    for ((category_id, _), payments) in groups {
+        let by_period = &payments.by_period;
+        let (Some(&(first, _)), Some(&(last, latest))) = (by_period.first(), by_period.last())
+        else {
+            continue;
+        };
+        let recent = &by_period[by_period.len().saturating_sub(RECURRING_MIN_MONTHS)..];
+        let consecutive = recent.windows(2).all(|w| w[1].0 == w[0].0 + 1);
+        let steady = recent.iter().all(|&(_, amount)| {
+            (amount - latest).abs().to_decimal() <= latest.to_decimal() * RECURRING_TOLERANCE
+        });
+        let coverage = by_period.len() as f64 / (last - first + 1) as f64;
+        if by_period.len() >= RECURRING_MIN_MONTHS
+            && latest > Money::ZERO
+            && last >= last_period - 1
+            && consecutive
+            && steady
+            && coverage >= RECURRING_COVERAGE
+        {
+            bills.entry(category_id).or_default().push(RecurringBill {
+                description: payments.description,
+                amount: latest,
+                months_seen: by_period.len() as u32,
+                last_date: payments.last_date,
+            });
+        }
+    }
+    for category_bills in bills.values_mut() {
+        category_bills.sort_by(|a, b| {
+            b.amount
+                .cmp(&a.amount)
+                .then(a.description.cmp(&b.description))
+        });
+    }
+    Ok(bills)
+}
+
+/// Proposes a budget for `year` for the active expense and income categories, from the
+/// `history_months` full months before the year, or before the current month when the
+/// year is yet to come. Categories without any activity in the last two years and the
+/// history are left out. Nothing is saved.
+pub(crate) fn forecast_budget(
+    conn: &Connection,
+    year: i64,
+    method: ForecastMethod,
+    history_months: u32,
+    today: NaiveDate,
+) -> Result<BudgetForecast, String> {
+    if !(3..=36).contains(&history_months) {
+        return Err(format!(
+            "The history must cover 3 to 36 months, not {}",
+            history_months
+        ));
+    }
+    let last = period(year, 1).min(period(i64::from(today.year()), i64::from(today.month()))) - 1;
+    let first = last - i64::from(history_months) + 1;
+    // Seasonality is told from the last two years
+    let read_from = first.min(last - 23);
+    let date_to = parse_date(&period_start(last + 1))?
+        .pred_opt()
+        .ok_or_else(|| format!("Invalid year: {}", year))?
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, COALESCE(color, '#9ca3af'), type FROM categories
+             WHERE type IN ('expense', 'income') AND is_active = 1 AND is_inputable = 1
+             ORDER BY type = 'income', sort_order, name",
+        )
+        .map_err(|e| format!("Cannot read categories: {}", e))?;
+    let categories = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read categories: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read categories: {}", e))?;
+    // Budgets are positive, expenses negative
+    let signs: HashMap<i64, i64> = categories
+        .iter()
+        .map(|(id, _, _, category_type)| (*id, if category_type == "expense" { -1 } else { 1 }))
+        .collect();
+
+    let actuals = read_actuals(conn, &period_start(read_from), &date_to)?;
+    let current = read_amounts(
+        conn,
+        "SELECT category_id, year, month, amount FROM budget_entries WHERE year = ?1",
+        &[&year],
+        "budget entries",
+    )?;
+    let mut bills = detect_recurring_bills(conn, &period_start(first), &date_to, last, &signs)?;
+
+    let mut lines = Vec::new();
+    for (category_id, category_name, category_color, category_type) in categories {
+        let sign = signs[&category_id];
+        let value = |p: i64| {
+            let actual = actuals.get(&(category_id, p)).copied().unwrap_or_default();
+            if sign < 0 {
+                -actual
+            } else {
+                actual
+            }
+        };
+        let read = (read_from..=last).map(value).collect::<Vec<_>>();
+        if read.iter().all(|v| v.is_zero()) {
+            continue;
+        }
+        let history = &read[(first - read_from) as usize..];
+        let last_year = &read[read.len() - 12..];
+        let average = average(history);
+        let median = median(history);
+        let active_months = history.iter().filter(|v| !v.is_zero()).count() as u32;
+
+        // Two full years of activity are needed to tell seasonal spending
+        let started = read.iter().position(|v| !v.is_zero()).unwrap_or(read.len());
+        let seasonal = started + 24 <= read.len()
+            && is_seasonal(&read[read.len() - 24..read.len() - 12], last_year);
+
+        let recurring = bills.remove(&category_id).unwrap_or_default();
+        let recurring_total: Money = recurring.iter().map(|b| b.amount).sum();
+
+        let method = match method {
+            ForecastMethod::Auto => {
+                if !recurring.is_empty()
+                    && recurring_total.to_decimal() >= average.to_decimal() * RECURRING_SHARE
+                {
+                    ForecastMethod::Recurring
+                } else if seasonal {
+                    ForecastMethod::SameMonthLastYear
+                } else if (average - median).abs().to_decimal()
+                    > average.abs().to_decimal() * SKEWED_AVERAGE
+                {
+                    ForecastMethod::Median
+                } else {
+                    ForecastMethod::Average
+                }
+            }
+            ForecastMethod::Recurring if recurring.is_empty() => ForecastMethod::Average,
+            method => method,
+        };
+        let months = (1..=12)
+            .map(|month| match method {
+                ForecastMethod::Median => budget_amount(median),
+                ForecastMethod::SameMonthLastYear => {
+                    // The latest occurrence of the month, a year before the budget or earlier
+                    let mut p = period(year - 1, month);
+                    while p > last {
+                        p -= 12;
+                    }
+                    budget_amount(value(p))
+                }
+                ForecastMethod::Recurring => budget_amount(recurring_total),
+                _ => budget_amount(average),
+            })
+            .collect();
+
+        lines.push(ForecastLine {
+            category_id,
+            category_name,
+            category_color,
+            category_type,
+            method,
+            months,
+            current: (1..=12)
+                .map(|month| {
+                    current
+                        .get(&(category_id, period(year, month)))
+                        .copied()
+                        .unwrap_or_default()
+                })
+                .collect(),
+            average,
+            median,
+            active_months,
+            seasonal,
+            recurring,
+        });
+    }
+
+    let month_of = |p: i64| format!("{:04}-{:02}", p.div_euclid(12), p.rem_euclid(12) + 1);
+    Ok(BudgetForecast {
+        year,
+        history_from: month_of(first),
+        history_to: month_of(last),
+        lines,
+    })
+}
+
+/// Envelopes of the active profile's expense categories, month by month for `year`.
+#[tauri::command]
+pub fn get_budget_envelopes(app: tauri::AppHandle, year: i64) -> Result<Vec<Envelope>, String> {
+    let conn = open_active_profile_db(&app)?;
+    compute_envelopes(&conn, year)
+}
+
+/// Moves `amount` from one envelope to another for a month, recorded in the transfer
+/// history.
+#[tauri::command]
+pub fn move_budget_money(
+    app: tauri::AppHandle,
+    year: i64,
+    month: i64,
+    from_category_id: i64,
+    to_category_id: i64,
+    amount: Money,
+    notes: Option<String>,
+) -> Result<BudgetTransfer, String> {
+    let mut conn = open_active_profile_db(&app)?;
+    move_money(
+        &mut conn,
+        NewTransfer {
+            year,
+            month,
+            from_category_id,
+            to_category_id,
+            amount,
+            notes,
+            reversed_transfer_id: None,
+        },
+    )
+}
+
+/// Transfers made for months of `year`, newest first.
+#[tauri::command]
+pub fn get_budget_transfers(
+    app: tauri::AppHandle,
+    year: i64,
+) -> Result<Vec<BudgetTransfer>, String> {
+    let conn = open_active_profile_db(&app)?;
+    read_transfers(&conn, "t.year = ?1", &[&year])
+}
+
+/// Moves the money of a transfer back with a new, linked transfer.
+#[tauri::command]
+pub fn reverse_budget_transfer(app: tauri::AppHandle, id: i64) -> Result<BudgetTransfer, String> {
+    let mut conn = open_active_profile_db(&app)?;
+    reverse_transfer(&mut conn, id)
+}
+
+/// Whether what is left in a category's envelope at the end of a month carries over.
+#[tauri::command]
+pub fn set_budget_rollover(
+    app: tauri::AppHandle,
+    category_id: i64,
+    enabled: bool,
+) -> Result<(), String> {
+    let conn = open_active_profile_db(&app)?;
+    check_envelope_category(&conn, category_id)?;
+    conn.execute(
+        "UPDATE categories SET budget_rollover = ?1 WHERE id = ?2",
+        params![enabled, category_id],
+    )
+    .map_err(|e| format!("Cannot save budget rollover: {}", e))?;
+    Ok(())
+}
+
+/// Budget proposed for `year` from the active profile's past transactions, to review
+/// before applying it; nothing is saved.
+#[tauri::command]
+pub fn propose_budget(
+    app: tauri::AppHandle,
+    year: i64,
+    method: ForecastMethod,
+    history_months: u32,
+) -> Result<BudgetForecast, String> {
+    let conn = open_active_profile_db(&app)?;
+    forecast_budget(
+        &conn,
+        year,
+        method,
+        history_months,
+        Local::now().date_naive(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::migrated_test_db;
+
+    // Seeded expense categories: 20 Loyer, 21 Électricité, 22 Épicerie, and 31 Assurances,
+    // a parent without an envelope; 10 Paie is an income category
+    const RENT: i64 = 20;
+    const POWER: i64 = 21;
+    const GROCERIES: i64 = 22;
+
+    fn money(value: f64) -> Money {
+        Money::from_decimal(value).unwrap()
+    }
+
+    fn budget(conn: &Connection, category_id: i64, year: i64, month: i64, amount: f64) {
+        conn.execute(
+            "INSERT INTO budget_entries (category_id, year, month, amount)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![category_id, year, month, money(amount)],
+        )
+        .unwrap();
+    }
+
+    fn spend(conn: &Connection, category_id: i64, date: &str, amount: f64) {
+        conn.execute(
+            "INSERT INTO transactions (date, description, amount, category_id)
+             VALUES (?1, 'fixture', ?2, ?3)",
+            params![date, money(-amount), category_id],
+        )
+        .unwrap();
+    }
+
+    fn transfer(from: i64, to: i64, month: i64, amount: f64) -> NewTransfer {
+        NewTransfer {
+            year: 2025,
+            month,
+            from_category_id: from,
+            to_category_id: to,
+            amount: money(amount),
+            notes: None,
+            reversed_transfer_id: None,
+        }
+    }
+
+    /// Available amount of an envelope for each month of 2025.
+    fn available(conn: &Connection, category_id: i64) -> Vec<Money> {
+        compute_envelopes(conn, 2025)
+            .unwrap()
+            .into_iter()
+            .find(|e| e.category_id == category_id)
+            .map(|e| e.months.iter().map(|m| m.available).collect())
+            .unwrap_or_else(|| vec![Money::ZERO; 12])
+    }
+
+    #[test]
+    fn rolling_envelopes_carry_what_is_left_or_overspent() {
+        let conn = migrated_test_db();
+        conn.execute(
+            "UPDATE categories SET budget_rollover = 1 WHERE id = ?1",
+            [GROCERIES],
+        )
+        .unwrap();
+        // Funded the year before, so December's surplus carries into January
+        budget(&conn, GROCERIES, 2024, 12, 50.0);
+        budget(&conn, GROCERIES, 2025, 1, 100.0);
+        budget(&conn, GROCERIES, 2025, 2, 100.0);
+        spend(&conn, GROCERIES, "2025-01-10", 30.0);
+        spend(&conn, GROCERIES, "2025-02-10", 250.0);
+        budget(&conn, POWER, 2025, 1, 100.0);
+        spend(&conn, POWER, "2025-01-10", 30.0);
+
+        let envelopes = compute_envelopes(&conn, 2025).unwrap();
+        let groceries = envelopes
+            .iter()
+            .find(|e| e.category_id == GROCERIES)
+            .unwrap();
+        assert!(groceries.rollover);
+        assert_eq!(groceries.months[0].carried_in, money(50.0));
+        assert_eq!(groceries.months[0].available, money(120.0));
+        assert_eq!(groceries.months[1].carried_in, money(120.0));
+        assert_eq!(groceries.months[1].spent, money(250.0));
+        assert_eq!(groceries.months[1].available, money(-30.0));
+        assert_eq!(groceries.months[11].available, money(-30.0));
+        // Without rollover every month starts from zero
+        assert_eq!(available(&conn, POWER)[..2], [money(70.0), Money::ZERO]);
+    }
+
+    #[test]
+    fn transfers_move_available_money_between_envelopes() {
+        let mut conn = migrated_test_db();
+        budget(&conn, RENT, 2025, 1, 100.0);
+
+        let saved = move_money(&mut conn, transfer(RENT, POWER, 1, 40.0)).unwrap();
+        assert_eq!(saved.from_category_name, "Loyer");
+        assert_eq!(available(&conn, RENT)[0], money(60.0));
+        assert_eq!(available(&conn, POWER)[0], money(40.0));
+
+        let err = move_money(&mut conn, transfer(RENT, POWER, 1, 60.01)).unwrap_err();
+        assert_eq!(err, "Only 60.00 available in Loyer for 2025-01");
+        assert!(move_money(&mut conn, transfer(RENT, RENT, 1, 1.0)).is_err());
+        assert!(move_money(&mut conn, transfer(RENT, 10, 1, 1.0)).is_err());
+        assert!(move_money(&mut conn, transfer(RENT, 31, 1, 1.0)).is_err());
+        assert!(move_money(&mut conn, transfer(RENT, POWER, 13, 1.0)).is_err());
+        assert!(move_money(&mut conn, transfer(RENT, POWER, 1, 0.0)).is_err());
+        assert_eq!(available(&conn, RENT)[0], money(60.0));
+    }
+
+    #[test]
+    fn undoing_a_transfer_is_allowed_after_the_money_was_spent() {
+        let mut conn = migrated_test_db();
+        budget(&conn, RENT, 2025, 1, 100.0);
+        spend(&conn, POWER, "2025-01-10", 20.0);
+        let moved = move_money(&mut conn, transfer(RENT, POWER, 1, 50.0)).unwrap();
+        spend(&conn, POWER, "2025-01-20", 40.0);
+        assert_eq!(available(&conn, POWER)[0], money(-10.0));
+
+        let undo = reverse_transfer(&mut conn, moved.id).unwrap();
+        assert_eq!(undo.reversed_transfer_id, Some(moved.id));
+        assert_eq!((undo.from_category_id, undo.to_category_id), (POWER, RENT));
+        assert_eq!(available(&conn, POWER)[0], money(-60.0));
+        assert_eq!(available(&conn, RENT)[0], money(100.0));
+
+        let history = read_transfers(&conn, "1 = 1", &[]).unwrap();
+        assert!(history.iter().any(|t| t.id == moved.id && t.is_reversed));
+        assert!(reverse_transfer(&mut conn, moved.id).is_err());
+        assert!(reverse_transfer(&mut conn, undo.id).is_err());
+    }
+
+    fn pay(conn: &Connection, category_id: i64, date: &str, description: &str, amount: f64) {
+        conn.execute(
+            "INSERT INTO transactions (date, description, amount, category_id)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![date, description, money(-amount), category_id],
+        )
+        .unwrap();
+    }
+
+    /// `YYYY-MM-15` of the `index`th month from June 2023.
+    fn month_date(index: usize) -> String {
+        let p = period(2023, 6) + index as i64;
+        format!("{:04}-{:02}-15", p.div_euclid(12), p.rem_euclid(12) + 1)
+    }
+
+    #[test]
+    fn budget_amounts_are_whole_dollars_and_never_negative() {
+        assert_eq!(budget_amount(money(12.49)), money(12.0));
+        assert_eq!(budget_amount(money(12.5)), money(13.0));
+        assert_eq!(budget_amount(money(0.49)), Money::ZERO);
+        assert_eq!(budget_amount(money(-40.0)), Money::ZERO);
+        let values = [money(10.0), money(40.0), money(20.0), money(30.0)];
+        assert_eq!(average(&values), money(25.0));
+        assert_eq!(median(&values), money(25.0));
+        assert_eq!(median(&values[..3]), money(20.0));
+        assert_eq!(average(&[]), Money::ZERO);
+    }
+
+    #[test]
+    fn recurring_bills_are_steady_consecutive_and_current() {
+        let conn = migrated_test_db();
+        // Reference numbers and case differ from one month to the next
+        for (date, description, amount) in [
+            ("2025-01-03", "NETFLIX 1234", 16.0),
+            ("2025-02-03", "Netflix  5678", 17.0),
+            ("2025-03-03", "NETFLIX 9012", 16.5),
+            ("2025-04-03", "NETFLIX 3456", 16.5),
+        ] {
+            pay(&conn, 27, date, description, amount);
+        }
+        for (date, amount) in [
+            ("2025-02-05", 30.0),
+            ("2025-03-05", 80.0),
+            ("2025-04-05", 30.0),
+        ] {
+            pay(&conn, 27, date, "SPOTIFY", amount);
+        }
+        // Stopped before the last months of the history
+        for date in ["2024-10-07", "2024-11-07", "2024-12-07"] {
+            pay(&conn, 27, date, "DEEZER", 12.0);
+        }
+        let signs = HashMap::from([(27, -1)]);
+
+        let bills =
+            detect_recurring_bills(&conn, "2024-05-01", "2025-04-30", period(2025, 4), &signs)
+                .unwrap();
+
+        let music = &bills[&27];
+        assert_eq!(music.len(), 1, "{:?}", music);
+        assert_eq!(music[0].description, "NETFLIX 3456");
+        assert_eq!(music[0].amount, money(16.5));
+        assert_eq!(music[0].months_seen, 4);
+        assert_eq!(music[0].last_date, "2025-04-03");
+    }
+
+    #[test]
+    fn auto_forecast_picks_a_method_per_category() {
+        let conn = migrated_test_db();
+        let letters = "ABCDEFGHIJKLMNOPQRSTUVWX";
+        for index in 0..24 {
+            let date = month_date(index);
+            let month = index % 12;
+            // Électricité: high in winter (December to February), both years
+            let power = if (6..=8).contains(&month) {
+                300.0
+            } else {
+                50.0
+            };
+            pay(
+                &conn,
+                POWER,
+                &date,
+                &format!("HYDRO {}", &letters[index..=index]),
+                power,
+            );
+            if index < 12 {
+                continue;
+            }
+            pay(&conn, RENT, &date, "LOYER", 1000.0);
+            let groceries = if index % 2 == 0 { 200.0 } else { 220.0 };
+            pay(
+                &conn,
+                GROCERIES,
+                &date,
+                &format!("MARCHE {}", &letters[index..=index]),
+                groceries,
+            );
+            // Restaurant: one unusual month skews the average
+            let restaurant = if index == 18 { 600.0 } else { 50.0 };
+            pay(
+                &conn,
+                24,
+                &date,
+                &format!("RESTO {}", &letters[index..=index]),
+                restaurant,
+            );
+        }
+        let today = NaiveDate::from_ymd_opt(2025, 6, 15).unwrap();
+
+        let forecast = forecast_budget(&conn, 2026, ForecastMethod::Auto, 12, today).unwrap();
+
+        assert_eq!(
+            (forecast.history_from.as_str(), forecast.history_to.as_str()),
+            ("2024-06", "2025-05")
+        );
+        let line = |id: i64| forecast.lines.iter().find(|l| l.category_id == id).unwrap();
+        assert_eq!(line(RENT).method, ForecastMethod::Recurring);
+        assert_eq!(line(RENT).months, vec![money(1000.0); 12]);
+        assert_eq!(line(RENT).recurring[0].months_seen, 12);
+        assert_eq!(line(GROCERIES).method, ForecastMethod::Average);
+        assert_eq!(line(GROCERIES).months, vec![money(210.0); 12]);
+        assert_eq!(line(24).method, ForecastMethod::Median);
+        assert_eq!(line(24).average, Money::from_cents(9583));
+        assert_eq!(line(24).months, vec![money(50.0); 12]);
+        let power = line(POWER);
+        assert!(power.seasonal);
+        assert_eq!(power.method, ForecastMethod::SameMonthLastYear);
+        assert_eq!(power.months[0], money(300.0));
+        assert_eq!(power.months[2], money(50.0));
+        assert_eq!(power.months[11], money(300.0));
+
+        // A chosen method applies to every category; Recurring falls back to the average
+        let forecast = forecast_budget(&conn, 2026, ForecastMethod::Recurring, 12, today).unwrap();
+        let restaurant = forecast.lines.iter().find(|l| l.category_id == 24).unwrap();
+        assert_eq!(restaurant.method, ForecastMethod::Average);
+        assert_eq!(restaurant.months[0], money(96.0));
+        assert!(forecast_budget(&conn, 2026, ForecastMethod::Auto, 2, today).is_err());
+    }
+}
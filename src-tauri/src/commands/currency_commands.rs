@@ -0,0 +1,189 @@
+use rusqlite::params;
+use serde::Serialize;
+
+use super::profile_commands::open_active_profile_db;
+use crate::currency::{self, ConversionSummary};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RateImportReport {
+    /// Rates read from the file; existing ones for the same pair and date are replaced.
+    pub rate_count: usize,
+    pub currencies: Vec<String>,
+    pub date_from: String,
+    pub date_to: String,
+    pub conversion: ConversionSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RateCoverage {
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate_count: i64,
+    pub first_date: String,
+    pub last_date: String,
+}
+
+/// Imports a rates CSV saved locally (e.g. the ECB `eurofxref-hist.csv`), whose rates
+/// are quoted for `reference` (EUR by default), then converts every transaction again.
+#[tauri::command]
+pub fn import_exchange_rates(
+    app: tauri::AppHandle,
+    file_path: String,
+    reference: Option<String>,
+) -> Result<RateImportReport, String> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Cannot read rates file: {}", e))?;
+    let reference = reference.unwrap_or_else(|| "EUR".to_string());
+    let rates = currency::parse_rates_csv(&content, &reference)?;
+    let file_name = std::path::Path::new(&file_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string());
+
+    let mut conn = open_active_profile_db(&app)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    {
+        let mut insert = tx
+            .prepare(
+                "INSERT INTO exchange_rates (from_currency, to_currency, rate_date, rate, source)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(from_currency, to_currency, rate_date) DO UPDATE SET
+                   rate = excluded.rate, source = excluded.source",
+            )
+            .map_err(|e| format!("Cannot save exchange rates: {}", e))?;
+        for rate in &rates {
+            insert
+                .execute(params![
+                    rate.from_currency,
+                    rate.to_currency,
+                    rate.rate_date,
+                    rate.rate,
+                    file_name
+                ])
+                .map_err(|e| format!("Cannot save exchange rates: {}", e))?;
+        }
+    }
+    let conversion = currency::refresh_base_amounts(&tx, false)?;
+    tx.commit()
+        .map_err(|e| format!("Cannot save exchange rates: {}", e))?;
+
+    let mut currencies: Vec<String> = rates.iter().map(|r| r.to_currency.clone()).collect();
+    currencies.sort();
+    currencies.dedup();
+    Ok(RateImportReport {
+        rate_count: rates.len(),
+        currencies,
+        date_from: rates
+            .iter()
+            .map(|r| r.rate_date.clone())
+            .min()
+            .unwrap_or_default(),
+        date_to: rates
+            .iter()
+            .map(|r| r.rate_date.clone())
+            .max()
+            .unwrap_or_default(),
+        conversion,
+    })
+}
+
+/// Stored rates per currency pair.
+#[tauri::command]
+pub fn get_exchange_rate_coverage(app: tauri::AppHandle) -> Result<Vec<RateCoverage>, String> {
+    let conn = open_active_profile_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT from_currency, to_currency, COUNT(*), MIN(rate_date), MAX(rate_date)
+             FROM exchange_rates
+             GROUP BY from_currency, to_currency
+             ORDER BY from_currency, to_currency",
+        )
+        .map_err(|e| format!("Cannot read exchange rates: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(RateCoverage {
+                from_currency: row.get(0)?,
+                to_currency: row.get(1)?,
+                rate_count: row.get(2)?,
+                first_date: row.get(3)?,
+                last_date: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("Cannot read exchange rates: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read exchange rates: {}", e));
+    rows
+}
+
+#[tauri::command]
+pub fn delete_exchange_rates(
+    app: tauri::AppHandle,
+    from_currency: String,
+    to_currency: String,
+) -> Result<ConversionSummary, String> {
+    let conn = open_active_profile_db(&app)?;
+    conn.execute(
+        "DELETE FROM exchange_rates WHERE from_currency = ?1 AND to_currency = ?2",
+        params![from_currency, to_currency],
+    )
+    .map_err(|e| format!("Cannot delete exchange rates: {}", e))?;
+    currency::refresh_base_amounts(&conn, false)
+}
+
+#[tauri::command]
+pub fn get_base_currency(app: tauri::AppHandle) -> Result<String, String> {
+    let conn = open_active_profile_db(&app)?;
+    currency::base_currency(&conn)
+}
+
+/// Changes the currency reports and budgets aggregate in, and converts every
+/// transaction to it.
+#[tauri::command]
+pub fn set_base_currency(
+    app: tauri::AppHandle,
+    currency: String,
+) -> Result<ConversionSummary, String> {
+    currency::validate_currency(&currency)?;
+    let conn = open_active_profile_db(&app)?;
+    conn.execute(
+        "INSERT INTO user_preferences (key, value, updated_at)
+         VALUES ('currency', ?1, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = ?1, updated_at = CURRENT_TIMESTAMP",
+        [&currency],
+    )
+    .map_err(|e| format!("Cannot save base currency: {}", e))?;
+    currency::refresh_base_amounts(&conn, false)
+}
+
+/// Sets the currency of one transaction; `None` makes it follow its account again.
+#[tauri::command]
+pub fn set_transaction_currency(
+    app: tauri::AppHandle,
+    transaction_id: i64,
+    currency: Option<String>,
+) -> Result<ConversionSummary, String> {
+    if let Some(code) = &currency {
+        currency::validate_currency(code)?;
+    }
+    let conn = open_active_profile_db(&app)?;
+    // Split parts share their parent's currency
+    conn.execute(
+        "UPDATE transactions SET currency = ?1, base_amount = NULL
+         WHERE id = ?2 OR parent_transaction_id = ?2",
+        params![currency, transaction_id],
+    )
+    .map_err(|e| format!("Cannot save transaction currency: {}", e))?;
+    currency::refresh_base_amounts(&conn, true)
+}
+
+/// Converts transactions to the base currency: all of them, or with `only_missing`,
+/// those without a base amount yet (after an import or a split).
+#[tauri::command]
+pub fn refresh_base_amounts(
+    app: tauri::AppHandle,
+    only_missing: bool,
+) -> Result<ConversionSummary, String> {
+    let conn = open_active_profile_db(&app)?;
+    currency::refresh_base_amounts(&conn, only_missing)
+}
@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+use super::profile_commands::read_profiles_config;
+use super::snapshot_commands::{snapshot_db, REASON_PRE_DATE_REPAIR};
+use crate::database::{self, date_audit};
+use crate::dates::{self, DateFormat};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportDates {
+    /// ISO date of each value, or `None` when it is not a valid date in the format.
+    pub dates: Vec<Option<String>>,
+    /// The format with day and month swapped, when it reads every value too, as other
+    /// dates: the file alone cannot tell which of the two it uses.
+    pub ambiguous_with: Option<String>,
+}
+
+/// Reads the date column of an imported file with `format`, in strftime (`%d/%m/%Y`) or
+/// token (`DD/MM/YYYY`) notation.
+#[tauri::command]
+pub fn parse_import_dates(values: Vec<String>, format: String) -> Result<ImportDates, String> {
+    let date_format = DateFormat::parse(&format)?;
+    let dates = values
+        .iter()
+        .map(|v| dates::to_iso(v, &date_format).ok())
+        .collect();
+    let parsed: Vec<&str> = values
+        .iter()
+        .map(String::as_str)
+        .filter(|v| date_format.parse_value(v).is_ok())
+        .collect();
+    let ambiguous_with = date_format
+        .swapped()
+        .filter(|_| dates::is_ambiguous(&parsed, &date_format))
+        .map(|swapped| {
+            let separator = format.chars().find(|c| matches!(c, '/' | '-' | '.'));
+            swapped.notation(separator.unwrap_or('/'))
+        });
+    Ok(ImportDates {
+        dates,
+        ambiguous_with,
+    })
+}
+
+/// Finds the active profile's dates not stored as `YYYY-MM-DD` and, unless `dry_run`,
+/// rewrites them in that form after taking a snapshot.
+#[tauri::command]
+pub fn audit_profile_dates(
+    app: tauri::AppHandle,
+    dry_run: bool,
+) -> Result<date_audit::DateAuditReport, String> {
+    let config = read_profiles_config(&app)?;
+    let profile = config
+        .profiles
+        .iter()
+        .find(|p| p.id == config.active_profile_id)
+        .ok_or_else(|| "No active profile".to_string())?;
+    if !dry_run {
+        snapshot_db(&app, &profile.db_filename, REASON_PRE_DATE_REPAIR)
+            .map_err(|e| format!("Cannot snapshot the database before repairing dates: {}", e))?;
+    }
+    let mut conn = database::open_profile_db(&app, &profile.db_filename)?;
+    date_audit::audit_dates(&mut conn, dry_run)
+}
@@ -0,0 +1,315 @@
+use rand::RngCore;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::export_import_commands::derive_key;
+use super::profile_commands::{hex_encode, read_profiles_config, write_profiles_config, Profile};
+use crate::database::{self, encryption};
+
+const KEY_SALT_LEN: usize = 16;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileDbEncryption {
+    pub encrypted: bool,
+    pub unlocked: bool,
+}
+
+fn find_profile(app: &tauri::AppHandle, profile_id: &str) -> Result<Profile, String> {
+    read_profiles_config(app)?
+        .profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile not found: {}", profile_id))
+}
+
+fn set_profile_salt(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    salt: Option<String>,
+) -> Result<(), String> {
+    let mut config = read_profiles_config(app)?;
+    for profile in config.profiles.iter_mut() {
+        if profile.id == profile_id {
+            profile.db_key_salt = salt.clone();
+        }
+    }
+    write_profiles_config(app, &config)
+}
+
+fn hex_decode(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err("Invalid key salt".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "Invalid key salt".to_string()))
+        .collect()
+}
+
+fn profile_key(profile: &Profile, password: &str) -> Result<String, String> {
+    let salt = profile
+        .db_key_salt
+        .as_deref()
+        .ok_or_else(|| "This profile database is not encrypted".to_string())?;
+    let key = derive_key(password, &hex_decode(salt)?)?;
+    Ok(encryption::raw_key(&key))
+}
+
+fn new_key(password: &str) -> Result<(String, String), String> {
+    if password.is_empty() {
+        return Err("A password is required".to_string());
+    }
+    let mut salt = [0u8; KEY_SALT_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    let key = derive_key(password, &salt)?;
+    Ok((hex_encode(&salt), encryption::raw_key(&key)))
+}
+
+/// Rewrites a profile database through `sqlcipher_export` with `target_key` (empty for
+/// plaintext) into a temporary file next to it, checked to open with the new key, and
+/// returns its path. The original database is not touched.
+fn rewrite_db(
+    app: &tauri::AppHandle,
+    profile: &Profile,
+    source: rusqlite::Connection,
+    target_key: &str,
+) -> Result<PathBuf, String> {
+    let tmp_path = database::get_db_dir(app)?.join(format!("{}.rekey-tmp", profile.db_filename));
+
+    source
+        .execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+        .map_err(|e| format!("Cannot checkpoint database: {}", e))?;
+    let result = encryption::export_with_key(&source, &tmp_path, target_key);
+    drop(source);
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    // Make sure the rewritten copy opens with the new key before replacing anything
+    let check = if target_key.is_empty() {
+        database::open_db(&tmp_path).and_then(|c| database::check_integrity(&c))
+    } else {
+        encryption::open_with_key(&tmp_path, target_key).and_then(|c| database::check_integrity(&c))
+    };
+    if let Err(e) = check {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    Ok(tmp_path)
+}
+
+/// Puts the rewritten database at `tmp_path` in place of `db_path`. The key salt of the
+/// new file is saved first: if profiles.json cannot be written, the original database is
+/// kept, and if the swap fails the previous salt is put back, so the saved salt always
+/// matches the file on disk.
+fn swap_rewritten_db(
+    db_path: &Path,
+    tmp_path: &Path,
+    save_new_salt: impl FnOnce() -> Result<(), String>,
+    restore_old_salt: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    if let Err(e) = save_new_salt() {
+        let _ = fs::remove_file(tmp_path);
+        return Err(e);
+    }
+    let swapped = database::remove_sidecar_files(db_path).and_then(|_| {
+        fs::rename(tmp_path, db_path).map_err(|e| format!("Cannot replace database: {}", e))
+    });
+    if let Err(e) = swapped {
+        let _ = fs::remove_file(tmp_path);
+        return Err(match restore_old_salt() {
+            Ok(()) => e,
+            Err(restore) => format!(
+                "{} (and the previous key salt could not be restored: {})",
+                e, restore
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Rewrites the database of `profile` with `target_key` and records `salt`, the salt of
+/// that key (`None` for plaintext), in profiles.json.
+fn rekey_profile_db(
+    app: &tauri::AppHandle,
+    profile: &Profile,
+    source: rusqlite::Connection,
+    target_key: &str,
+    salt: Option<String>,
+) -> Result<(), String> {
+    let db_path = database::get_db_path(app, &profile.db_filename)?;
+    let tmp_path = rewrite_db(app, profile, source, target_key)?;
+    swap_rewritten_db(
+        &db_path,
+        &tmp_path,
+        || set_profile_salt(app, &profile.id, salt),
+        || set_profile_salt(app, &profile.id, profile.db_key_salt.clone()),
+    )
+}
+
+/// Unlocks an encrypted profile so that its database can be opened by the frontend.
+#[tauri::command]
+pub fn unlock_profile_db(
+    app: tauri::AppHandle,
+    profile_id: String,
+    password: String,
+) -> Result<(), String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let key = profile_key(&profile, &password)?;
+    let db_path = database::get_db_path(&app, &profile.db_filename)?;
+    encryption::forget_key(&profile.db_filename);
+    encryption::open_with_key(&db_path, &key)?;
+    encryption::register_key(&profile.db_filename, key);
+    Ok(())
+}
+
+/// Forgets the key of a profile. The frontend must close its connection first.
+#[tauri::command]
+pub fn lock_profile_db(app: tauri::AppHandle, profile_id: String) -> Result<(), String> {
+    let profile = find_profile(&app, &profile_id)?;
+    encryption::forget_key(&profile.db_filename);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_profile_db_encryption(
+    app: tauri::AppHandle,
+    profile_id: String,
+) -> Result<ProfileDbEncryption, String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let encrypted = profile.db_key_salt.is_some();
+    Ok(ProfileDbEncryption {
+        encrypted,
+        unlocked: encrypted && encryption::is_unlocked(&profile.db_filename),
+    })
+}
+
+/// Encrypts an existing plaintext profile database in place. The frontend must close its
+/// connection to the database first.
+#[tauri::command]
+pub fn encrypt_profile_db(
+    app: tauri::AppHandle,
+    profile_id: String,
+    password: String,
+) -> Result<(), String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let db_path = database::get_db_path(&app, &profile.db_filename)?;
+    if profile.db_key_salt.is_some() || encryption::is_file_encrypted(&db_path)? {
+        return Err("This profile database is already encrypted".to_string());
+    }
+
+    let (salt, key) = new_key(&password)?;
+    let source = database::open_profile_db(&app, &profile.db_filename)?;
+    rekey_profile_db(&app, &profile, source, &key, Some(salt))?;
+    encryption::register_key(&profile.db_filename, key);
+    Ok(())
+}
+
+/// Decrypts a profile database in place. The frontend must close its connection first.
+#[tauri::command]
+pub fn decrypt_profile_db(
+    app: tauri::AppHandle,
+    profile_id: String,
+    password: String,
+) -> Result<(), String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let key = profile_key(&profile, &password)?;
+    let db_path = database::get_db_path(&app, &profile.db_filename)?;
+
+    let source = encryption::open_with_key(&db_path, &key)?;
+    rekey_profile_db(&app, &profile, source, "", None)?;
+    encryption::forget_key(&profile.db_filename);
+    Ok(())
+}
+
+/// Changes the password of an encrypted profile database by re-exporting it with the new
+/// key (`PRAGMA rekey` is not usable while the plugin keeps the database in WAL mode).
+#[tauri::command]
+pub fn change_profile_db_password(
+    app: tauri::AppHandle,
+    profile_id: String,
+    old_password: String,
+    new_password: String,
+) -> Result<(), String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let old_key = profile_key(&profile, &old_password)?;
+    let (salt, new_key) = new_key(&new_password)?;
+    let db_path = database::get_db_path(&app, &profile.db_filename)?;
+
+    let source = encryption::open_with_key(&db_path, &old_key)?;
+    rekey_profile_db(&app, &profile, source, &new_key, Some(salt))?;
+    encryption::register_key(&profile.db_filename, new_key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn files(name: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("rekey-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (db_path, tmp_path) = (dir.join("profile.db"), dir.join("profile.db.rekey-tmp"));
+        fs::write(&db_path, "old").unwrap();
+        fs::write(&tmp_path, "new").unwrap();
+        (db_path, tmp_path)
+    }
+
+    #[test]
+    fn swap_replaces_the_database_after_saving_the_salt() {
+        let (db_path, tmp_path) = files("ok");
+        let saved = Cell::new(false);
+        swap_rewritten_db(
+            &db_path,
+            &tmp_path,
+            || {
+                saved.set(true);
+                Ok(())
+            },
+            || panic!("nothing to restore"),
+        )
+        .unwrap();
+        assert!(saved.get());
+        assert_eq!(fs::read_to_string(&db_path).unwrap(), "new");
+        assert!(!tmp_path.exists());
+    }
+
+    #[test]
+    fn failed_config_write_keeps_the_original_database() {
+        let (db_path, tmp_path) = files("config");
+        let err = swap_rewritten_db(
+            &db_path,
+            &tmp_path,
+            || Err("Cannot write profiles.json: disk full".to_string()),
+            || panic!("the salt was never changed"),
+        )
+        .unwrap_err();
+        assert!(err.contains("profiles.json"));
+        assert_eq!(fs::read_to_string(&db_path).unwrap(), "old");
+        assert!(!tmp_path.exists());
+    }
+
+    #[test]
+    fn failed_swap_restores_the_previous_salt() {
+        let (db_path, tmp_path) = files("swap");
+        fs::remove_file(&tmp_path).unwrap();
+        let restored = Cell::new(false);
+        let err = swap_rewritten_db(
+            &db_path,
+            &tmp_path,
+            || Ok(()),
+            || {
+                restored.set(true);
+                Ok(())
+            },
+        )
+        .unwrap_err();
+        assert!(err.contains("Cannot replace database"));
+        assert!(restored.get());
+        assert_eq!(fs::read_to_string(&db_path).unwrap(), "old");
+    }
+}
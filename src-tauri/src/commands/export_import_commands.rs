@@ -1,17 +1,24 @@
 use aes_gcm::aead::{Aead, KeyInit, OsRng};
 use aes_gcm::{Aes256Gcm, Nonce};
+use age::secrecy::ExposeSecret;
 use argon2::Argon2;
 use rand::RngCore;
 use std::fs;
+use std::io::{BufReader, Read, Write};
+use std::str::FromStr;
+use tauri::Manager;
 use tauri_plugin_dialog::DialogExt;
 
 const MAGIC: &[u8; 4] = b"SREF";
+const AGE_MAGIC: &[u8] = b"age-encryption.org/v1\n";
+const AGE_ARMOR_MAGIC: &[u8] = b"-----BEGIN AGE ENCRYPTED FILE-----";
+const IDENTITY_FILENAME: &str = "export_identity.txt";
 const VERSION: u8 = 0x01;
 const SALT_LEN: usize = 16;
 const NONCE_LEN: usize = 12;
 const HEADER_LEN: usize = 4 + 1 + SALT_LEN + NONCE_LEN; // 33 bytes
 
-fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+pub(crate) fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
     let params = argon2::Params::new(65536, 3, 1, Some(32))
         .map_err(|e| format!("Argon2 params error: {}", e))?;
     let argon2 = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
@@ -73,6 +80,206 @@ fn decrypt_data(data: &[u8], password: &str) -> Result<Vec<u8>, String> {
         .map_err(|_| "Decryption failed — wrong password or corrupted file".to_string())
 }
 
+/// How an export file is protected, detected from its leading bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Plain,
+    Password,
+    Recipients,
+}
+
+impl ExportFormat {
+    fn detect(bytes: &[u8]) -> Self {
+        // SREF version 2 is an unencrypted profile backup container
+        if bytes.starts_with(MAGIC) && bytes.get(4) == Some(&VERSION) {
+            ExportFormat::Password
+        } else if bytes.starts_with(AGE_MAGIC) || bytes.starts_with(AGE_ARMOR_MAGIC) {
+            ExportFormat::Recipients
+        } else {
+            ExportFormat::Plain
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Plain => "plain",
+            ExportFormat::Password => "password",
+            ExportFormat::Recipients => "recipients",
+        }
+    }
+}
+
+fn get_identity_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Cannot get app data dir: {}", e))?;
+    Ok(app_dir.join(IDENTITY_FILENAME))
+}
+
+/// Loads the local X25519 identity, generating and persisting a new one on first use.
+fn load_or_create_identity(app: &tauri::AppHandle) -> Result<age::x25519::Identity, String> {
+    let path = get_identity_path(app)?;
+
+    if path.exists() {
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Cannot read identity file: {}", e))?;
+        let line = content
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .ok_or_else(|| "Identity file contains no key".to_string())?;
+        return age::x25519::Identity::from_str(line)
+            .map_err(|e| format!("Invalid identity file: {}", e));
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Cannot create app data dir: {}", e))?;
+    }
+
+    let identity = age::x25519::Identity::generate();
+    let content = format!(
+        "# created: {}\n# public key: {}\n{}\n",
+        super::profile_commands::chrono_now(),
+        identity.to_public(),
+        identity.to_string().expose_secret()
+    );
+    write_private_file(&path, content.as_bytes())?;
+    Ok(identity)
+}
+
+#[cfg(unix)]
+fn write_private_file(path: &std::path::Path, bytes: &[u8]) -> Result<(), String> {
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| format!("Cannot create identity file: {}", e))?;
+    file.write_all(bytes)
+        .map_err(|e| format!("Cannot write identity file: {}", e))
+}
+
+#[cfg(not(unix))]
+fn write_private_file(path: &std::path::Path, bytes: &[u8]) -> Result<(), String> {
+    fs::write(path, bytes).map_err(|e| format!("Cannot write identity file: {}", e))
+}
+
+/// Parses `age1...` public keys, naming the first invalid one.
+pub(crate) fn parse_recipients(
+    recipients: &[String],
+) -> Result<Vec<age::x25519::Recipient>, String> {
+    recipients
+        .iter()
+        .map(|r| {
+            age::x25519::Recipient::from_str(r.trim())
+                .map_err(|e| format!("Invalid recipient \"{}\": {}", r, e))
+        })
+        .collect()
+}
+
+fn encrypt_to_recipients(plaintext: &[u8], recipients: &[String]) -> Result<Vec<u8>, String> {
+    let parsed = parse_recipients(recipients)?;
+
+    let encryptor =
+        age::Encryptor::with_recipients(parsed.iter().map(|r| r as &dyn age::Recipient))
+            .map_err(|e| format!("Encryption error: {}", e))?;
+
+    let mut output = Vec::with_capacity(plaintext.len() + 256);
+    let mut writer = encryptor
+        .wrap_output(&mut output)
+        .map_err(|e| format!("Encryption error: {}", e))?;
+    writer
+        .write_all(plaintext)
+        .map_err(|e| format!("Encryption error: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Encryption error: {}", e))?;
+
+    Ok(output)
+}
+
+fn decrypt_with_identity_file(data: &[u8], identity_file: &str) -> Result<Vec<u8>, String> {
+    let identities = age::IdentityFile::from_buffer(BufReader::new(identity_file.as_bytes()))
+        .map_err(|e| format!("Invalid identity file: {}", e))?
+        .into_identities()
+        .map_err(|e| format!("Invalid identity file: {}", e))?;
+
+    let decryptor = age::Decryptor::new_buffered(age::armor::ArmoredReader::new(data))
+        .map_err(|e| format!("Not a valid age encrypted file: {}", e))?;
+    let mut reader = decryptor
+        .decrypt(identities.iter().map(|i| i.as_ref() as &dyn age::Identity))
+        .map_err(|_| "Decryption failed — this file was not encrypted for your key".to_string())?;
+
+    let mut plaintext = Vec::new();
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|e| format!("Decryption error: {}", e))?;
+    Ok(plaintext)
+}
+
+/// Encrypts `plaintext` to the given recipients, or with `password` when no recipient is
+/// given. Returns the plaintext unchanged when neither is provided.
+pub(crate) fn encrypt_payload(
+    plaintext: Vec<u8>,
+    password: Option<&str>,
+    recipients: &[String],
+) -> Result<Vec<u8>, String> {
+    if !recipients.is_empty() {
+        return encrypt_to_recipients(&plaintext, recipients);
+    }
+    match password {
+        Some(pw) if !pw.is_empty() => encrypt_data(&plaintext, pw),
+        _ => Ok(plaintext),
+    }
+}
+
+/// Decrypts `bytes` according to their detected format. Recipient-encrypted data is
+/// decrypted with `identity_path` if given, otherwise with the local identity.
+pub(crate) fn decrypt_payload(
+    app: &tauri::AppHandle,
+    bytes: Vec<u8>,
+    password: Option<String>,
+    identity_path: Option<String>,
+) -> Result<Vec<u8>, String> {
+    match ExportFormat::detect(&bytes) {
+        ExportFormat::Password => {
+            let pw = password
+                .filter(|p| !p.is_empty())
+                .ok_or_else(|| "This file is encrypted — a password is required".to_string())?;
+            decrypt_data(&bytes, &pw)
+        }
+        ExportFormat::Recipients => {
+            let identity_file = match identity_path.filter(|p| !p.is_empty()) {
+                Some(path) => fs::read_to_string(&path)
+                    .map_err(|e| format!("Cannot read identity file: {}", e))?,
+                None => {
+                    let identity = load_or_create_identity(app)?;
+                    identity.to_string().expose_secret().to_string()
+                }
+            };
+            decrypt_with_identity_file(&bytes, &identity_file)
+        }
+        ExportFormat::Plain => Ok(bytes),
+    }
+}
+
+/// Returns the local public key as a one-element recipient list, used when data must be
+/// encrypted without asking the user for a password.
+pub(crate) fn local_recipients(app: &tauri::AppHandle) -> Result<Vec<String>, String> {
+    let identity = load_or_create_identity(app)?;
+    Ok(vec![identity.to_public().to_string()])
+}
+
+/// Returns this installation's public key (`age1...`), to be shared with whoever sends us
+/// encrypted exports.
+#[tauri::command]
+pub fn get_export_public_key(app: tauri::AppHandle) -> Result<String, String> {
+    let identity = load_or_create_identity(&app)?;
+    Ok(identity.to_public().to_string())
+}
+
 #[tauri::command]
 pub async fn pick_save_file(
     app: tauri::AppHandle,
@@ -111,33 +318,44 @@ pub fn write_export_file(
     file_path: String,
     content: String,
     password: Option<String>,
+    recipients: Option<Vec<String>>,
 ) -> Result<(), String> {
-    let bytes = match password {
-        Some(ref pw) if !pw.is_empty() => encrypt_data(content.as_bytes(), pw)?,
-        _ => content.into_bytes(),
-    };
+    let recipients: Vec<String> = recipients
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| !r.trim().is_empty())
+        .collect();
+
+    let bytes = encrypt_payload(content.into_bytes(), password.as_deref(), &recipients)?;
 
     fs::write(&file_path, bytes).map_err(|e| format!("Failed to write file: {}", e))
 }
 
+/// Reads an export file, decrypting it when needed. Recipient-encrypted files are
+/// decrypted with `identity_path` if given, otherwise with the local identity.
 #[tauri::command]
-pub fn read_import_file(file_path: String, password: Option<String>) -> Result<String, String> {
+pub fn read_import_file(
+    app: tauri::AppHandle,
+    file_path: String,
+    password: Option<String>,
+    identity_path: Option<String>,
+) -> Result<String, String> {
     let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
-
-    let plaintext = if bytes.len() >= 4 && &bytes[0..4] == MAGIC {
-        let pw = password
-            .filter(|p| !p.is_empty())
-            .ok_or_else(|| "This file is encrypted — a password is required".to_string())?;
-        decrypt_data(&bytes, &pw)?
-    } else {
-        bytes
-    };
+    let plaintext = decrypt_payload(&app, bytes, password, identity_path)?;
 
     String::from_utf8(plaintext).map_err(|e| format!("File content is not valid UTF-8: {}", e))
 }
 
+/// Returns true when the file is password-encrypted (SREF) and a password must be asked.
 #[tauri::command]
 pub fn is_file_encrypted(file_path: String) -> Result<bool, String> {
     let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
-    Ok(bytes.len() >= 4 && &bytes[0..4] == MAGIC)
+    Ok(ExportFormat::detect(&bytes) == ExportFormat::Password)
+}
+
+/// Returns `"plain"`, `"password"` or `"recipients"` depending on how the file is protected.
+#[tauri::command]
+pub fn get_export_file_format(file_path: String) -> Result<String, String> {
+    let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(ExportFormat::detect(&bytes).as_str().to_string())
 }
@@ -0,0 +1,302 @@
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::profile_commands::{read_profiles_config, Profile};
+use crate::currency;
+use crate::database::{self, encryption};
+use crate::money::Money;
+
+/// SQLite attaches at most 10 databases per connection by default.
+const MAX_HOUSEHOLD_PROFILES: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileAmounts {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub income: Money,
+    pub expenses: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseholdTrendItem {
+    pub month: String,
+    pub income: Money,
+    pub expenses: Money,
+    pub profiles: Vec<ProfileAmounts>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileTotal {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub total: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseholdCategoryItem {
+    pub category_name: String,
+    pub category_color: String,
+    pub total: Money,
+    pub profiles: Vec<ProfileTotal>,
+}
+
+/// Read-only SQLite URI for a database file.
+fn readonly_uri(path: &Path) -> String {
+    let mut path = path.to_string_lossy().replace('\\', "/");
+    if !path.starts_with('/') {
+        // Windows drive paths are written `file:/C:/...`
+        path.insert(0, '/');
+    }
+    let escaped = path
+        .replace('%', "%25")
+        .replace('?', "%3f")
+        .replace('#', "%23");
+    format!("file:{}?mode=ro", escaped)
+}
+
+/// Declared type of a column of an attached profile's transactions, `None` when the
+/// column does not exist.
+fn column_type(conn: &Connection, schema: &str, column: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT type FROM pragma_table_info('transactions', ?1) WHERE name = ?2",
+        [schema, column],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("Cannot read the schema of {}: {}", schema, e))
+}
+
+/// SQL reading a transaction of an attached profile in cents of the profile's base
+/// currency: `t.base_amount`, else `t.amount`. A profile not opened since amounts are
+/// stored as cents (schema v12) still has decimal amounts, and one not opened since
+/// currencies were added has no `base_amount`.
+fn cents_expression(conn: &Connection, schema: &str) -> Result<String, String> {
+    let cents = |column: &str, column_type: &str| {
+        if column_type.eq_ignore_ascii_case("INTEGER") {
+            format!("t.{}", column)
+        } else {
+            format!("CAST(ROUND(t.{} * 100) AS INTEGER)", column)
+        }
+    };
+    let amount_type = column_type(conn, schema, "amount")?
+        .ok_or_else(|| format!("Cannot read the schema of {}: no amount column", schema))?;
+    let amount = cents("amount", &amount_type);
+    Ok(match column_type(conn, schema, "base_amount")? {
+        Some(base_type) => format!("COALESCE({}, {})", cents("base_amount", &base_type), amount),
+        None => amount,
+    })
+}
+
+/// Resolves the requested profiles and attaches their databases, read-only, to an
+/// in-memory connection as `p0`, `p1`... Also returns the expression of each profile's
+/// amounts in cents. Amounts are added up as they are, so the profiles must share one
+/// base currency.
+fn attach_profiles(
+    app: &tauri::AppHandle,
+    profile_ids: &[String],
+) -> Result<(Connection, Vec<Profile>, Vec<String>), String> {
+    if profile_ids.is_empty() {
+        return Err("Select at least one profile".to_string());
+    }
+    if profile_ids.len() > MAX_HOUSEHOLD_PROFILES {
+        return Err(format!(
+            "At most {} profiles can be combined",
+            MAX_HOUSEHOLD_PROFILES
+        ));
+    }
+    let config = read_profiles_config(app)?;
+    let mut profiles = Vec::new();
+    for id in profile_ids {
+        let profile = config
+            .profiles
+            .iter()
+            .find(|p| &p.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Profile not found: {}", id))?;
+        if profiles.iter().any(|p: &Profile| p.id == profile.id) {
+            continue;
+        }
+        profiles.push(profile);
+    }
+
+    let conn = Connection::open_in_memory_with_flags(
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_URI,
+    )
+    .map_err(|e| format!("Cannot open reporting database: {}", e))?;
+    for (index, profile) in profiles.iter().enumerate() {
+        let key = match encryption::key_for_db(&profile.db_filename) {
+            Some(key) => key,
+            None if profile.db_key_salt.is_some() => {
+                return Err(format!("Unlock the profile {} first", profile.name))
+            }
+            None => String::new(),
+        };
+        let path = database::get_db_path(app, &profile.db_filename)?;
+        if !path.exists() {
+            return Err(format!("Database file not found: {}", profile.db_filename));
+        }
+        conn.execute(
+            &format!("ATTACH DATABASE ?1 AS p{} KEY ?2", index),
+            [readonly_uri(&path), key],
+        )
+        .map_err(|e| format!("Cannot open the profile {}: {}", profile.name, e))?;
+    }
+    let currencies = (0..profiles.len())
+        .map(|index| currency::attached_base_currency(&conn, &format!("p{}", index)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(index) = currencies.iter().position(|c| *c != currencies[0]) {
+        return Err(format!(
+            "The profiles {} ({}) and {} ({}) use different currencies and cannot be combined",
+            profiles[0].name, currencies[0], profiles[index].name, currencies[index]
+        ));
+    }
+    let amounts = (0..profiles.len())
+        .map(|index| cents_expression(&conn, &format!("p{}", index)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((conn, profiles, amounts))
+}
+
+/// One SELECT per attached profile, joined with UNION ALL. `{p}` is replaced by the
+/// schema name, `{i}` by the profile index and `{amount}` by its amount in cents.
+fn union_all(template: &str, amounts: &[String]) -> String {
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(i, amount)| {
+            template
+                .replace("{p}", &format!("p{}", i))
+                .replace("{i}", &i.to_string())
+                .replace("{amount}", amount)
+        })
+        .collect::<Vec<_>>()
+        .join("\nUNION ALL\n")
+}
+
+/// Monthly income and expenses of several profiles combined, with each profile's share.
+#[tauri::command]
+pub fn get_household_monthly_trends(
+    app: tauri::AppHandle,
+    profile_ids: Vec<String>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<HouseholdTrendItem>, String> {
+    let (conn, profiles, amounts) = attach_profiles(&app, &profile_ids)?;
+    let sql = union_all(
+        "SELECT {i}, strftime('%Y-%m', t.date) AS month,
+                COALESCE(SUM(CASE WHEN t.amount > 0 THEN {amount} ELSE 0 END), 0),
+                ABS(COALESCE(SUM(CASE WHEN t.amount < 0 THEN {amount} ELSE 0 END), 0))
+         FROM {p}.transactions t
+         WHERE (?1 IS NULL OR t.date >= ?1) AND (?2 IS NULL OR t.date <= ?2)
+         GROUP BY month",
+        &amounts,
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Cannot build household report: {}", e))?;
+    let rows = stmt
+        .query_map([&date_from, &date_to], |row| {
+            Ok((
+                row.get::<_, usize>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Money>(2)?,
+                row.get::<_, Money>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot run household report: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot run household report: {}", e))?;
+
+    let mut months: Vec<HouseholdTrendItem> = Vec::new();
+    for (index, month, income, expenses) in rows {
+        let item = match months.iter_mut().position(|m| m.month == month) {
+            Some(pos) => &mut months[pos],
+            None => {
+                months.push(HouseholdTrendItem {
+                    month,
+                    income: Money::ZERO,
+                    expenses: Money::ZERO,
+                    profiles: Vec::new(),
+                });
+                months.last_mut().unwrap()
+            }
+        };
+        item.income += income;
+        item.expenses += expenses;
+        item.profiles.push(ProfileAmounts {
+            profile_id: profiles[index].id.clone(),
+            profile_name: profiles[index].name.clone(),
+            income,
+            expenses,
+        });
+    }
+    months.sort_by(|a, b| a.month.cmp(&b.month));
+    Ok(months)
+}
+
+/// Expenses by category of several profiles combined. Categories are matched by name
+/// (case-insensitive), since ids differ from one profile to another.
+#[tauri::command]
+pub fn get_household_expenses_by_category(
+    app: tauri::AppHandle,
+    profile_ids: Vec<String>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<HouseholdCategoryItem>, String> {
+    let (conn, profiles, amounts) = attach_profiles(&app, &profile_ids)?;
+    let sql = union_all(
+        "SELECT {i}, COALESCE(c.name, 'Uncategorized'), COALESCE(c.color, '#9ca3af'),
+                ABS(SUM({amount}))
+         FROM {p}.transactions t
+         LEFT JOIN {p}.categories c ON t.category_id = c.id
+         WHERE COALESCE(c.type, 'expense') = 'expense'
+           AND (?1 IS NULL OR t.date >= ?1) AND (?2 IS NULL OR t.date <= ?2)
+         GROUP BY c.name",
+        &amounts,
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Cannot build household report: {}", e))?;
+    let rows = stmt
+        .query_map([&date_from, &date_to], |row| {
+            Ok((
+                row.get::<_, usize>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Money>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot run household report: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot run household report: {}", e))?;
+
+    let mut by_name: HashMap<String, HouseholdCategoryItem> = HashMap::new();
+    for (index, name, color, total) in rows {
+        // The name and color of the first profile listed win
+        let item = by_name
+            .entry(name.trim().to_lowercase())
+            .or_insert_with(|| HouseholdCategoryItem {
+                category_name: name.trim().to_string(),
+                category_color: color,
+                total: Money::ZERO,
+                profiles: Vec::new(),
+            });
+        item.total += total;
+        match item
+            .profiles
+            .iter_mut()
+            .find(|p| p.profile_id == profiles[index].id)
+        {
+            Some(share) => share.total += total,
+            None => item.profiles.push(ProfileTotal {
+                profile_id: profiles[index].id.clone(),
+                profile_name: profiles[index].name.clone(),
+                total,
+            }),
+        }
+    }
+    let mut categories: Vec<HouseholdCategoryItem> = by_name.into_values().collect();
+    categories.sort_by_key(|c| std::cmp::Reverse(c.total));
+    Ok(categories)
+}
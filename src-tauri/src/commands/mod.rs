@@ -1,7 +1,43 @@
 pub mod fs_commands;
 pub mod export_import_commands;
 pub mod profile_commands;
+pub mod backup_commands;
+pub mod backup_schedule_commands;
+pub mod db_encryption_commands;
+pub mod schema_commands;
+pub mod snapshot_commands;
+pub mod profile_trash_commands;
+pub mod profile_clone_commands;
+pub mod profile_bundle_commands;
+pub mod profile_merge_commands;
+pub mod household_report_commands;
+pub mod adjustment_commands;
+pub mod pivot_report_commands;
+pub mod account_commands;
+pub mod reconciliation_commands;
+pub mod currency_commands;
+pub mod date_commands;
+pub mod amount_commands;
+pub mod budget_commands;
 
 pub use fs_commands::*;
 pub use export_import_commands::*;
 pub use profile_commands::*;
+pub use backup_commands::*;
+pub use backup_schedule_commands::*;
+pub use db_encryption_commands::*;
+pub use schema_commands::*;
+pub use snapshot_commands::*;
+pub use profile_trash_commands::*;
+pub use profile_clone_commands::*;
+pub use profile_bundle_commands::*;
+pub use profile_merge_commands::*;
+pub use household_report_commands::*;
+pub use adjustment_commands::*;
+pub use pivot_report_commands::*;
+pub use account_commands::*;
+pub use reconciliation_commands::*;
+pub use currency_commands::*;
+pub use date_commands::*;
+pub use amount_commands::*;
+pub use budget_commands::*;
@@ -0,0 +1,19 @@
+use super::profile_commands::open_active_profile_db;
+use crate::pivot::{self, PivotConfig, PivotResult};
+
+/// Dynamic (pivot) report over the active profile's transactions.
+#[tauri::command]
+pub fn get_pivot_report(app: tauri::AppHandle, config: PivotConfig) -> Result<PivotResult, String> {
+    let conn = open_active_profile_db(&app)?;
+    pivot::run_pivot(&conn, &config)
+}
+
+/// Distinct values of a pivot field, for the dynamic report filters.
+#[tauri::command]
+pub fn get_pivot_filter_values(
+    app: tauri::AppHandle,
+    field_id: String,
+) -> Result<Vec<String>, String> {
+    let conn = open_active_profile_db(&app)?;
+    pivot::filter_values(&conn, &field_id)
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use super::backup_commands::{
+    check_backup_schema, install_profile_db, pack_backup, snapshot_profile, unpack_backup,
+};
+use super::export_import_commands::{decrypt_payload, encrypt_payload};
+use super::profile_commands::{
+    chrono_now, new_profile_ids, read_profiles_config, write_profiles_config, Profile,
+};
+use crate::database;
+
+const BUNDLE_MAGIC: &[u8; 4] = b"SRPB";
+const BUNDLE_VERSION: u8 = 0x01;
+const BUNDLE_HEADER_LEN: usize = 4 + 1 + 4; // magic + version + profile length
+
+/// Profile metadata carried by a bundle. The database key salt is left out: the bundled
+/// snapshot is always plaintext, the bundle itself being protected by its password.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleProfile {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub pin_hash: Option<String>,
+    pub db_filename: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleImportResult {
+    pub profile: Profile,
+    /// True when the bundled id or database filename was already taken on this machine.
+    pub renamed: bool,
+}
+
+/// A bundle is `SRPB | version | profile JSON length | profile JSON | backup container`,
+/// optionally wrapped in an SREF password envelope.
+fn pack_bundle(profile: &BundleProfile, container: &[u8]) -> Result<Vec<u8>, String> {
+    let profile_json = serde_json::to_vec(profile).map_err(|e| format!("JSON error: {}", e))?;
+    let mut output = Vec::with_capacity(BUNDLE_HEADER_LEN + profile_json.len() + container.len());
+    output.extend_from_slice(BUNDLE_MAGIC);
+    output.push(BUNDLE_VERSION);
+    output.extend_from_slice(&(profile_json.len() as u32).to_le_bytes());
+    output.extend_from_slice(&profile_json);
+    output.extend_from_slice(container);
+    Ok(output)
+}
+
+fn unpack_bundle(data: &[u8]) -> Result<(BundleProfile, &[u8]), String> {
+    if data.len() < BUNDLE_HEADER_LEN || &data[0..4] != BUNDLE_MAGIC {
+        return Err("Not a valid profile bundle".to_string());
+    }
+    if data[4] != BUNDLE_VERSION {
+        return Err(format!("Unsupported bundle version: {}", data[4]));
+    }
+
+    let profile_len = u32::from_le_bytes([data[5], data[6], data[7], data[8]]) as usize;
+    let profile_end = BUNDLE_HEADER_LEN + profile_len;
+    if data.len() < profile_end {
+        return Err("Bundle is truncated".to_string());
+    }
+    let profile: BundleProfile = serde_json::from_slice(&data[BUNDLE_HEADER_LEN..profile_end])
+        .map_err(|e| format!("Invalid bundle profile: {}", e))?;
+    Ok((profile, &data[profile_end..]))
+}
+
+/// Writes a profile (name, color, PIN hash and a snapshot of its database) to a single
+/// file that can be imported on another machine. With a password the file is SREF
+/// encrypted; without one it is written as is, which is refused for a profile whose
+/// database is encrypted at rest since the bundled snapshot is plaintext.
+#[tauri::command]
+pub fn export_profile_bundle(
+    app: tauri::AppHandle,
+    profile_id: String,
+    file_path: String,
+    password: Option<String>,
+) -> Result<BundleProfile, String> {
+    let config = read_profiles_config(&app)?;
+    let profile = config
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile not found: {}", profile_id))?;
+    if profile.db_key_salt.is_some() && !database::encryption::is_unlocked(&profile.db_filename) {
+        return Err("Unlock the encrypted profile before exporting it".to_string());
+    }
+    if profile.db_key_salt.is_some() && password.as_deref().is_none_or(str::is_empty) {
+        return Err("A password is required to export an encrypted profile".to_string());
+    }
+
+    let (manifest, db_bytes) = snapshot_profile(&app, profile)?;
+    let container = pack_backup(&manifest, &db_bytes)?;
+    let bundle_profile = BundleProfile {
+        id: profile.id.clone(),
+        name: profile.name.clone(),
+        color: profile.color.clone(),
+        pin_hash: profile.pin_hash.clone(),
+        db_filename: profile.db_filename.clone(),
+        created_at: profile.created_at.clone(),
+    };
+    let bundle = pack_bundle(&bundle_profile, &container)?;
+
+    let bytes = encrypt_payload(bundle, password.as_deref(), &[])?;
+    fs::write(&file_path, bytes).map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(bundle_profile)
+}
+
+/// Imports a profile bundle and registers it in `profiles.json`. The bundled id and
+/// database filename are kept unless one of them is already in use, in which case fresh
+/// ones are assigned. The imported database is not encrypted at rest.
+#[tauri::command]
+pub fn import_profile_bundle(
+    app: tauri::AppHandle,
+    file_path: String,
+    password: Option<String>,
+) -> Result<BundleImportResult, String> {
+    let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let bundle = decrypt_payload(&app, bytes, password, None)?;
+    let (bundled, container) = unpack_bundle(&bundle)?;
+    let (manifest, db_bytes) = unpack_backup(container)?;
+    check_backup_schema(&manifest)?;
+
+    let mut config = read_profiles_config(&app)?;
+    let db_dir = database::get_db_dir(&app)?;
+    // A bundled filename is only trusted when it is a plain `.db` name
+    let valid_filename = bundled.db_filename.ends_with(".db")
+        && !bundled.db_filename.contains(['/', '\\'])
+        && !bundled.db_filename.starts_with('.');
+    let renamed = !valid_filename
+        || config
+            .profiles
+            .iter()
+            .any(|p| p.id == bundled.id || p.db_filename == bundled.db_filename)
+        || db_dir.join(&bundled.db_filename).exists();
+    let (id, db_filename) = if renamed {
+        new_profile_ids()
+    } else {
+        (bundled.id.clone(), bundled.db_filename.clone())
+    };
+
+    install_profile_db(&app, &db_filename, db_bytes)?;
+
+    let profile = Profile {
+        id,
+        name: bundled.name,
+        color: bundled.color,
+        pin_hash: bundled.pin_hash,
+        db_filename,
+        created_at: if bundled.created_at.is_empty() {
+            chrono_now()
+        } else {
+            bundled.created_at
+        },
+        db_key_salt: None,
+    };
+    config.profiles.push(profile.clone());
+    write_profiles_config(&app, &config)?;
+
+    Ok(BundleImportResult { profile, renamed })
+}
@@ -0,0 +1,175 @@
+use rusqlite::Connection;
+use std::fs;
+
+use super::profile_commands::{
+    chrono_now, new_profile_ids, read_profiles_config, write_profiles_config, Profile,
+};
+use crate::database::{self, encryption};
+
+/// Tables kept by a structure-only copy; every other data table is emptied.
+const STRUCTURE_TABLES: &[&str] = &[
+    "categories",
+    "keywords",
+    "suppliers",
+    "import_sources",
+    "import_config_templates",
+    "budget_templates",
+    "budget_template_entries",
+    "user_preferences",
+];
+
+/// Preferences that describe the source profile itself rather than its setup.
+const PROFILE_SPECIFIC_PREFERENCES: &[&str] = &["backup_schedule", "backup_history"];
+
+/// Empties every table that is not part of the profile structure (transactions, imports,
+/// adjustments, budgets...) and resets their id sequences.
+fn strip_to_structure(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '_sqlx_%'",
+        )
+        .map_err(|e| format!("Cannot list tables: {}", e))?;
+    let tables: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Cannot list tables: {}", e))?
+        .filter_map(Result::ok)
+        .filter(|name: &String| !STRUCTURE_TABLES.contains(&name.as_str()))
+        .collect();
+    drop(stmt);
+
+    let mut sql = String::from("BEGIN;\n");
+    if tables.iter().any(|t| t == "reconciliations") {
+        // Reconciled transactions are locked against deletion by a trigger
+        sql.push_str("UPDATE transactions SET reconciliation_id = NULL;\n");
+    }
+    for table in &tables {
+        sql.push_str(&format!("DELETE FROM \"{}\";\n", table));
+        sql.push_str(&format!(
+            "DELETE FROM sqlite_sequence WHERE name = '{}';\n",
+            table.replace('\'', "''")
+        ));
+    }
+    for key in PROFILE_SPECIFIC_PREFERENCES {
+        sql.push_str(&format!(
+            "DELETE FROM user_preferences WHERE key = '{}';\n",
+            key
+        ));
+    }
+    sql.push_str("COMMIT;\nVACUUM;");
+    conn.execute_batch(&sql)
+        .map_err(|e| format!("Cannot strip the copied database: {}", e))
+}
+
+/// Creates a new profile from an existing one. `mode` is `full` (complete copy of the
+/// database) or `structure` (categories, keywords, suppliers, import sources, import and
+/// budget templates, without any transaction). The copy of an encrypted profile stays
+/// encrypted with the same password; the PIN is not copied.
+#[tauri::command]
+pub fn clone_profile(
+    app: tauri::AppHandle,
+    source_profile_id: String,
+    name: String,
+    color: String,
+    mode: String,
+) -> Result<Profile, String> {
+    if mode != "full" && mode != "structure" {
+        return Err(format!("Invalid clone mode: {}", mode));
+    }
+    if name.trim().is_empty() {
+        return Err("A profile name is required".to_string());
+    }
+    let mut config = read_profiles_config(&app)?;
+    let source = config
+        .profiles
+        .iter()
+        .find(|p| p.id == source_profile_id)
+        .cloned()
+        .ok_or_else(|| format!("Profile not found: {}", source_profile_id))?;
+    if source.db_key_salt.is_some() && !encryption::is_unlocked(&source.db_filename) {
+        return Err("Unlock the encrypted profile before copying it".to_string());
+    }
+
+    let (id, db_filename) = new_profile_ids();
+    let target_path = database::get_db_path(&app, &db_filename)?;
+    let key = encryption::key_for_db(&source.db_filename);
+
+    let result = (|| {
+        let conn = database::open_profile_db(&app, &source.db_filename)?;
+        match &key {
+            Some(key) => encryption::export_with_key(&conn, &target_path, key)?,
+            None => database::snapshot_into(&conn, &target_path)?,
+        }
+        drop(conn);
+
+        if let Some(key) = &key {
+            encryption::register_key(&db_filename, key.clone());
+        }
+        let copy = database::open_db(&target_path)?;
+        if mode == "structure" {
+            strip_to_structure(&copy)?;
+        }
+        database::check_integrity(&copy)
+    })();
+    if let Err(e) = result {
+        encryption::forget_key(&db_filename);
+        let _ = fs::remove_file(&target_path);
+        return Err(e);
+    }
+
+    let profile = Profile {
+        id,
+        name: name.trim().to_string(),
+        color,
+        pin_hash: None,
+        db_filename,
+        created_at: chrono_now(),
+        db_key_salt: source.db_key_salt.clone(),
+    };
+    config.profiles.push(profile.clone());
+    write_profiles_config(&app, &config)?;
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::migrated_test_db;
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn strip_removes_reconciled_transactions() {
+        let conn = migrated_test_db();
+        conn.execute_batch(
+            "INSERT INTO import_sources (id, name, column_mapping) VALUES (1, 'Chq', '{}');
+             INSERT INTO reconciliations (id, source_id, statement_date, statement_balance, computed_balance)
+             VALUES (1, 1, '2025-01-31', 0, 0);
+             INSERT INTO transactions (date, description, amount, source_id, reconciliation_id)
+             VALUES ('2025-01-10', 'reconciled', -100, 1, 1),
+                    ('2025-02-10', 'open', -100, 1, NULL);
+             INSERT INTO user_preferences (key, value) VALUES ('backup_schedule', '{}');",
+        )
+        .unwrap();
+        let categories = count(&conn, "categories");
+
+        strip_to_structure(&conn).unwrap();
+        assert_eq!(count(&conn, "transactions"), 0);
+        assert_eq!(count(&conn, "reconciliations"), 0);
+        assert_eq!(count(&conn, "import_sources"), 1);
+        assert_eq!(count(&conn, "categories"), categories);
+        let schedules: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM user_preferences WHERE key = 'backup_schedule'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(schedules, 0);
+    }
+}
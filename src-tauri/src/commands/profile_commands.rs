@@ -1,7 +1,13 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use subtle::ConstantTimeEq;
 use tauri::Manager;
 
 use crate::database;
@@ -14,6 +20,9 @@ pub struct Profile {
     pub pin_hash: Option<String>,
     pub db_filename: String,
     pub created_at: String,
+    /// Hex salt of the SQLCipher key; present only for encrypted profile databases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_key_salt: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,12 +31,77 @@ pub struct ProfilesConfig {
     pub profiles: Vec<Profile>,
 }
 
+const PROFILES_FILENAME: &str = "profiles.json";
+pub(crate) const DEFAULT_DB_FILENAME: &str = "simpl_resultat.db";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfilesRebuildResult {
+    pub config: ProfilesConfig,
+    /// Encrypted databases that could not be re-added: their key salt was only stored in
+    /// the lost profiles.json.
+    pub encrypted_skipped: Vec<String>,
+}
+
 fn get_profiles_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     let app_dir = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("Cannot get app data dir: {}", e))?;
-    Ok(app_dir.join("profiles.json"))
+    Ok(app_dir.join(PROFILES_FILENAME))
+}
+
+/// `profiles.json` + `suffix`, e.g. `profiles.json.bak`.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn read_profiles_file(path: &Path) -> Result<ProfilesConfig, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> Result<(), String> {
+    fs::File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(|e| format!("Cannot sync app data dir: {}", e))
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// Writes profiles.json atomically: temp file, fsync, rename. The previous version is
+/// kept as `profiles.json.bak` if it is still valid.
+fn write_profiles_file(path: &Path, config: &ProfilesConfig) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config).map_err(|e| format!("JSON error: {}", e))?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| "Invalid profiles.json path".to_string())?;
+    fs::create_dir_all(parent).map_err(|e| format!("Cannot create app data dir: {}", e))?;
+
+    let tmp_path = sibling_path(path, ".tmp");
+    let mut file =
+        fs::File::create(&tmp_path).map_err(|e| format!("Cannot write profiles.json: {}", e))?;
+    file.write_all(json.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Cannot write profiles.json: {}", e))?;
+    drop(file);
+
+    // Never replace a good backup with a corrupted file
+    if read_profiles_file(path).is_ok() {
+        let bak_path = sibling_path(path, ".bak");
+        fs::copy(path, &bak_path)
+            .and_then(|_| fs::File::open(&bak_path)?.sync_all())
+            .map_err(|e| format!("Cannot back up profiles.json: {}", e))?;
+    }
+
+    fs::rename(&tmp_path, path).map_err(|e| format!("Cannot write profiles.json: {}", e))?;
+    sync_dir(parent)
 }
 
 fn make_default_config() -> ProfilesConfig {
@@ -40,14 +114,15 @@ fn make_default_config() -> ProfilesConfig {
             name: "Default".to_string(),
             color: "#4A90A4".to_string(),
             pin_hash: None,
-            db_filename: "simpl_resultat.db".to_string(),
+            db_filename: DEFAULT_DB_FILENAME.to_string(),
             created_at: now,
+            db_key_salt: None,
         }],
     }
 }
 
-fn chrono_now() -> String {
-    // Simple ISO-ish timestamp without pulling in chrono crate
+/// Current time as a Unix timestamp string, the format of `Profile::created_at`.
+pub(crate) fn chrono_now() -> String {
     let dur = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default();
@@ -56,58 +131,145 @@ fn chrono_now() -> String {
     secs.to_string()
 }
 
+/// Generates a fresh profile id and matching database filename, mirroring the frontend's
+/// `crypto.randomUUID()` + `profile_<first segment>.db` convention.
+pub(crate) fn new_profile_ids() -> (String, String) {
+    let mut bytes = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex_encode(&bytes);
+    let id = format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    );
+    let db_filename = format!("profile_{}.db", &hex[0..8]);
+    (id, db_filename)
+}
+
+pub(crate) fn read_profiles_config(app: &tauri::AppHandle) -> Result<ProfilesConfig, String> {
+    load_profiles(app.clone())
+}
+
+/// Opens the database of the active profile.
+pub(crate) fn open_active_profile_db(
+    app: &tauri::AppHandle,
+) -> Result<rusqlite::Connection, String> {
+    let config = read_profiles_config(app)?;
+    let profile = config
+        .profiles
+        .iter()
+        .find(|p| p.id == config.active_profile_id)
+        .ok_or_else(|| "No active profile".to_string())?;
+    database::open_profile_db(app, &profile.db_filename)
+}
+
+pub(crate) fn write_profiles_config(
+    app: &tauri::AppHandle,
+    config: &ProfilesConfig,
+) -> Result<(), String> {
+    save_profiles(app.clone(), config.clone())
+}
+
 #[tauri::command]
 pub fn load_profiles(app: tauri::AppHandle) -> Result<ProfilesConfig, String> {
     let path = get_profiles_path(&app)?;
+    let bak_path = sibling_path(&path, ".bak");
 
-    if !path.exists() {
+    if !path.exists() && !bak_path.exists() {
         let config = make_default_config();
-        let json =
-            serde_json::to_string_pretty(&config).map_err(|e| format!("JSON error: {}", e))?;
-
-        // Ensure parent dir exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Cannot create app data dir: {}", e))?;
-        }
-
-        fs::write(&path, json).map_err(|e| format!("Cannot write profiles.json: {}", e))?;
+        write_profiles_file(&path, &config)?;
         return Ok(config);
     }
 
-    let content =
-        fs::read_to_string(&path).map_err(|e| format!("Cannot read profiles.json: {}", e))?;
-    let config: ProfilesConfig =
-        serde_json::from_str(&content).map_err(|e| format!("Invalid profiles.json: {}", e))?;
+    let primary_error = match read_profiles_file(&path) {
+        Ok(config) => return Ok(config),
+        Err(e) => e,
+    };
+    // The primary is unreadable (e.g. truncated by a crash): fall back to the backup, and
+    // keep the broken file aside for manual inspection
+    let config = read_profiles_file(&bak_path).map_err(|_| {
+        format!(
+            "{}, and no valid backup exists. The profile list can be rebuilt from the database files.",
+            primary_error
+        )
+    })?;
+    if path.exists() {
+        let _ = fs::rename(&path, sibling_path(&path, ".corrupt"));
+    }
+    write_profiles_file(&path, &config)?;
     Ok(config)
 }
 
 #[tauri::command]
 pub fn save_profiles(app: tauri::AppHandle, config: ProfilesConfig) -> Result<(), String> {
     let path = get_profiles_path(&app)?;
-    let json =
-        serde_json::to_string_pretty(&config).map_err(|e| format!("JSON error: {}", e))?;
-    fs::write(&path, json).map_err(|e| format!("Cannot write profiles.json: {}", e))
+    write_profiles_file(&path, &config)
 }
 
+/// Recovery path when profiles.json and its backup are both unusable: recreates one
+/// profile per database file found. Names, colors and PINs cannot be recovered.
 #[tauri::command]
-pub fn delete_profile_db(app: tauri::AppHandle, db_filename: String) -> Result<(), String> {
-    if db_filename == "simpl_resultat.db" {
-        return Err("Cannot delete the default profile database".to_string());
-    }
-
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Cannot get app data dir: {}", e))?;
-    let db_path = app_dir.join(&db_filename);
+pub fn rebuild_profiles(app: tauri::AppHandle) -> Result<ProfilesRebuildResult, String> {
+    let db_dir = database::get_db_dir(&app)?;
+    let mut db_filenames: Vec<String> = match fs::read_dir(&db_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| entry.file_name().to_str().map(String::from))
+            .filter(|name| name.ends_with(".db"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    db_filenames.sort();
 
-    if db_path.exists() {
-        fs::remove_file(&db_path)
-            .map_err(|e| format!("Cannot delete database file: {}", e))?;
+    let mut config = ProfilesConfig {
+        active_profile_id: String::new(),
+        profiles: Vec::new(),
+    };
+    let mut encrypted_skipped = Vec::new();
+    let mut recovered = 0;
+    for db_filename in db_filenames {
+        if database::encryption::is_file_encrypted(&db_dir.join(&db_filename))? {
+            encrypted_skipped.push(db_filename);
+            continue;
+        }
+        if db_filename == DEFAULT_DB_FILENAME {
+            config
+                .profiles
+                .insert(0, make_default_config().profiles.remove(0));
+            continue;
+        }
+        recovered += 1;
+        let (id, _) = new_profile_ids();
+        config.profiles.push(Profile {
+            id,
+            name: format!("Recovered profile {}", recovered),
+            color: "#4A90A4".to_string(),
+            pin_hash: None,
+            db_filename,
+            created_at: chrono_now(),
+            db_key_salt: None,
+        });
+    }
+    if config.profiles.is_empty() {
+        config = make_default_config();
     }
+    config.active_profile_id = config.profiles[0].id.clone();
 
-    Ok(())
+    let path = get_profiles_path(&app)?;
+    if path.exists() {
+        let _ = fs::rename(&path, sibling_path(&path, ".corrupt"));
+    }
+    write_profiles_file(&path, &config)?;
+    Ok(ProfilesRebuildResult {
+        config,
+        encrypted_skipped,
+    })
 }
 
 #[tauri::command]
@@ -118,40 +280,180 @@ pub fn get_new_profile_init_sql() -> Result<Vec<String>, String> {
     ])
 }
 
-#[tauri::command]
-pub fn hash_pin(pin: String) -> Result<String, String> {
-    let mut salt = [0u8; 16];
-    rand::rngs::OsRng.fill_bytes(&mut salt);
-    let salt_hex = hex_encode(&salt);
+const PIN_ATTEMPTS_FILENAME: &str = "pin_attempts.json";
+/// Failed attempts allowed before the backoff kicks in.
+const PIN_FREE_ATTEMPTS: u32 = 3;
+const PIN_BASE_DELAY_SECS: u64 = 30;
+const PIN_MAX_DELAY_SECS: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PinAttempts {
+    failed_attempts: u32,
+    locked_until: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PinVerification {
+    pub valid: bool,
+    pub failed_attempts: u32,
+    /// Seconds to wait before the next attempt is accepted, when locked out.
+    pub retry_after_secs: Option<u64>,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Lockout after the n-th consecutive failure: none for the first few attempts, then
+/// doubling from 30 seconds up to one hour.
+fn pin_backoff_secs(failed_attempts: u32) -> u64 {
+    if failed_attempts < PIN_FREE_ATTEMPTS {
+        return 0;
+    }
+    let exponent = (failed_attempts - PIN_FREE_ATTEMPTS).min(16);
+    (PIN_BASE_DELAY_SECS << exponent).min(PIN_MAX_DELAY_SECS)
+}
+
+fn get_pin_attempts_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Cannot get app data dir: {}", e))?;
+    Ok(app_dir.join(PIN_ATTEMPTS_FILENAME))
+}
+
+fn load_pin_attempts(app: &tauri::AppHandle) -> Result<HashMap<String, PinAttempts>, String> {
+    let path = get_pin_attempts_path(app)?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read {}: {}", PIN_ATTEMPTS_FILENAME, e))?;
+    // A damaged counter file must not lock everyone out; start over instead
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+fn save_pin_attempts(
+    app: &tauri::AppHandle,
+    attempts: &HashMap<String, PinAttempts>,
+) -> Result<(), String> {
+    let path = get_pin_attempts_path(app)?;
+    let json = serde_json::to_string_pretty(attempts).map_err(|e| format!("JSON error: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Cannot write {}: {}", PIN_ATTEMPTS_FILENAME, e))
+}
+
+fn is_legacy_pin_hash(stored_hash: &str) -> bool {
+    !stored_hash.starts_with('$')
+}
+
+/// Verifies a legacy `salt:hash` value (salted SHA-256) in constant time.
+fn verify_legacy_pin(pin: &str, stored_hash: &str) -> Result<bool, String> {
+    let (salt_hex, expected_hash) = stored_hash
+        .split_once(':')
+        .ok_or_else(|| "Invalid stored hash format".to_string())?;
 
     let mut hasher = Sha256::new();
     hasher.update(salt_hex.as_bytes());
     hasher.update(pin.as_bytes());
-    let result = hasher.finalize();
-    let hash_hex = hex_encode(&result);
+    let computed_hash = hex_encode(&hasher.finalize());
+
+    Ok(bool::from(
+        computed_hash.as_bytes().ct_eq(expected_hash.as_bytes()),
+    ))
+}
+
+fn verify_pin_hash(pin: &str, stored_hash: &str) -> Result<bool, String> {
+    if is_legacy_pin_hash(stored_hash) {
+        return verify_legacy_pin(pin, stored_hash);
+    }
+    let parsed =
+        PasswordHash::new(stored_hash).map_err(|e| format!("Invalid stored hash format: {}", e))?;
+    Ok(Argon2::default()
+        .verify_password(pin.as_bytes(), &parsed)
+        .is_ok())
+}
+
+/// Hashes a PIN with Argon2id, returned as a PHC string (`$argon2id$v=19$...`).
+#[tauri::command]
+pub fn hash_pin(pin: String) -> Result<String, String> {
+    let mut salt = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt).map_err(|e| format!("Salt error: {}", e))?;
 
-    // Store as "salt:hash"
-    Ok(format!("{}:{}", salt_hex, hash_hex))
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("PIN hashing error: {}", e))
 }
 
+/// Verifies the PIN of a profile, with a persisted failed-attempt counter and exponential
+/// backoff. On success, a legacy hash is transparently replaced by an Argon2id one.
 #[tauri::command]
-pub fn verify_pin(pin: String, stored_hash: String) -> Result<bool, String> {
-    let parts: Vec<&str> = stored_hash.split(':').collect();
-    if parts.len() != 2 {
-        return Err("Invalid stored hash format".to_string());
+pub fn verify_profile_pin(
+    app: tauri::AppHandle,
+    profile_id: String,
+    pin: String,
+) -> Result<PinVerification, String> {
+    let mut config = read_profiles_config(&app)?;
+    let profile = config
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile not found: {}", profile_id))?;
+    let stored_hash = match profile.pin_hash.clone() {
+        Some(hash) => hash,
+        None => {
+            return Ok(PinVerification {
+                valid: true,
+                failed_attempts: 0,
+                retry_after_secs: None,
+            })
+        }
+    };
+
+    let mut all_attempts = load_pin_attempts(&app)?;
+    let attempts = all_attempts.entry(profile_id.clone()).or_default();
+    let now = now_secs();
+    if attempts.locked_until > now {
+        return Ok(PinVerification {
+            valid: false,
+            failed_attempts: attempts.failed_attempts,
+            retry_after_secs: Some(attempts.locked_until - now),
+        });
     }
-    let salt_hex = parts[0];
-    let expected_hash = parts[1];
 
-    let mut hasher = Sha256::new();
-    hasher.update(salt_hex.as_bytes());
-    hasher.update(pin.as_bytes());
-    let result = hasher.finalize();
-    let computed_hash = hex_encode(&result);
+    if !verify_pin_hash(&pin, &stored_hash)? {
+        attempts.failed_attempts += 1;
+        let delay = pin_backoff_secs(attempts.failed_attempts);
+        attempts.locked_until = if delay > 0 { now + delay } else { 0 };
+        let result = PinVerification {
+            valid: false,
+            failed_attempts: attempts.failed_attempts,
+            retry_after_secs: (delay > 0).then_some(delay),
+        };
+        save_pin_attempts(&app, &all_attempts)?;
+        return Ok(result);
+    }
+
+    if all_attempts.remove(&profile_id).is_some() {
+        save_pin_attempts(&app, &all_attempts)?;
+    }
+
+    if is_legacy_pin_hash(&stored_hash) {
+        profile.pin_hash = Some(hash_pin(pin)?);
+        write_profiles_config(&app, &config)?;
+    }
 
-    Ok(computed_hash == expected_hash)
+    Ok(PinVerification {
+        valid: true,
+        failed_attempts: 0,
+        retry_after_secs: None,
+    })
 }
 
-fn hex_encode(bytes: &[u8]) -> String {
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
     bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
@@ -0,0 +1,52 @@
+use super::profile_commands::read_profiles_config;
+use super::schema_commands::migrate_profile_db;
+use super::snapshot_commands::{snapshot_db, REASON_PRE_MERGE};
+use crate::database::{self, encryption, merge};
+
+/// Merges another profile into the active one: categories and keywords are matched by
+/// name and hierarchy, transactions already present are skipped and the other rows are
+/// copied with their references remapped. Call it with `dry_run` first to get the
+/// conflict report; on conflicts the active profile's values are kept. The source
+/// profile is left untouched, and the active database is snapshotted before the merge.
+#[tauri::command]
+pub fn merge_profile(
+    app: tauri::AppHandle,
+    source_profile_id: String,
+    dry_run: bool,
+) -> Result<merge::MergeReport, String> {
+    let config = read_profiles_config(&app)?;
+    if source_profile_id == config.active_profile_id {
+        return Err("Cannot merge a profile into itself".to_string());
+    }
+    let find = |id: &str| {
+        config
+            .profiles
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Profile not found: {}", id))
+    };
+    let source = find(&source_profile_id)?;
+    let target = find(&config.active_profile_id)?;
+    for profile in [source, target] {
+        if profile.db_key_salt.is_some() && !encryption::is_unlocked(&profile.db_filename) {
+            return Err(format!(
+                "Unlock the profile {} before merging",
+                profile.name
+            ));
+        }
+    }
+
+    // The source may not have been opened since the last update
+    let migration = migrate_profile_db(app.clone(), source.db_filename.clone())?;
+    if let Some(e) = migration.error {
+        return Err(e);
+    }
+
+    let source_conn = database::open_profile_db(&app, &source.db_filename)?;
+    let mut target_conn = database::open_profile_db(&app, &target.db_filename)?;
+    if !dry_run {
+        snapshot_db(&app, &target.db_filename, REASON_PRE_MERGE)
+            .map_err(|e| format!("Cannot snapshot the database before merging: {}", e))?;
+    }
+    merge::merge_db(&mut target_conn, &source_conn, dry_run)
+}
@@ -0,0 +1,215 @@
+use chrono::{Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::profile_commands::{
+    new_profile_ids, read_profiles_config, write_profiles_config, Profile, DEFAULT_DB_FILENAME,
+};
+use super::snapshot_commands::delete_snapshots;
+use crate::database::{self, encryption};
+
+const TRASH_DIR: &str = "trash";
+const TRASH_METADATA_FILENAME: &str = "profile.json";
+/// Deleted profiles stay recoverable for this many days.
+const TRASH_RETENTION_DAYS: i64 = 30;
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Stored next to the database files of a deleted profile, to restore it as it was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrashMetadata {
+    profile: Profile,
+    deleted_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletedProfile {
+    pub trash_id: String,
+    pub profile: Profile,
+    pub deleted_at: String,
+    pub expires_at: String,
+}
+
+fn get_trash_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(database::get_db_dir(app)?.join(TRASH_DIR))
+}
+
+fn remove_files(paths: &[PathBuf], secure: bool) -> Result<(), String> {
+    for path in paths.iter().filter(|p| p.exists()) {
+        if secure {
+            database::secure_remove_file(path)?;
+        } else {
+            fs::remove_file(path)
+                .map_err(|e| format!("Cannot delete {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// The database file followed by its SQLite sidecars.
+fn db_file_set(db_path: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![db_path.to_path_buf()];
+    paths.extend(database::sidecar_paths(db_path));
+    paths
+}
+
+fn read_trash_entry(entry_dir: &Path) -> Option<DeletedProfile> {
+    let content = fs::read_to_string(entry_dir.join(TRASH_METADATA_FILENAME)).ok()?;
+    let metadata: TrashMetadata = serde_json::from_str(&content).ok()?;
+    let deleted_at =
+        NaiveDateTime::parse_from_str(&metadata.deleted_at, "%Y-%m-%dT%H:%M:%S").ok()?;
+    Some(DeletedProfile {
+        trash_id: entry_dir.file_name()?.to_str()?.to_string(),
+        profile: metadata.profile,
+        deleted_at: metadata.deleted_at,
+        expires_at: (deleted_at + Duration::days(TRASH_RETENTION_DAYS))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string(),
+    })
+}
+
+fn list_trash(app: &tauri::AppHandle) -> Result<Vec<DeletedProfile>, String> {
+    let dir = get_trash_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut deleted: Vec<DeletedProfile> = fs::read_dir(&dir)
+        .map_err(|e| format!("Cannot read trash folder: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| read_trash_entry(&entry.path()))
+        .collect();
+    deleted.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(deleted)
+}
+
+/// Permanently removes a trash entry together with the snapshots of its database.
+fn purge_entry(app: &tauri::AppHandle, entry: &DeletedProfile) -> Result<(), String> {
+    let entry_dir = get_trash_dir(app)?.join(&entry.trash_id);
+    delete_snapshots(app, &entry.profile.db_filename, false)?;
+    fs::remove_dir_all(&entry_dir).map_err(|e| format!("Cannot empty trash: {}", e))
+}
+
+/// Permanently removes the deleted profiles whose retention period is over.
+pub fn purge_expired_profiles(app: &tauri::AppHandle) -> Result<(), String> {
+    let now = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    for entry in list_trash(app)?.iter().filter(|e| e.expires_at <= now) {
+        purge_entry(app, entry)?;
+    }
+    Ok(())
+}
+
+/// Deletes a profile database and removes the profile from profiles.json. By default the
+/// database and its `-wal`/`-shm`/`-journal` files are moved to a trash folder, from which
+/// they can be restored for 30 days; `secure_overwrite` instead overwrites them (and the
+/// profile's snapshots) with random data and deletes them for good. The profile must not
+/// be the active one, and the frontend must have closed its connection to it.
+#[tauri::command]
+pub fn delete_profile_db(
+    app: tauri::AppHandle,
+    db_filename: String,
+    secure_overwrite: Option<bool>,
+) -> Result<(), String> {
+    if db_filename == DEFAULT_DB_FILENAME {
+        return Err("Cannot delete the default profile database".to_string());
+    }
+    // Only a filename listed in profiles.json is accepted, which also rules out traversal
+    let mut config = read_profiles_config(&app)?;
+    let index = config
+        .profiles
+        .iter()
+        .position(|p| p.db_filename == db_filename)
+        .ok_or_else(|| format!("No profile uses the database {}", db_filename))?;
+    if config.profiles[index].id == config.active_profile_id {
+        return Err("Cannot delete the database of the active profile".to_string());
+    }
+
+    let db_path = database::get_db_path(&app, &db_filename)?;
+    let files = db_file_set(&db_path);
+    if secure_overwrite.unwrap_or(false) {
+        remove_files(&files, true)?;
+        delete_snapshots(&app, &db_filename, true)?;
+    } else {
+        let deleted_at = Local::now();
+        let entry_dir = get_trash_dir(&app)?.join(format!(
+            "{}-{}",
+            db_filename.trim_end_matches(".db"),
+            deleted_at.format(TIMESTAMP_FORMAT)
+        ));
+        fs::create_dir_all(&entry_dir).map_err(|e| format!("Cannot create trash folder: {}", e))?;
+        let metadata = TrashMetadata {
+            profile: config.profiles[index].clone(),
+            deleted_at: deleted_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        };
+        let json =
+            serde_json::to_string_pretty(&metadata).map_err(|e| format!("JSON error: {}", e))?;
+        fs::write(entry_dir.join(TRASH_METADATA_FILENAME), json)
+            .map_err(|e| format!("Cannot write trash metadata: {}", e))?;
+        for path in files.iter().filter(|p| p.exists()) {
+            let file_name = path.file_name().unwrap_or_default();
+            fs::rename(path, entry_dir.join(file_name))
+                .map_err(|e| format!("Cannot move {} to trash: {}", path.display(), e))?;
+        }
+    }
+
+    encryption::forget_key(&db_filename);
+    config.profiles.remove(index);
+    write_profiles_config(&app, &config)?;
+    // Opportunistic cleanup; a failure here must not fail the deletion
+    let _ = purge_expired_profiles(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_deleted_profiles(app: tauri::AppHandle) -> Result<Vec<DeletedProfile>, String> {
+    purge_expired_profiles(&app)?;
+    list_trash(&app)
+}
+
+/// Moves a deleted profile back out of the trash. It gets a new id and database filename
+/// if the original ones have been reused in the meantime.
+#[tauri::command]
+pub fn restore_deleted_profile(app: tauri::AppHandle, trash_id: String) -> Result<Profile, String> {
+    let entry = list_trash(&app)?
+        .into_iter()
+        .find(|e| e.trash_id == trash_id)
+        .ok_or_else(|| format!("Deleted profile not found: {}", trash_id))?;
+    let entry_dir = get_trash_dir(&app)?.join(&entry.trash_id);
+
+    let mut config = read_profiles_config(&app)?;
+    let mut profile = entry.profile.clone();
+    let db_dir = database::get_db_dir(&app)?;
+    let taken = config
+        .profiles
+        .iter()
+        .any(|p| p.id == profile.id || p.db_filename == profile.db_filename)
+        || db_dir.join(&profile.db_filename).exists();
+    if taken {
+        let (id, db_filename) = new_profile_ids();
+        profile.id = id;
+        profile.db_filename = db_filename;
+    }
+
+    let source = entry_dir.join(&entry.profile.db_filename);
+    let target = db_dir.join(&profile.db_filename);
+    for (from, to) in db_file_set(&source).iter().zip(db_file_set(&target)) {
+        if from.exists() {
+            fs::rename(from, &to).map_err(|e| format!("Cannot restore database: {}", e))?;
+        }
+    }
+
+    config.profiles.push(profile.clone());
+    write_profiles_config(&app, &config)?;
+    let _ = fs::remove_dir_all(&entry_dir);
+    Ok(profile)
+}
+
+/// Empties the trash right away instead of waiting for the retention period.
+#[tauri::command]
+pub fn purge_deleted_profile(app: tauri::AppHandle, trash_id: String) -> Result<(), String> {
+    let entry = list_trash(&app)?
+        .into_iter()
+        .find(|e| e.trash_id == trash_id)
+        .ok_or_else(|| format!("Deleted profile not found: {}", trash_id))?;
+    purge_entry(&app, &entry)
+}
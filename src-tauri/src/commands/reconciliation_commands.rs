@@ -0,0 +1,356 @@
+use chrono::{Duration, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::account_commands::{balance_at, validate_date, BALANCE_FILTER};
+use super::profile_commands::open_active_profile_db;
+use crate::money::Money;
+
+/// How long after the statement date a transaction may still belong to it (card payments
+/// and cheques are often posted a few days late).
+const LATE_POSTING_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reconciliation {
+    pub id: i64,
+    pub source_id: i64,
+    pub statement_date: String,
+    pub statement_balance: Money,
+    pub computed_balance: Money,
+    /// Transactions locked by this reconciliation.
+    pub transaction_count: i64,
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+/// A change to the books that would explain (part of) the difference with a statement.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationProposal {
+    /// `duplicate` (a second copy of a transaction, to delete), `late_posting` (dated
+    /// after the statement but possibly on it) or `missing` (no matching transaction;
+    /// `amount` is what the books lack).
+    pub kind: String,
+    pub transaction_id: Option<i64>,
+    pub date: String,
+    pub description: String,
+    pub amount: Money,
+    /// True when this change alone makes the balances match.
+    pub explains_difference: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationPreview {
+    pub source_id: i64,
+    pub statement_date: String,
+    pub statement_balance: Money,
+    pub computed_balance: Money,
+    /// Statement minus computed balance.
+    pub difference: Money,
+    pub matches: bool,
+    /// Statement date of the latest reconciliation of this account.
+    pub last_reconciled_date: Option<String>,
+    /// Transactions that completing the reconciliation would lock.
+    pub unreconciled_count: i64,
+    pub proposals: Vec<ReconciliationProposal>,
+}
+
+fn last_reconciled_date(conn: &Connection, source_id: i64) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT MAX(statement_date) FROM reconciliations WHERE source_id = ?1",
+        [source_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Cannot read reconciliations: {}", e))
+}
+
+fn unreconciled_count(conn: &Connection, source_id: i64, date: &str) -> Result<i64, String> {
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*)
+             FROM transactions t JOIN import_sources s ON s.id = t.source_id
+             WHERE {} AND t.date <= ?2 AND t.reconciliation_id IS NULL",
+            BALANCE_FILTER
+        ),
+        params![source_id, date],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Cannot read transactions: {}", e))
+}
+
+fn proposal_rows(
+    conn: &Connection,
+    sql: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<(i64, String, String, Money)>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Cannot read transactions: {}", e))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .map_err(|e| format!("Cannot read transactions: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read transactions: {}", e));
+    rows
+}
+
+/// Transactions whose deletion or inclusion would explain a difference of `difference`
+/// (statement minus books) at `statement_date`, the ones that explain it fully first.
+fn proposals(
+    conn: &Connection,
+    source_id: i64,
+    statement_date: &str,
+    difference: Money,
+) -> Result<Vec<ReconciliationProposal>, String> {
+    let mut proposals = Vec::new();
+
+    // Copies of a transaction (same day, amount and description) beyond the first; a
+    // reconciled copy counts as the first
+    let duplicates = proposal_rows(
+        conn,
+        &format!(
+            "SELECT id, date, description, amount FROM (
+                 SELECT t.id, t.date, t.description, t.amount, t.reconciliation_id,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY t.date, t.amount, LOWER(TRIM(t.description))
+                            ORDER BY t.reconciliation_id IS NULL, t.id
+                        ) AS copy
+                 FROM transactions t JOIN import_sources s ON s.id = t.source_id
+                 WHERE {} AND t.date <= ?2
+             )
+             WHERE copy > 1 AND reconciliation_id IS NULL
+             ORDER BY date, id",
+            BALANCE_FILTER
+        ),
+        &[&source_id, &statement_date],
+    )?;
+    for (id, date, description, amount) in duplicates {
+        proposals.push(ReconciliationProposal {
+            kind: "duplicate".to_string(),
+            transaction_id: Some(id),
+            date,
+            description,
+            // Deleting it lowers the books by its amount
+            explains_difference: (difference + amount).is_zero(),
+            amount,
+        });
+    }
+
+    let late_until = NaiveDate::parse_from_str(statement_date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}", statement_date))?
+        + Duration::days(LATE_POSTING_DAYS);
+    let late = proposal_rows(
+        conn,
+        &format!(
+            "SELECT t.id, t.date, t.description, t.amount
+             FROM transactions t JOIN import_sources s ON s.id = t.source_id
+             WHERE {} AND t.date > ?2 AND t.date <= ?3 AND t.reconciliation_id IS NULL
+             ORDER BY t.date, t.id",
+            BALANCE_FILTER
+        ),
+        &[
+            &source_id,
+            &statement_date,
+            &late_until.format("%Y-%m-%d").to_string(),
+        ],
+    )?;
+    for (id, date, description, amount) in late {
+        proposals.push(ReconciliationProposal {
+            kind: "late_posting".to_string(),
+            transaction_id: Some(id),
+            date,
+            description,
+            explains_difference: (difference - amount).is_zero(),
+            amount,
+        });
+    }
+
+    if !difference.is_zero() && !proposals.iter().any(|p| p.explains_difference) {
+        proposals.push(ReconciliationProposal {
+            kind: "missing".to_string(),
+            transaction_id: None,
+            date: statement_date.to_string(),
+            description: String::new(),
+            amount: difference,
+            explains_difference: true,
+        });
+    }
+
+    proposals.sort_by_key(|p| !p.explains_difference);
+    Ok(proposals)
+}
+
+fn preview(
+    conn: &Connection,
+    source_id: i64,
+    statement_date: &str,
+    statement_balance: Money,
+) -> Result<ReconciliationPreview, String> {
+    validate_date(statement_date)?;
+    let computed_balance = balance_at(conn, source_id, statement_date)?;
+    let difference = statement_balance - computed_balance;
+    Ok(ReconciliationPreview {
+        source_id,
+        statement_date: statement_date.to_string(),
+        statement_balance,
+        computed_balance,
+        difference,
+        matches: difference.is_zero(),
+        last_reconciled_date: last_reconciled_date(conn, source_id)?,
+        unreconciled_count: unreconciled_count(conn, source_id, statement_date)?,
+        proposals: proposals(conn, source_id, statement_date, difference)?,
+    })
+}
+
+fn read_reconciliations(
+    conn: &Connection,
+    where_sql: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<Reconciliation>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, source_id, statement_date, statement_balance, computed_balance,
+                    transaction_count, notes, created_at
+             FROM reconciliations WHERE {}
+             ORDER BY statement_date DESC",
+            where_sql
+        ))
+        .map_err(|e| format!("Cannot read reconciliations: {}", e))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok(Reconciliation {
+                id: row.get(0)?,
+                source_id: row.get(1)?,
+                statement_date: row.get(2)?,
+                statement_balance: row.get(3)?,
+                computed_balance: row.get(4)?,
+                transaction_count: row.get(5)?,
+                notes: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })
+        .map_err(|e| format!("Cannot read reconciliations: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read reconciliations: {}", e));
+    rows
+}
+
+/// Compares a statement closing balance with the books at `statement_date` and proposes
+/// duplicate or late transactions that would explain a difference. Nothing is written.
+#[tauri::command]
+pub fn preview_reconciliation(
+    app: tauri::AppHandle,
+    source_id: i64,
+    statement_date: String,
+    statement_balance: Money,
+) -> Result<ReconciliationPreview, String> {
+    let conn = open_active_profile_db(&app)?;
+    preview(&conn, source_id, &statement_date, statement_balance)
+}
+
+/// Records a reconciliation and locks the account's unreconciled transactions up to
+/// `statement_date`. Refused while the books differ from the statement, or when the
+/// date is not after the latest reconciliation.
+#[tauri::command]
+pub fn complete_reconciliation(
+    app: tauri::AppHandle,
+    source_id: i64,
+    statement_date: String,
+    statement_balance: Money,
+    notes: Option<String>,
+) -> Result<Reconciliation, String> {
+    let mut conn = open_active_profile_db(&app)?;
+    let preview = preview(&conn, source_id, &statement_date, statement_balance)?;
+    if let Some(last) = preview.last_reconciled_date.as_deref() {
+        if statement_date.as_str() <= last {
+            return Err(format!("Account already reconciled up to {}", last));
+        }
+    }
+    if !preview.matches {
+        return Err(format!(
+            "Statement balance differs from the computed balance by {}",
+            preview.difference
+        ));
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    tx.execute(
+        "INSERT INTO reconciliations
+           (source_id, statement_date, statement_balance, computed_balance, transaction_count, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            source_id,
+            statement_date,
+            statement_balance,
+            preview.computed_balance,
+            preview.unreconciled_count,
+            notes.filter(|n| !n.trim().is_empty()),
+        ],
+    )
+    .map_err(|e| format!("Cannot save reconciliation: {}", e))?;
+    let id = tx.last_insert_rowid();
+    tx.execute(
+        &format!(
+            "UPDATE transactions SET reconciliation_id = ?3
+             WHERE id IN (
+                 SELECT t.id FROM transactions t JOIN import_sources s ON s.id = t.source_id
+                 WHERE {} AND t.date <= ?2 AND t.reconciliation_id IS NULL
+             )",
+            BALANCE_FILTER
+        ),
+        params![source_id, statement_date, id],
+    )
+    .map_err(|e| format!("Cannot lock transactions: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Cannot save reconciliation: {}", e))?;
+
+    read_reconciliations(&conn, "id = ?1", &[&id])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Reconciliation not saved".to_string())
+}
+
+/// Reconciliation history of an account, newest first.
+#[tauri::command]
+pub fn get_reconciliations(
+    app: tauri::AppHandle,
+    source_id: i64,
+) -> Result<Vec<Reconciliation>, String> {
+    let conn = open_active_profile_db(&app)?;
+    read_reconciliations(&conn, "source_id = ?1", &[&source_id])
+}
+
+/// Removes the latest reconciliation of its account and unlocks its transactions.
+/// Older ones must stay, since later reconciliations start from them.
+#[tauri::command]
+pub fn undo_reconciliation(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    let mut conn = open_active_profile_db(&app)?;
+    let (source_id, statement_date): (i64, String) = conn
+        .query_row(
+            "SELECT source_id, statement_date FROM reconciliations WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Cannot read reconciliation: {}", e))?
+        .ok_or_else(|| format!("Reconciliation not found: {}", id))?;
+    if last_reconciled_date(&conn, source_id)?.as_deref() != Some(statement_date.as_str()) {
+        return Err("Only the latest reconciliation of an account can be undone".to_string());
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    tx.execute(
+        "UPDATE transactions SET reconciliation_id = NULL WHERE reconciliation_id = ?1",
+        [id],
+    )
+    .map_err(|e| format!("Cannot unlock transactions: {}", e))?;
+    tx.execute("DELETE FROM reconciliations WHERE id = ?1", [id])
+        .map_err(|e| format!("Cannot delete reconciliation: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Cannot delete reconciliation: {}", e))
+}
@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::profile_commands::{read_profiles_config, Profile};
+use super::snapshot_commands::{snapshot_db, REASON_PRE_MIGRATION, REASON_PRE_ROLLBACK};
+use crate::database::{self, encryption, migrations, schema_check};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSchemaCheck {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub db_filename: String,
+    pub schema_version: i64,
+    pub checksum_issues: Vec<schema_check::ChecksumIssue>,
+    pub drift: Vec<String>,
+    /// Encrypted and locked: it cannot be inspected until unlocked.
+    pub locked: bool,
+    /// Set when the database could not be inspected.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaCheckReport {
+    pub ok: bool,
+    pub latest_version: i64,
+    /// Differences between the consolidated schema and the migration chain.
+    pub consolidated_drift: Vec<String>,
+    pub profiles: Vec<ProfileSchemaCheck>,
+}
+
+/// Brings a profile database up to the latest schema version. Called by the frontend
+/// before it opens any profile; a database that does not exist yet is left untouched.
+#[tauri::command]
+pub fn migrate_profile_db(
+    app: tauri::AppHandle,
+    db_filename: String,
+) -> Result<migrations::MigrationReport, String> {
+    // New profiles are initialized before being added to profiles.json, so only make sure
+    // the name cannot point outside the database directory
+    let is_plain_name =
+        Path::new(&db_filename).file_name().and_then(|n| n.to_str()) == Some(db_filename.as_str());
+    if !is_plain_name || !db_filename.ends_with(".db") {
+        return Err(format!("Invalid database file name: {}", db_filename));
+    }
+
+    let path = database::get_db_path(&app, &db_filename)?;
+    if !path.exists() {
+        return Ok(migrations::MigrationReport {
+            db_filename,
+            from_version: 0,
+            to_version: 0,
+            latest_version: migrations::latest_version(),
+            baselined: false,
+            applied: Vec::new(),
+            error: None,
+        });
+    }
+
+    let mut conn = database::open_db(&path)?;
+    // Migrations can be destructive (table rebuilds, reseeding): keep a copy to go back to
+    if !migrations::pending_versions(&conn)?.is_empty() && !migrations::is_blank(&conn)? {
+        snapshot_db(&app, &db_filename, REASON_PRE_MIGRATION)
+            .map_err(|e| format!("Cannot snapshot the database before migrating it: {}", e))?;
+    }
+    migrations::migrate_db(&mut conn, &db_filename)
+}
+
+/// Rolls a profile database back to `target_version` using the down migrations, e.g.
+/// before reinstalling an older release (this build would migrate it up again on open).
+/// The rollback is refused, and nothing changes, if any step would lose data; `dry_run`
+/// only reports what would happen. The frontend must close the database first.
+#[tauri::command]
+pub fn rollback_profile_db(
+    app: tauri::AppHandle,
+    profile_id: String,
+    target_version: i64,
+    dry_run: bool,
+) -> Result<migrations::RollbackReport, String> {
+    let profile = read_profiles_config(&app)?
+        .profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile not found: {}", profile_id))?;
+    let mut conn = database::open_profile_db(&app, &profile.db_filename)?;
+
+    let preview = migrations::rollback_db(&mut conn, &profile.db_filename, target_version, true)?;
+    let refused = preview.steps.iter().any(|s| !s.data_loss.is_empty());
+    if dry_run || refused {
+        return Ok(preview);
+    }
+
+    snapshot_db(&app, &profile.db_filename, REASON_PRE_ROLLBACK)
+        .map_err(|e| format!("Cannot snapshot the database before rolling it back: {}", e))?;
+    migrations::rollback_db(&mut conn, &profile.db_filename, target_version, false)
+}
+
+fn check_profile(app: &tauri::AppHandle, profile: &Profile) -> ProfileSchemaCheck {
+    let mut check = ProfileSchemaCheck {
+        profile_id: profile.id.clone(),
+        profile_name: profile.name.clone(),
+        db_filename: profile.db_filename.clone(),
+        schema_version: 0,
+        checksum_issues: Vec::new(),
+        drift: Vec::new(),
+        locked: profile.db_key_salt.is_some() && !encryption::is_unlocked(&profile.db_filename),
+        error: None,
+    };
+    if check.locked {
+        return check;
+    }
+
+    let result = database::open_profile_db(app, &profile.db_filename).and_then(|conn| {
+        check.schema_version = database::get_schema_version(&conn)?;
+        check.checksum_issues = schema_check::check_checksums(&conn)?;
+        check.drift = schema_check::check_db_schema(&conn)?;
+        Ok(())
+    });
+    check.error = result.err();
+    check
+}
+
+fn build_schema_report(app: &tauri::AppHandle) -> Result<SchemaCheckReport, String> {
+    let consolidated_drift = schema_check::check_consolidated_schema()?;
+    let profiles: Vec<ProfileSchemaCheck> = read_profiles_config(app)?
+        .profiles
+        .iter()
+        .map(|profile| check_profile(app, profile))
+        .collect();
+    let ok = consolidated_drift.is_empty()
+        && profiles
+            .iter()
+            .all(|p| p.error.is_none() && p.checksum_issues.is_empty() && p.drift.is_empty());
+    Ok(SchemaCheckReport {
+        ok,
+        latest_version: migrations::latest_version(),
+        consolidated_drift,
+        profiles,
+    })
+}
+
+/// Verifies migration checksums and looks for schema drift in every profile database.
+#[tauri::command]
+pub fn check_schema(app: tauri::AppHandle) -> Result<SchemaCheckReport, String> {
+    build_schema_report(&app)
+}
+
+/// Result of the startup self-check, `None` until it has finished.
+static STARTUP_REPORT: Mutex<Option<Result<SchemaCheckReport, String>>> = Mutex::new(None);
+
+/// Startup self-check: runs `check_schema` in the background and keeps the report for
+/// `get_startup_schema_report`.
+pub fn run_schema_self_check(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let report = build_schema_report(&app);
+        *STARTUP_REPORT.lock().unwrap_or_else(|e| e.into_inner()) = Some(report);
+    });
+}
+
+/// Returns the report of the startup self-check, or `None` while it is still running.
+#[tauri::command]
+pub fn get_startup_schema_report() -> Result<Option<SchemaCheckReport>, String> {
+    STARTUP_REPORT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .transpose()
+}
@@ -0,0 +1,256 @@
+use chrono::{Local, NaiveDateTime};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::backup_commands::install_profile_db;
+use super::profile_commands::{read_profiles_config, Profile};
+use crate::database::{self, encryption};
+
+const SNAPSHOTS_DIR: &str = "snapshots";
+/// Snapshots kept per profile database and reason; older ones are deleted after each new
+/// snapshot. Counting per reason keeps e.g. a run of pre-update snapshots from pushing out
+/// the last pre-migration one.
+const MAX_SNAPSHOTS_PER_REASON: usize = 5;
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+pub const REASON_PRE_MIGRATION: &str = "pre-migration";
+pub const REASON_PRE_UPDATE: &str = "pre-update";
+pub const REASON_PRE_ROLLBACK: &str = "pre-rollback";
+pub const REASON_PRE_MERGE: &str = "pre-merge";
+pub const REASON_PRE_DATE_REPAIR: &str = "pre-date-repair";
+const REASON_PRE_RESTORE: &str = "pre-restore";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    pub file_name: String,
+    pub db_filename: String,
+    /// `pre-migration`, `pre-update`, `pre-rollback`, `pre-merge`, `pre-date-repair` or
+    /// `pre-restore`.
+    pub reason: String,
+    pub created_at: String,
+    pub schema_version: i64,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSnapshotResult {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub snapshot: Option<SnapshotInfo>,
+    pub error: Option<String>,
+}
+
+fn get_snapshots_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(database::get_db_dir(app)?.join(SNAPSHOTS_DIR))
+}
+
+fn db_stem(db_filename: &str) -> &str {
+    db_filename.strip_suffix(".db").unwrap_or(db_filename)
+}
+
+/// Snapshot files are named `<db stem>.<timestamp>.<reason>.v<schema version>.db`.
+fn parse_snapshot_name(db_filename: &str, path: &Path) -> Option<SnapshotInfo> {
+    let file_name = path.file_name()?.to_str()?.to_string();
+    let rest = file_name
+        .strip_prefix(db_stem(db_filename))?
+        .strip_prefix('.')?
+        .strip_suffix(".db")?;
+    let mut parts = rest.splitn(3, '.');
+    let timestamp = NaiveDateTime::parse_from_str(parts.next()?, TIMESTAMP_FORMAT).ok()?;
+    let reason = parts.next()?.to_string();
+    let schema_version = parts.next()?.strip_prefix('v')?.parse().ok()?;
+    let size_bytes = fs::metadata(path).ok()?.len();
+    Some(SnapshotInfo {
+        db_filename: db_filename.to_string(),
+        file_name,
+        reason,
+        created_at: timestamp.format("%Y-%m-%dT%H:%M:%S%.3f").to_string(),
+        schema_version,
+        size_bytes,
+    })
+}
+
+/// Lists the snapshots of a database, newest first.
+fn list_snapshots(app: &tauri::AppHandle, db_filename: &str) -> Result<Vec<SnapshotInfo>, String> {
+    let dir = get_snapshots_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots: Vec<SnapshotInfo> = fs::read_dir(&dir)
+        .map_err(|e| format!("Cannot read snapshots folder: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_snapshot_name(db_filename, &entry.path()))
+        .collect();
+    // Timestamps sort lexicographically in the stored format
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(snapshots)
+}
+
+fn prune_snapshots(app: &tauri::AppHandle, db_filename: &str) -> Result<(), String> {
+    let dir = get_snapshots_dir(app)?;
+    let mut kept: HashMap<String, usize> = HashMap::new();
+    for snapshot in list_snapshots(app, db_filename)? {
+        let count = kept.entry(snapshot.reason.clone()).or_default();
+        *count += 1;
+        if *count <= MAX_SNAPSHOTS_PER_REASON {
+            continue;
+        }
+        fs::remove_file(dir.join(&snapshot.file_name))
+            .map_err(|e| format!("Cannot delete old snapshot: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Deletes every snapshot of a database, e.g. when its profile is permanently deleted.
+pub(crate) fn delete_snapshots(
+    app: &tauri::AppHandle,
+    db_filename: &str,
+    secure: bool,
+) -> Result<(), String> {
+    let dir = get_snapshots_dir(app)?;
+    for snapshot in list_snapshots(app, db_filename)? {
+        let path = dir.join(&snapshot.file_name);
+        if secure {
+            database::secure_remove_file(&path)?;
+        } else {
+            fs::remove_file(&path).map_err(|e| format!("Cannot delete snapshot: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies a profile database into the snapshots folder, keeping the last few snapshots.
+/// Snapshots of encrypted databases stay encrypted with the same key.
+pub(crate) fn snapshot_db(
+    app: &tauri::AppHandle,
+    db_filename: &str,
+    reason: &str,
+) -> Result<SnapshotInfo, String> {
+    let conn = database::open_profile_db(app, db_filename)?;
+    let schema_version = database::get_schema_version(&conn)?;
+
+    let dir = get_snapshots_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Cannot create snapshots folder: {}", e))?;
+    let file_name = format!(
+        "{}.{}.{}.v{}.db",
+        db_stem(db_filename),
+        Local::now().format(TIMESTAMP_FORMAT),
+        reason,
+        schema_version
+    );
+    let dest = dir.join(&file_name);
+
+    let result = match encryption::connection_key(&conn) {
+        Some(key) => encryption::export_with_key(&conn, &dest, &key),
+        None => database::snapshot_into(&conn, &dest),
+    };
+    if let Err(e) = result {
+        let _ = fs::remove_file(&dest);
+        return Err(e);
+    }
+    drop(conn);
+
+    let info = parse_snapshot_name(db_filename, &dest)
+        .ok_or_else(|| "Cannot read back the snapshot".to_string())?;
+    prune_snapshots(app, db_filename)?;
+    Ok(info)
+}
+
+fn find_profile(app: &tauri::AppHandle, profile_id: &str) -> Result<Profile, String> {
+    read_profiles_config(app)?
+        .profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile not found: {}", profile_id))
+}
+
+/// Snapshots every profile database, e.g. before installing an update. Profiles that
+/// cannot be snapshotted (locked, missing database) are reported, not fatal.
+#[tauri::command]
+pub fn snapshot_all_profiles(
+    app: tauri::AppHandle,
+    reason: Option<String>,
+) -> Result<Vec<ProfileSnapshotResult>, String> {
+    let reason = reason.unwrap_or_else(|| REASON_PRE_UPDATE.to_string());
+    if reason != REASON_PRE_UPDATE && reason != REASON_PRE_MIGRATION {
+        return Err(format!("Invalid snapshot reason: {}", reason));
+    }
+
+    let config = read_profiles_config(&app)?;
+    Ok(config
+        .profiles
+        .iter()
+        .map(|profile| {
+            let result = if profile.db_key_salt.is_some()
+                && !encryption::is_unlocked(&profile.db_filename)
+            {
+                Err("The profile database is encrypted and locked".to_string())
+            } else {
+                snapshot_db(&app, &profile.db_filename, &reason)
+            };
+            ProfileSnapshotResult {
+                profile_id: profile.id.clone(),
+                profile_name: profile.name.clone(),
+                snapshot: result.as_ref().ok().cloned(),
+                error: result.err(),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn list_profile_snapshots(
+    app: tauri::AppHandle,
+    profile_id: String,
+) -> Result<Vec<SnapshotInfo>, String> {
+    let profile = find_profile(&app, &profile_id)?;
+    list_snapshots(&app, &profile.db_filename)
+}
+
+/// Restores a profile database from one of its snapshots, by default the most recent
+/// one taken before a migration or an update. The current database is snapshotted first,
+/// so the restore can itself be undone. The frontend must close the database first.
+#[tauri::command]
+pub fn restore_previous_version(
+    app: tauri::AppHandle,
+    profile_id: String,
+    snapshot_file: Option<String>,
+) -> Result<SnapshotInfo, String> {
+    let profile = find_profile(&app, &profile_id)?;
+    let snapshots = list_snapshots(&app, &profile.db_filename)?;
+    let snapshot = match snapshot_file {
+        Some(name) => snapshots.into_iter().find(|s| s.file_name == name),
+        None => snapshots
+            .into_iter()
+            .find(|s| s.reason != REASON_PRE_RESTORE),
+    }
+    .ok_or_else(|| "No snapshot available for this profile".to_string())?;
+    let snapshot_path = get_snapshots_dir(&app)?.join(&snapshot.file_name);
+
+    // install_profile_db expects a plaintext copy and re-encrypts it for encrypted profiles
+    let plain_path =
+        database::get_db_dir(&app)?.join(format!("{}.snapshot-restore-tmp", profile.db_filename));
+    let result = (|| {
+        if encryption::is_file_encrypted(&snapshot_path)? {
+            let key = encryption::key_for_db(&profile.db_filename).ok_or_else(|| {
+                "Unlock the encrypted profile before restoring a snapshot".to_string()
+            })?;
+            let conn = encryption::open_with_key(&snapshot_path, &key)
+                .map_err(|_| "The snapshot was encrypted with a previous password".to_string())?;
+            encryption::export_with_key(&conn, &plain_path, "")?;
+        } else {
+            database::snapshot_into(&database::open_db(&snapshot_path)?, &plain_path)?;
+        }
+        fs::read(&plain_path).map_err(|e| format!("Cannot read snapshot: {}", e))
+    })();
+    let _ = fs::remove_file(&plain_path);
+    let db_bytes = result?;
+
+    if database::get_db_path(&app, &profile.db_filename)?.exists() {
+        snapshot_db(&app, &profile.db_filename, REASON_PRE_RESTORE)?;
+    }
+    install_profile_db(&app, &profile.db_filename, &db_bytes)?;
+    Ok(snapshot)
+}
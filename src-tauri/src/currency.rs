@@ -0,0 +1,437 @@
+//! Exchange rates and the conversion of transaction amounts to the profile's base
+//! currency (the `currency` preference).
+//!
+//! A rate row reads "1 `from_currency` = `rate` `to_currency` on `rate_date`". Rates are
+//! used on and after their date until the next one, so an ECB file (no rates on weekends
+//! and holidays) covers every day. A pair without a direct rate is converted through its
+//! inverse or through a currency both sides have rates against (EUR for ECB files).
+//!
+//! Each transaction stores its converted amount in `base_amount`, so that reports and
+//! budgets can keep aggregating in SQL. It is NULL when no rate covers the transaction,
+//! and readers then fall back to `amount`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::money::Money;
+
+/// Base currency of profiles that never set one.
+pub const DEFAULT_BASE_CURRENCY: &str = "CAD";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRate {
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate_date: String,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingRate {
+    pub currency: String,
+    pub transaction_count: i64,
+    pub first_date: String,
+    pub last_date: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionSummary {
+    pub base_currency: String,
+    /// Transactions whose base amount was computed.
+    pub converted: i64,
+    /// Currencies with transactions no rate covers, which reports count unconverted.
+    pub missing: Vec<MissingRate>,
+}
+
+pub fn is_currency_code(value: &str) -> bool {
+    value.len() == 3 && value.chars().all(|c| c.is_ascii_uppercase())
+}
+
+pub fn validate_currency(value: &str) -> Result<(), String> {
+    if is_currency_code(value) {
+        Ok(())
+    } else {
+        Err(format!("Invalid currency code: {}", value))
+    }
+}
+
+pub fn base_currency(conn: &Connection) -> Result<String, String> {
+    attached_base_currency(conn, "main")
+}
+
+/// Base currency of the database attached as `schema`.
+pub fn attached_base_currency(conn: &Connection, schema: &str) -> Result<String, String> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT value FROM {}.user_preferences WHERE key = 'currency'",
+                schema
+            ),
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Cannot read base currency: {}", e))?;
+    Ok(value
+        .filter(|v| is_currency_code(v))
+        .unwrap_or_else(|| DEFAULT_BASE_CURRENCY.to_string()))
+}
+
+fn parse_rate(value: &str) -> Option<f64> {
+    let value = value.trim().replace(',', ".");
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|r| r.is_finite() && *r > 0.0)
+}
+
+fn parse_date(value: &str, line: usize) -> Result<String, String> {
+    let value = value.trim();
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| format!("Line {}: invalid date {}", line, value))
+}
+
+/// Parses a rates CSV against `reference`, the currency the rates are quoted for:
+///
+/// - wide, as in the ECB historical file: `Date,USD,JPY,...` with one row per day and
+///   one column per currency (1 `reference` = value);
+/// - long: `date,currency,rate` columns, in any order.
+///
+/// Both use `YYYY-MM-DD` dates. Values such as `N/A` or empty cells are skipped. A `;`
+/// separator (with decimal commas) is accepted too.
+pub fn parse_rates_csv(content: &str, reference: &str) -> Result<Vec<ExchangeRate>, String> {
+    validate_currency(reference)?;
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_start_matches('\u{feff}').trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let (_, header) = lines.next().ok_or("The rates file is empty")?;
+    let separator = if !header.contains(',') && header.contains(';') {
+        ';'
+    } else {
+        ','
+    };
+    let columns: Vec<String> = header
+        .split(separator)
+        .map(|c| c.trim().trim_matches('"').to_string())
+        .collect();
+    let position = |name: &str| columns.iter().position(|c| c.eq_ignore_ascii_case(name));
+    let date_column = position("date").ok_or("The rates file has no Date column")?;
+
+    let mut rates = Vec::new();
+    let mut push = |to: &str, date: &str, rate: f64| {
+        if to != reference {
+            rates.push(ExchangeRate {
+                from_currency: reference.to_string(),
+                to_currency: to.to_string(),
+                rate_date: date.to_string(),
+                rate,
+            });
+        }
+    };
+
+    if let (Some(currency_column), Some(rate_column)) = (position("currency"), position("rate")) {
+        for (line, text) in lines {
+            let cells: Vec<&str> = text
+                .split(separator)
+                .map(|c| c.trim().trim_matches('"'))
+                .collect();
+            let currency = cells
+                .get(currency_column)
+                .copied()
+                .unwrap_or_default()
+                .to_uppercase();
+            let Some(rate) = cells.get(rate_column).and_then(|v| parse_rate(v)) else {
+                continue;
+            };
+            if !is_currency_code(&currency) {
+                return Err(format!("Line {}: invalid currency {}", line, currency));
+            }
+            let date = parse_date(cells.get(date_column).copied().unwrap_or_default(), line)?;
+            push(&currency, &date, rate);
+        }
+    } else {
+        let currencies: Vec<(usize, String)> = columns
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| *i != date_column && is_currency_code(c))
+            .map(|(i, c)| (i, c.clone()))
+            .collect();
+        if currencies.is_empty() {
+            return Err("The rates file has no currency columns".to_string());
+        }
+        for (line, text) in lines {
+            let cells: Vec<&str> = text
+                .split(separator)
+                .map(|c| c.trim().trim_matches('"'))
+                .collect();
+            let date = parse_date(cells.get(date_column).copied().unwrap_or_default(), line)?;
+            for (index, currency) in &currencies {
+                if let Some(rate) = cells.get(*index).and_then(|v| parse_rate(v)) {
+                    push(currency, &date, rate);
+                }
+            }
+        }
+    }
+
+    if rates.is_empty() {
+        return Err("The rates file contains no rates".to_string());
+    }
+    Ok(rates)
+}
+
+/// All stored rates, indexed by pair and date.
+pub struct RateTable {
+    pairs: HashMap<(String, String), BTreeMap<String, f64>>,
+    currencies: BTreeSet<String>,
+}
+
+impl RateTable {
+    pub fn load(conn: &Connection) -> Result<Self, String> {
+        let mut stmt = conn
+            .prepare("SELECT from_currency, to_currency, rate_date, rate FROM exchange_rates")
+            .map_err(|e| format!("Cannot read exchange rates: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                ))
+            })
+            .map_err(|e| format!("Cannot read exchange rates: {}", e))?;
+
+        let mut table = RateTable {
+            pairs: HashMap::new(),
+            currencies: BTreeSet::new(),
+        };
+        for row in rows {
+            let (from, to, date, rate) =
+                row.map_err(|e| format!("Cannot read exchange rates: {}", e))?;
+            table.currencies.insert(from.clone());
+            table.currencies.insert(to.clone());
+            table
+                .pairs
+                .entry((from, to))
+                .or_default()
+                .insert(date, rate);
+        }
+        Ok(table)
+    }
+
+    /// Latest direct rate on or before `date`, or the inverse of the reverse pair.
+    fn pair_rate(&self, from: &str, to: &str, date: &str) -> Option<f64> {
+        let latest = |a: &str, b: &str| {
+            self.pairs
+                .get(&(a.to_string(), b.to_string()))
+                .and_then(|rates| rates.range(..=date.to_string()).next_back())
+                .map(|(_, rate)| *rate)
+        };
+        latest(from, to).or_else(|| latest(to, from).map(|rate| 1.0 / rate))
+    }
+
+    /// Units of `to` for one unit of `from` on `date`.
+    pub fn rate(&self, from: &str, to: &str, date: &str) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.pair_rate(from, to, date).or_else(|| {
+            self.currencies
+                .iter()
+                .filter(|via| via.as_str() != from && via.as_str() != to)
+                .find_map(|via| {
+                    Some(self.pair_rate(from, via, date)? * self.pair_rate(via, to, date)?)
+                })
+        })
+    }
+}
+
+/// Recomputes `base_amount`, for every transaction or only for those without one (new
+/// imports and split parts). A transaction's currency is its own, else its account's,
+/// else the base currency.
+pub fn refresh_base_amounts(
+    conn: &Connection,
+    only_missing: bool,
+) -> Result<ConversionSummary, String> {
+    let base = base_currency(conn)?;
+    let rates = RateTable::load(conn)?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT t.id, t.date, t.amount, COALESCE(t.currency, s.currency, ?1)
+             FROM transactions t LEFT JOIN import_sources s ON s.id = t.source_id
+             {}
+             ORDER BY t.date",
+            if only_missing {
+                "WHERE t.base_amount IS NULL"
+            } else {
+                ""
+            }
+        ))
+        .map_err(|e| format!("Cannot read transactions: {}", e))?;
+    let rows = stmt
+        .query_map([&base], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Money>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read transactions: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read transactions: {}", e))?;
+    drop(stmt);
+
+    // Callers already inside a transaction (merges) get the updates in theirs
+    let tx = if conn.is_autocommit() {
+        Some(
+            conn.unchecked_transaction()
+                .map_err(|e| format!("Cannot start transaction: {}", e))?,
+        )
+    } else {
+        None
+    };
+    let mut converted = 0;
+    let mut missing: BTreeMap<String, MissingRate> = BTreeMap::new();
+    {
+        let mut update = conn
+            .prepare("UPDATE transactions SET base_amount = ?1 WHERE id = ?2")
+            .map_err(|e| format!("Cannot convert transactions: {}", e))?;
+        for (id, date, amount, currency) in rows {
+            let base_amount = rates
+                .rate(&currency, &base, &date)
+                .map(|rate| amount.convert(rate));
+            if base_amount.is_some() {
+                converted += 1;
+            } else {
+                let entry = missing
+                    .entry(currency.clone())
+                    .or_insert_with(|| MissingRate {
+                        currency,
+                        transaction_count: 0,
+                        first_date: date.clone(),
+                        last_date: date.clone(),
+                    });
+                entry.transaction_count += 1;
+                entry.last_date = date;
+            }
+            update
+                .execute(params![base_amount, id])
+                .map_err(|e| format!("Cannot convert transactions: {}", e))?;
+        }
+    }
+    if let Some(tx) = tx {
+        tx.commit()
+            .map_err(|e| format!("Cannot convert transactions: {}", e))?;
+    }
+
+    Ok(ConversionSummary {
+        base_currency: base,
+        converted,
+        missing: missing.into_values().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::migrated_test_db;
+
+    fn rate(from: &str, to: &str, date: &str, rate: f64) -> ExchangeRate {
+        ExchangeRate {
+            from_currency: from.to_string(),
+            to_currency: to.to_string(),
+            rate_date: date.to_string(),
+            rate,
+        }
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("no rate");
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn parses_the_wide_ecb_layout() {
+        let content = "\u{feff}Date,USD,JPY,EUR,\n\
+                       2025-01-03,1.0299,N/A,1,\n\
+                       2025-01-02,1.0321,163.06,1,\n";
+        assert_eq!(
+            parse_rates_csv(content, "EUR").unwrap(),
+            vec![
+                rate("EUR", "USD", "2025-01-03", 1.0299),
+                rate("EUR", "USD", "2025-01-02", 1.0321),
+                rate("EUR", "JPY", "2025-01-02", 163.06),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_the_long_layout_in_any_column_order() {
+        let content = "rate,currency,date\n0.72,usd,2025-01-02\n,EUR,2025-01-02\n";
+        assert_eq!(
+            parse_rates_csv(content, "CAD").unwrap(),
+            vec![rate("CAD", "USD", "2025-01-02", 0.72)]
+        );
+        let err = parse_rates_csv("date,currency,rate\n2025-01-02,US,0.72\n", "CAD");
+        assert_eq!(err.unwrap_err(), "Line 2: invalid currency US");
+    }
+
+    #[test]
+    fn parses_semicolons_with_decimal_commas() {
+        let content = "\"Date\";\"USD\"\n\"2025-01-02\";\"1,0321\"\n";
+        assert_eq!(
+            parse_rates_csv(content, "EUR").unwrap(),
+            vec![rate("EUR", "USD", "2025-01-02", 1.0321)]
+        );
+    }
+
+    #[test]
+    fn rejects_unusable_rate_files() {
+        assert!(parse_rates_csv("", "EUR").is_err());
+        assert!(parse_rates_csv("Day,USD\n2025-01-02,1.03\n", "EUR").is_err());
+        assert!(parse_rates_csv("Date,Note\n2025-01-02,x\n", "EUR").is_err());
+        assert!(parse_rates_csv("Date,USD\n2025-01-02,N/A\n", "EUR").is_err());
+        assert_eq!(
+            parse_rates_csv("Date,USD\n02/01/2025,1.03\n", "EUR").unwrap_err(),
+            "Line 2: invalid date 02/01/2025"
+        );
+        assert!(parse_rates_csv("Date,USD\n2025-01-02,1.03\n", "eur").is_err());
+    }
+
+    #[test]
+    fn rates_use_the_latest_date_the_inverse_and_cross_pairs() {
+        let conn = migrated_test_db();
+        conn.execute_batch(
+            "INSERT INTO exchange_rates (from_currency, to_currency, rate_date, rate)
+             VALUES ('EUR', 'USD', '2025-01-02', 1.25),
+                    ('EUR', 'USD', '2025-02-03', 1.5),
+                    ('EUR', 'CAD', '2025-01-02', 1.6);",
+        )
+        .unwrap();
+        let table = RateTable::load(&conn).unwrap();
+
+        assert_eq!(table.rate("JPY", "JPY", "2025-01-02"), Some(1.0));
+        assert_eq!(table.rate("EUR", "USD", "2025-01-01"), None);
+        // Weekends and holidays use the last rate before them
+        assert_close(table.rate("EUR", "USD", "2025-01-04"), 1.25);
+        assert_close(table.rate("EUR", "USD", "2025-02-10"), 1.5);
+        assert_close(table.rate("USD", "EUR", "2025-01-04"), 0.8);
+        // No USD/CAD rate: through EUR
+        assert_close(table.rate("USD", "CAD", "2025-01-04"), 0.8 * 1.6);
+        assert_close(table.rate("CAD", "USD", "2025-01-04"), 1.25 / 1.6);
+        assert_eq!(table.rate("USD", "GBP", "2025-01-04"), None);
+    }
+}
@@ -0,0 +1,147 @@
+use rusqlite::{Connection, OpenFlags};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Directory holding the profile databases.
+///
+/// `tauri-plugin-sql` resolves `sqlite:<file>` URLs against the app *config* dir, which
+/// differs from the app data dir (where `profiles.json` lives) on Linux and macOS.
+pub fn get_db_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map_err(|e| format!("Cannot get app config dir: {}", e))
+}
+
+pub fn get_db_path(app: &tauri::AppHandle, db_filename: &str) -> Result<PathBuf, String> {
+    Ok(get_db_dir(app)?.join(db_filename))
+}
+
+pub fn open_db(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open_with_flags(
+        path,
+        // CREATE is needed for ATTACH to create export files, not for the main database
+        OpenFlags::SQLITE_OPEN_READ_WRITE
+            | OpenFlags::SQLITE_OPEN_CREATE
+            | OpenFlags::SQLITE_OPEN_URI,
+    )
+    .map_err(|e| format!("Cannot open database {}: {}", path.display(), e))?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|e| format!("Cannot configure database: {}", e))?;
+    Ok(conn)
+}
+
+pub fn open_profile_db(app: &tauri::AppHandle, db_filename: &str) -> Result<Connection, String> {
+    let path = get_db_path(app, db_filename)?;
+    if !path.exists() {
+        return Err(format!("Database file not found: {}", db_filename));
+    }
+    open_db(&path)
+}
+
+/// Writes a consistent, compacted copy of the database to `dest` with `VACUUM INTO`.
+/// Safe to run while the app holds other connections to the same file. Snapshots of
+/// encrypted databases are written in plaintext so that they can be restored elsewhere;
+/// callers are expected to encrypt them (backups are always wrapped in SREF or age).
+pub fn snapshot_into(conn: &Connection, dest: &Path) -> Result<(), String> {
+    if super::encryption::connection_key(conn).is_some() {
+        return super::encryption::export_with_key(conn, dest, "");
+    }
+    if dest.exists() {
+        std::fs::remove_file(dest)
+            .map_err(|e| format!("Cannot replace snapshot file: {}", e))?;
+    }
+    conn.execute("VACUUM INTO ?1", [dest.to_string_lossy()])
+        .map_err(|e| format!("Cannot snapshot database: {}", e))?;
+    Ok(())
+}
+
+/// Returns the highest successfully applied migration version, or 0 for databases that
+/// were never migrated (e.g. created from the consolidated schema).
+pub fn get_schema_version(conn: &Connection) -> Result<i64, String> {
+    let has_table: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Cannot read schema version: {}", e))?;
+    if !has_table {
+        return Ok(0);
+    }
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Cannot read schema version: {}", e))
+}
+
+/// Runs `PRAGMA integrity_check` and fails with the reported problems, if any.
+pub fn check_integrity(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA integrity_check")
+        .map_err(|e| format!("Integrity check failed: {}", e))?;
+    let problems: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Integrity check failed: {}", e))?
+        .filter_map(Result::ok)
+        .filter(|line| line != "ok")
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Database is corrupted: {}", problems.join("; ")))
+    }
+}
+
+/// The `-wal`, `-shm` and `-journal` files SQLite may keep next to a database file.
+pub fn sidecar_paths(db_path: &Path) -> Vec<PathBuf> {
+    ["-wal", "-shm", "-journal"]
+        .iter()
+        .map(|suffix| {
+            let mut name = db_path.as_os_str().to_owned();
+            name.push(suffix);
+            PathBuf::from(name)
+        })
+        .collect()
+}
+
+/// Removes the `-wal`, `-shm` and `-journal` files left next to a database file.
+pub fn remove_sidecar_files(db_path: &Path) -> Result<(), String> {
+    for sidecar in sidecar_paths(db_path) {
+        if sidecar.exists() {
+            std::fs::remove_file(&sidecar)
+                .map_err(|e| format!("Cannot delete {}: {}", sidecar.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Overwrites a file with random bytes, flushes it to disk, then deletes it. This defeats
+/// casual recovery on HDDs; SSD wear levelling and copy-on-write filesystems may still
+/// keep old blocks around.
+pub fn secure_remove_file(path: &Path) -> Result<(), String> {
+    use rand::RngCore;
+    use std::io::Write;
+
+    let len = std::fs::metadata(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+        .len();
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let mut chunk = vec![0u8; 64 * 1024];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(chunk.len() as u64) as usize;
+        rand::rngs::OsRng.fill_bytes(&mut chunk[..n]);
+        file.write_all(&chunk[..n])
+            .map_err(|e| format!("Cannot overwrite {}: {}", path.display(), e))?;
+        remaining -= n as u64;
+    }
+    file.sync_all()
+        .map_err(|e| format!("Cannot overwrite {}: {}", path.display(), e))?;
+    drop(file);
+    std::fs::remove_file(path).map_err(|e| format!("Cannot delete {}: {}", path.display(), e))
+}
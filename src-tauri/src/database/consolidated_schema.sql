@@ -1,6 +1,7 @@
 -- Consolidated schema for new profile databases
 -- This file bakes in the base schema + all migrations (v3-v6)
 -- Used ONLY for initializing new profile databases (not for the default profile)
+-- Later migrations are applied on open (see CONSOLIDATED_SCHEMA_VERSION in migrations.rs)
 
 CREATE TABLE IF NOT EXISTS import_sources (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
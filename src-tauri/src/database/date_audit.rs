@@ -0,0 +1,275 @@
+//! Finds stored dates that are not ISO `YYYY-MM-DD`, which `strftime` and date range
+//! queries silently skip, and rewrites them in that form.
+//!
+//! A transaction date is read with its account's `date_format` first. Other values are
+//! read year first, then day first or month first when only one of them gives a valid
+//! date (or both give the same one); values both could read differently, or none can,
+//! are reported and left as they are.
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::currency;
+use crate::dates::{self, DateFormat, FieldOrder};
+
+/// Date columns of a profile database, as table and column.
+const DATE_COLUMNS: &[(&str, &str)] = &[
+    ("transactions", "date"),
+    ("adjustments", "date"),
+    ("adjustment_exceptions", "occurrence_date"),
+    ("adjustment_exceptions", "override_date"),
+    ("import_sources", "opening_balance_date"),
+    ("statement_balances", "balance_date"),
+    ("reconciliations", "statement_date"),
+    ("exchange_rates", "rate_date"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DateFix {
+    pub table: String,
+    pub column: String,
+    pub row_id: i64,
+    pub value: String,
+    /// The ISO date written, or `None` when the value was left unchanged.
+    pub normalized: Option<String>,
+    /// Why the value could not be normalized.
+    pub problem: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DateAuditReport {
+    pub dry_run: bool,
+    /// Non-empty dates checked.
+    pub checked: i64,
+    /// Every non-ISO date found, normalized or not.
+    pub fixes: Vec<DateFix>,
+    /// True when the normalized dates were committed, i.e. not a dry run.
+    pub applied: bool,
+}
+
+/// The ISO form of a stored `value`, read with `hint` (an import format) when it is one.
+fn normalize(value: &str, hint: Option<&DateFormat>) -> Result<String, String> {
+    if let Some(date) = hint.and_then(|format| dates::to_iso(value, format).ok()) {
+        return Ok(date);
+    }
+    let separated = value.trim().contains(['/', '-', '.']);
+    let read = |order| {
+        dates::to_iso(
+            value,
+            &DateFormat {
+                order,
+                compact: !separated,
+            },
+        )
+    };
+    if let Ok(date) = read(FieldOrder::YearMonthDay) {
+        return Ok(date);
+    }
+    match (
+        read(FieldOrder::DayMonthYear),
+        read(FieldOrder::MonthDayYear),
+    ) {
+        (Ok(a), Ok(b)) if a != b => Err(format!(
+            "Ambiguous date: {} could be {} or {}",
+            value.trim(),
+            a,
+            b
+        )),
+        (Ok(date), _) | (_, Ok(date)) => Ok(date),
+        (Err(e), _) => Err(e),
+    }
+}
+
+/// Checks every date column, and normalizes the non-ISO values in a transaction that is
+/// only committed when `dry_run` is false. A value that cannot be written back (a
+/// reconciled transaction, or a date another row already uses where dates are unique) is
+/// reported with the database error.
+pub fn audit_dates(conn: &mut Connection, dry_run: bool) -> Result<DateAuditReport, String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    let mut checked = 0;
+    let mut fixes = Vec::new();
+    let mut conversions_stale = false;
+
+    for (table, column) in DATE_COLUMNS {
+        // DATE columns have numeric affinity: `20250106` is stored as an integer
+        let sql = if *table == "transactions" {
+            "SELECT t.id, CAST(t.date AS TEXT), s.date_format
+             FROM transactions t LEFT JOIN import_sources s ON s.id = t.source_id
+             WHERE t.date IS NOT NULL AND t.date != ''"
+                .to_string()
+        } else {
+            format!(
+                "SELECT id, CAST({column} AS TEXT), NULL FROM {table}
+                 WHERE {column} IS NOT NULL AND {column} != ''"
+            )
+        };
+        let rows = tx
+            .prepare(&sql)
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| format!("Cannot read {}.{}: {}", table, column, e))?;
+
+        let update = format!("UPDATE {table} SET {column} = ?1 WHERE id = ?2");
+        for (id, value, format) in rows {
+            checked += 1;
+            if dates::is_iso(&value) {
+                continue;
+            }
+            let hint = format.and_then(|f| DateFormat::parse(&f).ok());
+            let result = normalize(&value, hint.as_ref()).and_then(|date| {
+                // Constraint errors only abort the statement, not the transaction
+                tx.execute(&update, params![date, id])
+                    .map(|_| date)
+                    .map_err(|e| format!("Cannot save {}: {}", value.trim(), e))
+            });
+            if result.is_ok() && matches!(*table, "transactions" | "exchange_rates") {
+                conversions_stale = true;
+            }
+            let (normalized, problem) = match result {
+                Ok(date) => (Some(date), None),
+                Err(e) => (None, Some(e)),
+            };
+            fixes.push(DateFix {
+                table: table.to_string(),
+                column: column.to_string(),
+                row_id: id,
+                value,
+                normalized,
+                problem,
+            });
+        }
+    }
+
+    // Transactions are converted at the rate of their date
+    if conversions_stale {
+        currency::refresh_base_amounts(&tx, false)?;
+    }
+    if !dry_run {
+        tx.commit()
+            .map_err(|e| format!("Cannot save normalized dates: {}", e))?;
+    }
+    // Otherwise the transaction is rolled back when dropped
+    Ok(DateAuditReport {
+        dry_run,
+        checked,
+        fixes,
+        applied: !dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::migrated_test_db;
+
+    /// Transactions with dates in several forms; the account stores month-first dates
+    /// and transaction 6 is reconciled.
+    fn fixture() -> Connection {
+        let conn = migrated_test_db();
+        conn.execute_batch(
+            "INSERT INTO import_sources (id, name, column_mapping, date_format)
+             VALUES (1, 'US', '{}', 'MM/DD/YYYY');
+             INSERT INTO reconciliations (id, source_id, statement_date, statement_balance, computed_balance)
+             VALUES (1, 1, '2025-01-31', 0, 0);
+             INSERT INTO transactions (id, date, description, amount, source_id, reconciliation_id)
+             VALUES (1, '01/02/2025', 'account format', -100, 1, NULL),
+                    (2, '13/01/2025', 'day first only', -100, NULL, NULL),
+                    (3, '05/06/2025', 'ambiguous', -100, NULL, NULL),
+                    (4, '2025-01-06', 'iso', -100, NULL, NULL),
+                    (5, '20250107', 'compact', -100, NULL, NULL),
+                    (6, '01/15/2025', 'reconciled', -100, 1, 1),
+                    (7, '31/02/2025', 'impossible', -100, NULL, NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn stored_date(conn: &Connection, id: i64) -> String {
+        conn.query_row(
+            "SELECT CAST(date AS TEXT) FROM transactions WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn fix(report: &DateAuditReport, row_id: i64) -> &DateFix {
+        report
+            .fixes
+            .iter()
+            .find(|f| f.table == "transactions" && f.row_id == row_id)
+            .unwrap_or_else(|| panic!("no fix for row {} in {:#?}", row_id, report.fixes))
+    }
+
+    #[test]
+    fn normalizes_with_the_account_format_first() {
+        let dmy = DateFormat::parse("DD/MM/YYYY").unwrap();
+        assert_eq!(normalize("01/02/2025", Some(&dmy)).unwrap(), "2025-02-01");
+        assert_eq!(
+            normalize("01/02/2025", None).unwrap_err(),
+            "Ambiguous date: 01/02/2025 could be 2025-02-01 or 2025-01-02"
+        );
+        // A hint that cannot read the value falls back to guessing
+        assert_eq!(normalize("2025/01/06", Some(&dmy)).unwrap(), "2025-01-06");
+        assert_eq!(normalize("12/25/25", None).unwrap(), "2025-12-25");
+        assert_eq!(normalize("07/07/2025", None).unwrap(), "2025-07-07");
+        assert!(normalize("someday", None).is_err());
+    }
+
+    #[test]
+    fn reports_without_writing_on_a_dry_run() {
+        let mut conn = fixture();
+        let report = audit_dates(&mut conn, true).unwrap();
+        assert!(!report.applied);
+        assert_eq!(fix(&report, 1).normalized.as_deref(), Some("2025-01-02"));
+        assert_eq!(fix(&report, 2).normalized.as_deref(), Some("2025-01-13"));
+        assert_eq!(fix(&report, 5).normalized.as_deref(), Some("2025-01-07"));
+        assert!(report.fixes.iter().all(|f| f.row_id != 4));
+        assert_eq!(stored_date(&conn, 1), "01/02/2025");
+    }
+
+    #[test]
+    fn skips_reconciled_and_unreadable_rows() {
+        let mut conn = fixture();
+        let report = audit_dates(&mut conn, false).unwrap();
+        assert!(report.applied);
+        assert_eq!(stored_date(&conn, 1), "2025-01-02");
+        assert_eq!(stored_date(&conn, 2), "2025-01-13");
+        assert_eq!(stored_date(&conn, 5), "2025-01-07");
+
+        // The lock on reconciled transactions aborts only that row's update
+        let reconciled = fix(&report, 6);
+        assert!(reconciled.normalized.is_none());
+        assert!(
+            reconciled
+                .problem
+                .as_deref()
+                .unwrap()
+                .contains("reconciled and locked"),
+            "{:?}",
+            reconciled.problem
+        );
+        assert_eq!(stored_date(&conn, 6), "01/15/2025");
+
+        assert!(fix(&report, 3)
+            .problem
+            .as_deref()
+            .unwrap()
+            .starts_with("Ambiguous date"));
+        assert_eq!(
+            fix(&report, 7).problem.as_deref(),
+            Some("Impossible date: 31/02/2025")
+        );
+        assert_eq!(stored_date(&conn, 3), "05/06/2025");
+    }
+}
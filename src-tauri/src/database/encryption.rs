@@ -0,0 +1,134 @@
+//! SQLCipher support for profile databases.
+//!
+//! Profile databases are opened both by `tauri-plugin-sql` (frontend queries) and by our
+//! own commands. Since the plugin offers no hook to run `PRAGMA key`, we register an SQLite
+//! auto-extension: it runs inside every `sqlite3_open` of the process and applies the key
+//! of any unlocked profile whose file is being opened.
+
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::path::Path;
+use std::sync::{Mutex, Once};
+
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Raw SQLCipher keys (`x'<hex>'`) of unlocked profiles, by database file name.
+static UNLOCKED_KEYS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+static INSTALL_HOOK: Once = Once::new();
+
+/// Formats a 32-byte key as an SQLCipher raw key, which skips SQLCipher's own PBKDF2
+/// since the key already comes out of Argon2.
+pub fn raw_key(key: &[u8; 32]) -> String {
+    let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("x'{}'", hex)
+}
+
+fn key_for_path(path: &str) -> Option<String> {
+    let file_name = Path::new(path).file_name()?.to_string_lossy().to_string();
+    let keys = UNLOCKED_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    keys.as_ref()?.get(&file_name).cloned()
+}
+
+pub fn register_key(db_filename: &str, raw_key: String) {
+    let mut keys = UNLOCKED_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    keys.get_or_insert_with(HashMap::new)
+        .insert(db_filename.to_string(), raw_key);
+}
+
+pub fn forget_key(db_filename: &str) {
+    let mut keys = UNLOCKED_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(keys) = keys.as_mut() {
+        keys.remove(db_filename);
+    }
+}
+
+pub fn key_for_db(db_filename: &str) -> Option<String> {
+    let keys = UNLOCKED_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    keys.as_ref()?.get(db_filename).cloned()
+}
+
+pub fn is_unlocked(db_filename: &str) -> bool {
+    key_for_db(db_filename).is_some()
+}
+
+/// Returns the key registered for the file a connection was opened on, if any.
+pub fn connection_key(conn: &Connection) -> Option<String> {
+    conn.path().and_then(key_for_path)
+}
+
+unsafe extern "C" fn apply_registered_key(
+    db: *mut libsqlite3_sys::sqlite3,
+    _err: *mut *mut c_char,
+    _api: *const libsqlite3_sys::sqlite3_api_routines,
+) -> c_int {
+    let filename = libsqlite3_sys::sqlite3_db_filename(db, c"main".as_ptr());
+    if filename.is_null() {
+        return libsqlite3_sys::SQLITE_OK;
+    }
+    let path = CStr::from_ptr(filename).to_string_lossy();
+    if let Some(key) = key_for_path(&path) {
+        libsqlite3_sys::sqlite3_key(db, key.as_ptr().cast(), key.len() as c_int);
+    }
+    libsqlite3_sys::SQLITE_OK
+}
+
+/// Registers the key-applying auto-extension. Must run before any database is opened.
+pub fn install_key_hook() {
+    INSTALL_HOOK.call_once(|| unsafe {
+        libsqlite3_sys::sqlite3_auto_extension(Some(apply_registered_key));
+    });
+}
+
+/// True when the file does not carry the plaintext SQLite header, i.e. it is encrypted.
+pub fn is_file_encrypted(path: &Path) -> Result<bool, String> {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("Cannot open database file: {}", e))?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header != PLAINTEXT_HEADER),
+        // An empty or tiny file is a fresh database, not an encrypted one
+        Err(_) => Ok(false),
+    }
+}
+
+/// Copies the database behind `conn` into a new file at `dest`, encrypted with `key`
+/// (an SQLCipher raw key) or in plaintext when `key` is empty.
+pub fn export_with_key(conn: &Connection, dest: &Path, key: &str) -> Result<(), String> {
+    if dest.exists() {
+        std::fs::remove_file(dest).map_err(|e| format!("Cannot replace {}: {}", dest.display(), e))?;
+    }
+    conn.execute(
+        "ATTACH DATABASE ?1 AS export KEY ?2",
+        [dest.to_string_lossy().as_ref(), key],
+    )
+    .map_err(|e| format!("Cannot create export database: {}", e))?;
+
+    let result = conn
+        .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+        .map_err(|e| format!("Cannot export database: {}", e));
+
+    conn.execute("DETACH DATABASE export", [])
+        .map_err(|e| format!("Cannot detach export database: {}", e))?;
+    result
+}
+
+/// Opens `path` with the given raw key and checks that the key actually decrypts it.
+pub fn open_with_key(path: &Path, key: &str) -> Result<Connection, String> {
+    let conn = super::open_db(path)?;
+    match connection_key(&conn) {
+        // The auto-extension already keyed the connection with the unlocked key
+        Some(registered) if registered == key => {}
+        Some(_) => return Err("Wrong password or corrupted database".to_string()),
+        None => conn
+            .pragma_update(None, "key", key)
+            .map_err(|e| format!("Cannot set database key: {}", e))?,
+    }
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map_err(|_| "Wrong password or corrupted database".to_string())?;
+    Ok(conn)
+}
@@ -0,0 +1,1404 @@
+//! Merging one profile database into another.
+//!
+//! Reference data is matched by natural key: categories by their name path from the root
+//! (e.g. `Logement > Assurances`), suppliers, import sources and templates by name,
+//! imported files by source and filename, keywords by text and category. Transactions
+//! already present in the target (same date, description and amount, as in the import
+//! duplicate detection) are skipped. When both sides disagree, the target value is kept
+//! and the difference is reported as a conflict.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::money::Money;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeConflict {
+    /// `category_type`, `supplier_category`, `source_config`, `file_hash`,
+    /// `keyword_category` or `budget_amount`.
+    pub kind: String,
+    pub item: String,
+    pub detail: String,
+}
+
+/// Rows of the source found in the target (or skipped as duplicates) vs. copied over.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeCounts {
+    pub matched: usize,
+    pub created: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeReport {
+    pub dry_run: bool,
+    pub categories: MergeCounts,
+    pub suppliers: MergeCounts,
+    pub import_sources: MergeCounts,
+    pub imported_files: MergeCounts,
+    pub keywords: MergeCounts,
+    pub transactions: MergeCounts,
+    pub adjustments: MergeCounts,
+    pub budget_entries: MergeCounts,
+    pub budget_templates: MergeCounts,
+    pub import_config_templates: MergeCounts,
+    pub conflicts: Vec<MergeConflict>,
+    /// True when the merge was committed, i.e. not a dry run.
+    pub applied: bool,
+}
+
+impl MergeReport {
+    fn conflict(&mut self, kind: &str, item: &str, detail: String) {
+        self.conflicts.push(MergeConflict {
+            kind: kind.to_string(),
+            item: item.to_string(),
+            detail,
+        });
+    }
+}
+
+/// Source id → target id.
+type IdMap = HashMap<i64, i64>;
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("Merge failed: {}", e)
+}
+
+fn query_rows<T>(
+    conn: &Connection,
+    sql: &str,
+    map: impl FnMut(&Row) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, String> {
+    let mut stmt = conn.prepare(sql).map_err(db_err)?;
+    let rows = stmt
+        .query_map([], map)
+        .map_err(db_err)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_err)?;
+    Ok(rows)
+}
+
+fn remap(ids: &IdMap, id: Option<i64>) -> Option<i64> {
+    id.and_then(|id| ids.get(&id).copied())
+}
+
+struct CategoryRow {
+    id: i64,
+    name: String,
+    parent_id: Option<i64>,
+    color: Option<String>,
+    icon: Option<String>,
+    category_type: String,
+    is_active: bool,
+    is_inputable: bool,
+    sort_order: i64,
+    budget_rollover: bool,
+}
+
+fn read_categories(conn: &Connection) -> Result<Vec<CategoryRow>, String> {
+    query_rows(
+        conn,
+        "SELECT id, name, parent_id, color, icon, type, is_active, is_inputable, sort_order,
+                budget_rollover
+         FROM categories",
+        |row| {
+            Ok(CategoryRow {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                parent_id: row.get(2)?,
+                color: row.get(3)?,
+                icon: row.get(4)?,
+                category_type: row.get(5)?,
+                is_active: row.get(6)?,
+                is_inputable: row.get(7)?,
+                sort_order: row.get(8)?,
+                budget_rollover: row.get(9)?,
+            })
+        },
+    )
+}
+
+/// Names from the root down to each category, e.g. `["Logement", "Assurances"]`.
+fn category_paths(rows: &[CategoryRow]) -> HashMap<i64, Vec<String>> {
+    let by_id: HashMap<i64, &CategoryRow> = rows.iter().map(|c| (c.id, c)).collect();
+    rows.iter()
+        .map(|category| {
+            let mut path = vec![category.name.trim().to_string()];
+            let mut parent = category.parent_id;
+            // The depth limit guards against parent cycles in damaged databases
+            while let Some(p) = parent
+                .and_then(|id| by_id.get(&id))
+                .filter(|_| path.len() < 16)
+            {
+                path.insert(0, p.name.trim().to_string());
+                parent = p.parent_id;
+            }
+            (category.id, path)
+        })
+        .collect()
+}
+
+fn path_key(path: &[String]) -> String {
+    path.join("\u{1f}").to_lowercase()
+}
+
+fn merge_categories(
+    target: &Connection,
+    source: &Connection,
+    report: &mut MergeReport,
+) -> Result<IdMap, String> {
+    let target_rows = read_categories(target)?;
+    let target_paths = category_paths(&target_rows);
+    let mut by_path: HashMap<String, (i64, String)> = target_rows
+        .iter()
+        .map(|c| {
+            (
+                path_key(&target_paths[&c.id]),
+                (c.id, c.category_type.clone()),
+            )
+        })
+        .collect();
+
+    let source_rows = read_categories(source)?;
+    let source_paths = category_paths(&source_rows);
+    let mut ordered: Vec<&CategoryRow> = source_rows.iter().collect();
+    // Parents before children, so a new child can point to its (possibly new) parent
+    ordered.sort_by_key(|c| (source_paths[&c.id].len(), c.id));
+
+    let mut ids = IdMap::new();
+    for category in ordered {
+        let path = &source_paths[&category.id];
+        let key = path_key(path);
+        if let Some((target_id, target_type)) = by_path.get(&key) {
+            if *target_type != category.category_type {
+                report.conflict(
+                    "category_type",
+                    &path.join(" > "),
+                    format!(
+                        "{} in the source, {} here",
+                        category.category_type, target_type
+                    ),
+                );
+            }
+            ids.insert(category.id, *target_id);
+            report.categories.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO categories
+                 (name, parent_id, color, icon, type, is_active, is_inputable, sort_order,
+                  budget_rollover)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    category.name,
+                    remap(&ids, category.parent_id),
+                    category.color,
+                    category.icon,
+                    category.category_type,
+                    category.is_active,
+                    category.is_inputable,
+                    category.sort_order,
+                    category.budget_rollover
+                ],
+            )
+            .map_err(db_err)?;
+        let new_id = target.last_insert_rowid();
+        by_path.insert(key, (new_id, category.category_type.clone()));
+        ids.insert(category.id, new_id);
+        report.categories.created += 1;
+    }
+    Ok(ids)
+}
+
+fn merge_suppliers(
+    target: &Connection,
+    source: &Connection,
+    categories: &IdMap,
+    report: &mut MergeReport,
+) -> Result<IdMap, String> {
+    let existing: HashMap<String, (i64, Option<i64>)> = query_rows(
+        target,
+        "SELECT name, id, category_id FROM suppliers",
+        |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))),
+    )?
+    .into_iter()
+    .collect();
+    let rows = query_rows(
+        source,
+        "SELECT id, name, normalized_name, category_id, is_active FROM suppliers",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, bool>(4)?,
+            ))
+        },
+    )?;
+
+    let mut ids = IdMap::new();
+    for (id, name, normalized_name, category_id, is_active) in rows {
+        let category_id = remap(categories, category_id);
+        if let Some((target_id, target_category)) = existing.get(&name) {
+            if category_id.is_some() && target_category.is_some() && category_id != *target_category
+            {
+                report.conflict(
+                    "supplier_category",
+                    &name,
+                    "The supplier is assigned to a different category".to_string(),
+                );
+            }
+            ids.insert(id, *target_id);
+            report.suppliers.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO suppliers (name, normalized_name, category_id, is_active)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![name, normalized_name, category_id, is_active],
+            )
+            .map_err(db_err)?;
+        ids.insert(id, target.last_insert_rowid());
+        report.suppliers.created += 1;
+    }
+    Ok(ids)
+}
+
+fn merge_import_sources(
+    target: &Connection,
+    source: &Connection,
+    report: &mut MergeReport,
+) -> Result<IdMap, String> {
+    let existing: HashMap<String, (i64, String)> = query_rows(
+        target,
+        "SELECT name, id, column_mapping FROM import_sources",
+        |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))),
+    )?
+    .into_iter()
+    .collect();
+    let rows = query_rows(
+        source,
+        "SELECT id, name, description, date_format, delimiter, encoding, column_mapping,
+                skip_lines, has_header, account_type, opening_balance, opening_balance_date,
+                currency, is_closed
+         FROM import_sources",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, i64>(7)?,
+                row.get::<_, bool>(8)?,
+                (
+                    row.get::<_, String>(9)?,
+                    row.get::<_, Money>(10)?,
+                    row.get::<_, Option<String>>(11)?,
+                    row.get::<_, String>(12)?,
+                    row.get::<_, bool>(13)?,
+                ),
+            ))
+        },
+    )?;
+
+    let mut ids = IdMap::new();
+    for (id, name, description, date_format, delimiter, encoding, mapping, skip, header, account) in
+        rows
+    {
+        let (account_type, opening_balance, opening_date, currency, is_closed) = account;
+        if let Some((target_id, target_mapping)) = existing.get(&name) {
+            if *target_mapping != mapping {
+                report.conflict(
+                    "source_config",
+                    &name,
+                    "The import source has a different column mapping".to_string(),
+                );
+            }
+            let (target_opening, target_opening_date): (Money, Option<String>) = target
+                .query_row(
+                    "SELECT opening_balance, opening_balance_date FROM import_sources WHERE id = ?1",
+                    [target_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(db_err)?;
+            if target_opening != opening_balance || target_opening_date != opening_date {
+                report.conflict(
+                    "opening_balance",
+                    &name,
+                    "The account has a different opening balance; the target's is kept".to_string(),
+                );
+            }
+            ids.insert(id, *target_id);
+            report.import_sources.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO import_sources (name, description, date_format, delimiter, encoding,
+                 column_mapping, skip_lines, has_header, account_type, opening_balance,
+                 opening_balance_date, currency, is_closed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    name,
+                    description,
+                    date_format,
+                    delimiter,
+                    encoding,
+                    mapping,
+                    skip,
+                    header,
+                    account_type,
+                    opening_balance,
+                    opening_date,
+                    currency,
+                    is_closed
+                ],
+            )
+            .map_err(db_err)?;
+        ids.insert(id, target.last_insert_rowid());
+        report.import_sources.created += 1;
+    }
+    Ok(ids)
+}
+
+fn merge_imported_files(
+    target: &Connection,
+    source: &Connection,
+    sources: &IdMap,
+    report: &mut MergeReport,
+) -> Result<IdMap, String> {
+    let existing: HashMap<(i64, String), (i64, String)> = query_rows(
+        target,
+        "SELECT source_id, filename, id, file_hash FROM imported_files",
+        |row| Ok(((row.get(0)?, row.get(1)?), (row.get(2)?, row.get(3)?))),
+    )?
+    .into_iter()
+    .collect();
+    let rows = query_rows(
+        source,
+        "SELECT id, source_id, filename, file_hash, import_date, row_count, status, notes
+         FROM imported_files",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, i64>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<String>>(7)?,
+            ))
+        },
+    )?;
+
+    let mut ids = IdMap::new();
+    for (id, source_id, filename, file_hash, import_date, row_count, status, notes) in rows {
+        let Some(source_id) = sources.get(&source_id).copied() else {
+            continue;
+        };
+        if let Some((target_id, target_hash)) = existing.get(&(source_id, filename.clone())) {
+            if *target_hash != file_hash {
+                report.conflict(
+                    "file_hash",
+                    &filename,
+                    "A different file with the same name was imported".to_string(),
+                );
+            }
+            ids.insert(id, *target_id);
+            report.imported_files.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO imported_files
+                 (source_id, filename, file_hash, import_date, row_count, status, notes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    source_id,
+                    filename,
+                    file_hash,
+                    import_date,
+                    row_count,
+                    status,
+                    notes
+                ],
+            )
+            .map_err(db_err)?;
+        ids.insert(id, target.last_insert_rowid());
+        report.imported_files.created += 1;
+    }
+    Ok(ids)
+}
+
+/// Statement balances follow their account; one already recorded for the same date and
+/// origin is kept.
+fn merge_statement_balances(
+    target: &Connection,
+    source: &Connection,
+    sources: &IdMap,
+    files: &IdMap,
+) -> Result<(), String> {
+    let rows = query_rows(
+        source,
+        "SELECT source_id, file_id, balance_date, balance, currency, origin, created_at
+         FROM statement_balances",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<i64>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Money>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        },
+    )?;
+    for (source_id, file_id, balance_date, balance, currency, origin, created_at) in rows {
+        let Some(source_id) = sources.get(&source_id).copied() else {
+            continue;
+        };
+        target
+            .execute(
+                "INSERT OR IGNORE INTO statement_balances
+                 (source_id, file_id, balance_date, balance, currency, origin, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    source_id,
+                    remap(files, file_id),
+                    balance_date,
+                    balance,
+                    currency,
+                    origin,
+                    created_at
+                ],
+            )
+            .map_err(db_err)?;
+    }
+    Ok(())
+}
+
+fn merge_exchange_rates(target: &Connection, source: &Connection) -> Result<(), String> {
+    let rows = query_rows(
+        source,
+        "SELECT from_currency, to_currency, rate_date, rate, source FROM exchange_rates",
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        },
+    )?;
+    // Rates already in the target win
+    for (from, to, date, rate, origin) in rows {
+        target
+            .execute(
+                "INSERT OR IGNORE INTO exchange_rates
+                 (from_currency, to_currency, rate_date, rate, source)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![from, to, date, rate, origin],
+            )
+            .map_err(db_err)?;
+    }
+    Ok(())
+}
+
+fn merge_keywords(
+    target: &Connection,
+    source: &Connection,
+    categories: &IdMap,
+    suppliers: &IdMap,
+    report: &mut MergeReport,
+) -> Result<(), String> {
+    let mut existing: HashMap<String, Vec<i64>> = HashMap::new();
+    for (keyword, category_id) in
+        query_rows(target, "SELECT keyword, category_id FROM keywords", |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?
+    {
+        existing.entry(keyword).or_default().push(category_id);
+    }
+    let rows = query_rows(
+        source,
+        "SELECT keyword, category_id, supplier_id, priority, is_active FROM keywords",
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, bool>(4)?,
+            ))
+        },
+    )?;
+
+    for (keyword, category_id, supplier_id, priority, is_active) in rows {
+        let Some(category_id) = categories.get(&category_id).copied() else {
+            continue;
+        };
+        match existing.get(&keyword) {
+            Some(targets) if targets.contains(&category_id) => report.keywords.matched += 1,
+            // Two categories for the same keyword would make categorization ambiguous
+            Some(_) => report.conflict(
+                "keyword_category",
+                &keyword,
+                "The keyword points to a different category".to_string(),
+            ),
+            None => {
+                target
+                    .execute(
+                        "INSERT INTO keywords (keyword, category_id, supplier_id, priority, is_active)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![
+                            keyword,
+                            category_id,
+                            remap(suppliers, supplier_id),
+                            priority,
+                            is_active
+                        ],
+                    )
+                    .map_err(db_err)?;
+                existing.insert(keyword, vec![category_id]);
+                report.keywords.created += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Key used for duplicate detection: date, description and amount.
+fn transaction_key(date: &str, description: &str, amount: Money) -> (String, String, Money) {
+    (date.to_string(), description.to_string(), amount)
+}
+
+struct ForeignKeys<'a> {
+    categories: &'a IdMap,
+    suppliers: &'a IdMap,
+    sources: &'a IdMap,
+    files: &'a IdMap,
+}
+
+fn merge_transactions(
+    target: &Connection,
+    source: &Connection,
+    keys: &ForeignKeys,
+    report: &mut MergeReport,
+) -> Result<(), String> {
+    // Counted, so that two identical purchases in the source are only matched against
+    // as many copies in the target
+    let mut existing: HashMap<(String, String, Money), usize> = HashMap::new();
+    for (date, description, amount) in query_rows(
+        target,
+        "SELECT date, description, amount FROM transactions",
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Money>(2)?,
+            ))
+        },
+    )? {
+        *existing
+            .entry(transaction_key(&date, &description, amount))
+            .or_default() += 1;
+    }
+
+    let rows = query_rows(
+        source,
+        "SELECT id, date, description, amount, category_id, supplier_id, source_id, file_id,
+                original_description, notes, is_manually_categorized, is_split,
+                parent_transaction_id, currency
+         FROM transactions
+         ORDER BY parent_transaction_id IS NOT NULL, id",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Money>(3)?,
+                [
+                    row.get::<_, Option<i64>>(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                    row.get(7)?,
+                ],
+                row.get::<_, Option<String>>(8)?,
+                row.get::<_, Option<String>>(9)?,
+                row.get::<_, bool>(10)?,
+                row.get::<_, bool>(11)?,
+                row.get::<_, Option<i64>>(12)?,
+                row.get::<_, Option<String>>(13)?,
+            ))
+        },
+    )?;
+
+    let mut ids = IdMap::new();
+    for (id, date, description, amount, fks, original, notes, manual, split, parent, currency) in
+        rows
+    {
+        let parent_id = match parent {
+            // Split parts follow their parent: a duplicate parent already has them
+            Some(parent) => match ids.get(&parent) {
+                Some(new_parent) => Some(*new_parent),
+                None => {
+                    report.transactions.matched += 1;
+                    continue;
+                }
+            },
+            None => {
+                let key = transaction_key(&date, &description, amount);
+                if let Some(count) = existing.get_mut(&key).filter(|c| **c > 0) {
+                    *count -= 1;
+                    report.transactions.matched += 1;
+                    continue;
+                }
+                None
+            }
+        };
+        let [category_id, supplier_id, source_id, file_id] = fks;
+        target
+            .execute(
+                "INSERT INTO transactions (date, description, amount, category_id, supplier_id,
+                 source_id, file_id, original_description, notes, is_manually_categorized,
+                 is_split, parent_transaction_id, currency)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    date,
+                    description,
+                    amount,
+                    remap(keys.categories, category_id),
+                    remap(keys.suppliers, supplier_id),
+                    remap(keys.sources, source_id),
+                    remap(keys.files, file_id),
+                    original,
+                    notes,
+                    manual,
+                    split,
+                    parent_id,
+                    currency
+                ],
+            )
+            .map_err(db_err)?;
+        ids.insert(id, target.last_insert_rowid());
+        report.transactions.created += 1;
+    }
+    Ok(())
+}
+
+fn merge_adjustments(
+    target: &Connection,
+    source: &Connection,
+    categories: &IdMap,
+    report: &mut MergeReport,
+) -> Result<(), String> {
+    let existing: Vec<(String, String)> =
+        query_rows(target, "SELECT name, date FROM adjustments", |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+    let rows = query_rows(
+        source,
+        "SELECT id, name, description, date, is_recurring, recurrence_rule FROM adjustments",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, bool>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        },
+    )?;
+    let entries = query_rows(
+        source,
+        "SELECT adjustment_id, category_id, amount, description FROM adjustment_entries",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Money>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        },
+    )?;
+    let exceptions = query_rows(
+        source,
+        "SELECT id, adjustment_id, occurrence_date, is_cancelled, override_date
+         FROM adjustment_exceptions",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, bool>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        },
+    )?;
+    let exception_entries = query_rows(
+        source,
+        "SELECT exception_id, category_id, amount, description FROM adjustment_exception_entries",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Money>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        },
+    )?;
+
+    for (id, name, description, date, is_recurring, rule) in rows {
+        if existing.contains(&(name.clone(), date.clone())) {
+            report.adjustments.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO adjustments (name, description, date, is_recurring, recurrence_rule)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![name, description, date, is_recurring, rule],
+            )
+            .map_err(db_err)?;
+        let adjustment_id = target.last_insert_rowid();
+        for (_, category_id, amount, entry_description) in entries.iter().filter(|e| e.0 == id) {
+            let Some(category_id) = categories.get(category_id) else {
+                continue;
+            };
+            target
+                .execute(
+                    "INSERT INTO adjustment_entries (adjustment_id, category_id, amount, description)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![adjustment_id, category_id, amount, entry_description],
+                )
+                .map_err(db_err)?;
+        }
+        // Skipped or modified occurrences of a recurring adjustment
+        for (exception_id, _, occurrence_date, is_cancelled, override_date) in
+            exceptions.iter().filter(|e| e.1 == id)
+        {
+            target
+                .execute(
+                    "INSERT INTO adjustment_exceptions
+                     (adjustment_id, occurrence_date, is_cancelled, override_date)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![adjustment_id, occurrence_date, is_cancelled, override_date],
+                )
+                .map_err(db_err)?;
+            let new_exception_id = target.last_insert_rowid();
+            for (_, category_id, amount, entry_description) in
+                exception_entries.iter().filter(|e| e.0 == *exception_id)
+            {
+                let Some(category_id) = categories.get(category_id) else {
+                    continue;
+                };
+                target
+                    .execute(
+                        "INSERT INTO adjustment_exception_entries
+                         (exception_id, category_id, amount, description)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        params![new_exception_id, category_id, amount, entry_description],
+                    )
+                    .map_err(db_err)?;
+            }
+        }
+        report.adjustments.created += 1;
+    }
+    Ok(())
+}
+
+fn merge_budget_entries(
+    target: &Connection,
+    source: &Connection,
+    categories: &IdMap,
+    category_names: &HashMap<i64, Vec<String>>,
+    report: &mut MergeReport,
+) -> Result<(), String> {
+    let existing: HashMap<(i64, i64, i64), Money> = query_rows(
+        target,
+        "SELECT category_id, year, month, amount FROM budget_entries",
+        |row| Ok(((row.get(0)?, row.get(1)?, row.get(2)?), row.get(3)?)),
+    )?
+    .into_iter()
+    .collect();
+    let rows = query_rows(
+        source,
+        "SELECT category_id, year, month, amount, notes FROM budget_entries",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Money>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        },
+    )?;
+
+    for (source_category, year, month, amount, notes) in rows {
+        let Some(category_id) = categories.get(&source_category).copied() else {
+            continue;
+        };
+        if let Some(target_amount) = existing.get(&(category_id, year, month)) {
+            if *target_amount != amount {
+                let name = category_names
+                    .get(&source_category)
+                    .map(|p| p.join(" > "))
+                    .unwrap_or_default();
+                report.conflict(
+                    "budget_amount",
+                    &format!("{} {}-{:02}", name, year, month),
+                    format!("{} in the source, {} here", amount, target_amount),
+                );
+            }
+            report.budget_entries.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO budget_entries (category_id, year, month, amount, notes)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![category_id, year, month, amount, notes],
+            )
+            .map_err(db_err)?;
+        report.budget_entries.created += 1;
+    }
+    Ok(())
+}
+
+/// Copies envelope transfers between categories that exist in the target, skipping those
+/// already there (same month, categories, amount and time).
+fn merge_budget_transfers(
+    target: &Connection,
+    source: &Connection,
+    categories: &IdMap,
+) -> Result<(), String> {
+    let rows = query_rows(
+        source,
+        "SELECT id, year, month, from_category_id, to_category_id, amount, notes,
+                reversed_transfer_id, created_at
+         FROM budget_transfers ORDER BY id",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                (row.get::<_, i64>(1)?, row.get::<_, i64>(2)?),
+                (row.get::<_, i64>(3)?, row.get::<_, i64>(4)?),
+                row.get::<_, Money>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, Option<i64>>(7)?,
+                row.get::<_, String>(8)?,
+            ))
+        },
+    )?;
+    let mut transfers: IdMap = HashMap::new();
+    for (id, (year, month), (from, to), amount, notes, reversed, created_at) in rows {
+        let (Some(from), Some(to)) = (categories.get(&from), categories.get(&to)) else {
+            continue;
+        };
+        let existing: Option<i64> = target
+            .query_row(
+                "SELECT id FROM budget_transfers
+                 WHERE year = ?1 AND month = ?2 AND from_category_id = ?3
+                   AND to_category_id = ?4 AND amount = ?5 AND created_at = ?6",
+                params![year, month, from, to, amount, created_at],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)?;
+        if let Some(existing) = existing {
+            transfers.insert(id, existing);
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO budget_transfers (year, month, from_category_id, to_category_id,
+                     amount, notes, reversed_transfer_id, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    year,
+                    month,
+                    from,
+                    to,
+                    amount,
+                    notes,
+                    reversed.and_then(|r| transfers.get(&r)),
+                    created_at
+                ],
+            )
+            .map_err(db_err)?;
+        transfers.insert(id, target.last_insert_rowid());
+    }
+    Ok(())
+}
+
+fn merge_templates(
+    target: &Connection,
+    source: &Connection,
+    categories: &IdMap,
+    report: &mut MergeReport,
+) -> Result<(), String> {
+    let existing: Vec<String> = query_rows(target, "SELECT name FROM budget_templates", |row| {
+        row.get(0)
+    })?;
+    let templates = query_rows(
+        source,
+        "SELECT id, name, description FROM budget_templates",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        },
+    )?;
+    let entries = query_rows(
+        source,
+        "SELECT template_id, category_id, amount FROM budget_template_entries",
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Money>(2)?,
+            ))
+        },
+    )?;
+    for (id, name, description) in templates {
+        if existing.contains(&name) {
+            report.budget_templates.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO budget_templates (name, description) VALUES (?1, ?2)",
+                params![name, description],
+            )
+            .map_err(db_err)?;
+        let template_id = target.last_insert_rowid();
+        for (_, category_id, amount) in entries.iter().filter(|e| e.0 == id) {
+            if let Some(category_id) = categories.get(category_id) {
+                target
+                    .execute(
+                        "INSERT OR IGNORE INTO budget_template_entries (template_id, category_id, amount)
+                         VALUES (?1, ?2, ?3)",
+                        params![template_id, category_id, amount],
+                    )
+                    .map_err(db_err)?;
+            }
+        }
+        report.budget_templates.created += 1;
+    }
+
+    let existing: Vec<String> =
+        query_rows(target, "SELECT name FROM import_config_templates", |row| {
+            row.get(0)
+        })?;
+    let rows = query_rows(
+        source,
+        "SELECT name, delimiter, encoding, date_format, skip_lines, has_header, column_mapping,
+                amount_mode, sign_convention
+         FROM import_config_templates",
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, bool>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, String>(7)?,
+                row.get::<_, String>(8)?,
+            ))
+        },
+    )?;
+    for (name, delimiter, encoding, date_format, skip, header, mapping, amount_mode, sign) in rows {
+        if existing.contains(&name) {
+            report.import_config_templates.matched += 1;
+            continue;
+        }
+        target
+            .execute(
+                "INSERT INTO import_config_templates (name, delimiter, encoding, date_format,
+                 skip_lines, has_header, column_mapping, amount_mode, sign_convention)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    name,
+                    delimiter,
+                    encoding,
+                    date_format,
+                    skip,
+                    header,
+                    mapping,
+                    amount_mode,
+                    sign
+                ],
+            )
+            .map_err(db_err)?;
+        report.import_config_templates.created += 1;
+    }
+    Ok(())
+}
+
+/// Merges `source` into `target` in a single transaction. Both databases must be at the
+/// same schema version. With `dry_run` every step still runs, so the report (counts and
+/// conflicts) is exact, but the transaction is rolled back.
+pub fn merge_db(
+    target: &mut Connection,
+    source: &Connection,
+    dry_run: bool,
+) -> Result<MergeReport, String> {
+    let target_version = super::get_schema_version(target)?;
+    let source_version = super::get_schema_version(source)?;
+    if target_version != source_version {
+        return Err(format!(
+            "Both profiles must be at the same schema version (source {}, target {})",
+            source_version, target_version
+        ));
+    }
+
+    let tx = target
+        .transaction()
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    let mut report = MergeReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    let categories = merge_categories(&tx, source, &mut report)?;
+    let suppliers = merge_suppliers(&tx, source, &categories, &mut report)?;
+    let sources = merge_import_sources(&tx, source, &mut report)?;
+    let files = merge_imported_files(&tx, source, &sources, &mut report)?;
+    merge_statement_balances(&tx, source, &sources, &files)?;
+    merge_keywords(&tx, source, &categories, &suppliers, &mut report)?;
+    let keys = ForeignKeys {
+        categories: &categories,
+        suppliers: &suppliers,
+        sources: &sources,
+        files: &files,
+    };
+    merge_exchange_rates(&tx, source)?;
+    merge_transactions(&tx, source, &keys, &mut report)?;
+    // Merged transactions are converted to the target's base currency
+    crate::currency::refresh_base_amounts(&tx, true)?;
+    merge_adjustments(&tx, source, &categories, &mut report)?;
+    let source_paths = category_paths(&read_categories(source)?);
+    merge_budget_entries(&tx, source, &categories, &source_paths, &mut report)?;
+    merge_budget_transfers(&tx, source, &categories)?;
+    merge_templates(&tx, source, &categories, &mut report)?;
+
+    report.applied = !dry_run;
+    if report.applied {
+        tx.commit()
+            .map_err(|e| format!("Cannot commit merge: {}", e))?;
+    }
+    // Otherwise the transaction is rolled back when dropped
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::migrated_test_db;
+
+    fn add_category(conn: &Connection, name: &str, parent_id: Option<i64>) -> i64 {
+        conn.execute(
+            "INSERT INTO categories (name, parent_id, type) VALUES (?1, ?2, 'expense')",
+            params![name, parent_id],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn add_transaction(
+        conn: &Connection,
+        date: &str,
+        description: &str,
+        amount: f64,
+        parent_id: Option<i64>,
+    ) -> i64 {
+        conn.execute(
+            "INSERT INTO transactions (date, description, amount, is_split, parent_transaction_id)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                date,
+                description,
+                Money::from_decimal(amount).unwrap(),
+                parent_id.is_some(),
+                parent_id
+            ],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    fn category_id(conn: &Connection, name: &str) -> i64 {
+        conn.query_row("SELECT id FROM categories WHERE name = ?1", [name], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn categories_are_matched_by_path() {
+        let mut target = migrated_test_db();
+        let source = migrated_test_db();
+        // Shifts the ids, so matching by id would pick the wrong category
+        add_category(&target, "Animaux", None);
+        let target_trips = add_category(&target, "Voyages", None);
+        let trips = add_category(&source, "Voyages", None);
+        add_category(&source, "Vols", Some(trips));
+        // Same name under another parent is a different category
+        add_category(&source, "Loyer", Some(4));
+        let seeded = count(&target, "SELECT COUNT(*) FROM categories") as usize - 2;
+
+        let report = merge_db(&mut target, &source, false).unwrap();
+
+        assert_eq!(report.categories.matched, seeded + 1);
+        assert_eq!(report.categories.created, 2);
+        let flights_parent: i64 = target
+            .query_row(
+                "SELECT parent_id FROM categories WHERE name = 'Vols'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(flights_parent, target_trips);
+        assert_eq!(
+            count(
+                &target,
+                "SELECT COUNT(*) FROM categories WHERE name = 'Loyer'"
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn suppliers_and_keywords_point_to_the_target_ids() {
+        let mut target = migrated_test_db();
+        let source = migrated_test_db();
+        add_category(&target, "Animaux", None);
+        let sports = add_category(&source, "Sports", None);
+        source
+            .execute(
+                "INSERT INTO suppliers (name, normalized_name, category_id)
+                 VALUES ('Décathlon', 'decathlon', ?1)",
+                [sports],
+            )
+            .unwrap();
+        let supplier = source.last_insert_rowid();
+        source
+            .execute(
+                "INSERT INTO keywords (keyword, category_id, supplier_id)
+                 VALUES ('DECATHLON CANADA', ?1, ?2)",
+                [sports, supplier],
+            )
+            .unwrap();
+
+        let report = merge_db(&mut target, &source, false).unwrap();
+
+        assert_eq!(report.suppliers.created, 1);
+        assert_eq!(report.keywords.created, 1);
+        let target_sports = category_id(&target, "Sports");
+        assert_ne!(target_sports, sports);
+        let (supplier_id, supplier_category): (i64, i64) = target
+            .query_row(
+                "SELECT id, category_id FROM suppliers WHERE name = 'Décathlon'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(supplier_category, target_sports);
+        let keyword: (i64, i64) = target
+            .query_row(
+                "SELECT category_id, supplier_id FROM keywords WHERE keyword = 'DECATHLON CANADA'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(keyword, (target_sports, supplier_id));
+    }
+
+    #[test]
+    fn duplicate_transactions_are_matched_once_each() {
+        let mut target = migrated_test_db();
+        let source = migrated_test_db();
+        add_transaction(&target, "2025-01-02", "CAFE", -3.0, None);
+        add_transaction(&source, "2025-01-02", "CAFE", -3.0, None);
+        add_transaction(&source, "2025-01-02", "CAFE", -3.0, None);
+        add_transaction(&source, "2025-01-03", "EPICERIE", -42.5, None);
+
+        let report = merge_db(&mut target, &source, false).unwrap();
+
+        assert_eq!(report.transactions.matched, 1);
+        assert_eq!(report.transactions.created, 2);
+        assert_eq!(
+            count(
+                &target,
+                "SELECT COUNT(*) FROM transactions WHERE description = 'CAFE'"
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn split_parts_follow_their_parent() {
+        let mut target = migrated_test_db();
+        let source = migrated_test_db();
+        let existing = add_transaction(&target, "2025-01-05", "COSTCO", -100.0, None);
+        add_transaction(&target, "2025-01-05", "COSTCO", -60.0, Some(existing));
+        add_transaction(&target, "2025-01-05", "COSTCO", -40.0, Some(existing));
+        // Already in the target: its parts are skipped with it, even if they differ
+        let duplicate = add_transaction(&source, "2025-01-05", "COSTCO", -100.0, None);
+        add_transaction(&source, "2025-01-05", "COSTCO", -70.0, Some(duplicate));
+        add_transaction(&source, "2025-01-05", "COSTCO", -30.0, Some(duplicate));
+        let new = add_transaction(&source, "2025-01-06", "IKEA", -200.0, None);
+        add_transaction(&source, "2025-01-06", "IKEA", -150.0, Some(new));
+        add_transaction(&source, "2025-01-06", "IKEA", -50.0, Some(new));
+
+        let report = merge_db(&mut target, &source, false).unwrap();
+
+        assert_eq!(report.transactions.matched, 3);
+        assert_eq!(report.transactions.created, 3);
+        let parts_of = |parent: i64| -> i64 {
+            target
+                .query_row(
+                    "SELECT COUNT(*) FROM transactions WHERE parent_transaction_id = ?1",
+                    [parent],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        assert_eq!(parts_of(existing), 2);
+        let new_parent: i64 = target
+            .query_row(
+                "SELECT id FROM transactions
+                 WHERE description = 'IKEA' AND parent_transaction_id IS NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(parts_of(new_parent), 2);
+    }
+
+    #[test]
+    fn disagreements_are_reported_and_the_target_kept() {
+        let mut target = migrated_test_db();
+        let source = migrated_test_db();
+        source
+            .execute("UPDATE categories SET type = 'income' WHERE id = 20", [])
+            .unwrap();
+        source
+            .execute(
+                "UPDATE keywords SET category_id = 20 WHERE keyword = 'HYDRO-QUEBEC'",
+                [],
+            )
+            .unwrap();
+        for (conn, category) in [(&target, 21), (&source, 22)] {
+            conn.execute(
+                "INSERT INTO suppliers (name, normalized_name, category_id)
+                 VALUES ('Metro', 'metro', ?1)",
+                [category],
+            )
+            .unwrap();
+        }
+
+        let report = merge_db(&mut target, &source, false).unwrap();
+
+        let mut kinds: Vec<(&str, &str)> = report
+            .conflicts
+            .iter()
+            .map(|c| (c.kind.as_str(), c.item.as_str()))
+            .collect();
+        kinds.sort();
+        assert_eq!(
+            kinds,
+            [
+                ("category_type", "Dépenses récurrentes > Loyer"),
+                ("keyword_category", "HYDRO-QUEBEC"),
+                ("supplier_category", "Metro"),
+            ]
+        );
+        let loyer_type: String = target
+            .query_row("SELECT type FROM categories WHERE id = 20", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(loyer_type, "expense");
+        assert_eq!(
+            count(
+                &target,
+                "SELECT COUNT(*) FROM keywords WHERE keyword = 'HYDRO-QUEBEC'"
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn dry_run_reports_the_same_counts_and_changes_nothing() {
+        let source = migrated_test_db();
+        add_category(&source, "Voyages", None);
+        add_transaction(&source, "2025-01-02", "CAFE", -3.0, None);
+        let mut applied = migrated_test_db();
+        let mut untouched = migrated_test_db();
+
+        let preview = merge_db(&mut untouched, &source, true).unwrap();
+        let report = merge_db(&mut applied, &source, false).unwrap();
+
+        assert!(preview.dry_run && !preview.applied);
+        assert!(report.applied);
+        assert_eq!(preview.categories.created, report.categories.created);
+        assert_eq!(preview.transactions.created, report.transactions.created);
+        assert_eq!(count(&untouched, "SELECT COUNT(*) FROM transactions"), 0);
+        assert_eq!(
+            count(
+                &untouched,
+                "SELECT COUNT(*) FROM categories WHERE name = 'Voyages'"
+            ),
+            0
+        );
+        assert_eq!(count(&applied, "SELECT COUNT(*) FROM transactions"), 1);
+    }
+
+    #[test]
+    fn merging_an_identical_profile_creates_nothing() {
+        let profile = || {
+            let conn = migrated_test_db();
+            let trips = add_category(&conn, "Voyages", None);
+            conn.execute(
+                "INSERT INTO keywords (keyword, category_id) VALUES ('AIR CANADA', ?1)",
+                [trips],
+            )
+            .unwrap();
+            let parent = add_transaction(&conn, "2025-01-05", "COSTCO", -100.0, None);
+            add_transaction(&conn, "2025-01-05", "COSTCO", -60.0, Some(parent));
+            add_transaction(&conn, "2025-01-05", "COSTCO", -40.0, Some(parent));
+            add_transaction(&conn, "2025-01-02", "CAFE", -3.0, None);
+            conn
+        };
+        let mut target = profile();
+        let source = profile();
+
+        let report = merge_db(&mut target, &source, false).unwrap();
+
+        assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+        assert_eq!(report.categories.created, 0);
+        assert_eq!(report.keywords.created, 0);
+        assert_eq!(report.transactions.created, 0);
+        assert_eq!(report.transactions.matched, 4);
+        assert_eq!(count(&target, "SELECT COUNT(*) FROM transactions"), 4);
+    }
+}
@@ -0,0 +1,1009 @@
+//! Migration chain shared by `tauri-plugin-sql` and the per-profile runner.
+//!
+//! The plugin only migrates the databases it was configured for at startup, i.e. the
+//! default profile. Every other profile database is brought up to date by
+//! `migrate_db`, which records applied versions in the same `_sqlx_migrations` table
+//! (same layout and SHA-384 checksums as sqlx), so both can share a database safely.
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use sha2::{Digest, Sha384};
+use std::time::Instant;
+
+pub struct MigrationDef {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+    /// SQL reverting the migration, when that is feasible.
+    pub down: Option<&'static str>,
+    /// Checks run before `down`; rolling back is refused if any of them finds rows.
+    pub down_checks: &'static [DataLossCheck],
+}
+
+/// A query counting the rows whose data a down migration would lose.
+pub struct DataLossCheck {
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Schema version matched by `CONSOLIDATED_SCHEMA` plus `SEED_CATEGORIES`, which new
+/// profiles are created from.
+pub const CONSOLIDATED_SCHEMA_VERSION: i64 = 6;
+
+pub const MIGRATIONS: &[MigrationDef] = &[
+    MigrationDef {
+        version: 1,
+        description: "create initial schema",
+        sql: super::SCHEMA,
+        down: None,
+        down_checks: &[],
+    },
+    MigrationDef {
+        version: 2,
+        description: "seed categories and keywords",
+        sql: super::SEED_CATEGORIES,
+        down: None,
+        down_checks: &[],
+    },
+    MigrationDef {
+        version: 3,
+        description: "add has_header to import_sources",
+        sql: "ALTER TABLE import_sources ADD COLUMN has_header INTEGER NOT NULL DEFAULT 1;",
+        down: Some("ALTER TABLE import_sources DROP COLUMN has_header;"),
+        down_checks: &[DataLossCheck {
+            description: "import sources configured without a header row",
+            sql: "SELECT COUNT(*) FROM import_sources WHERE has_header != 1",
+        }],
+    },
+    MigrationDef {
+        version: 4,
+        description: "add is_inputable to categories",
+        sql: "ALTER TABLE categories ADD COLUMN is_inputable INTEGER NOT NULL DEFAULT 1;",
+        down: Some("ALTER TABLE categories DROP COLUMN is_inputable;"),
+        down_checks: &[DataLossCheck {
+            description: "categories marked as not inputable",
+            sql: "SELECT COUNT(*) FROM categories WHERE is_inputable != 1",
+        }],
+    },
+    MigrationDef {
+        version: 5,
+        description: "create import_config_templates table",
+        sql: "CREATE TABLE IF NOT EXISTS import_config_templates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                delimiter TEXT NOT NULL DEFAULT ';',
+                encoding TEXT NOT NULL DEFAULT 'utf-8',
+                date_format TEXT NOT NULL DEFAULT 'DD/MM/YYYY',
+                skip_lines INTEGER NOT NULL DEFAULT 0,
+                has_header INTEGER NOT NULL DEFAULT 1,
+                column_mapping TEXT NOT NULL,
+                amount_mode TEXT NOT NULL DEFAULT 'single',
+                sign_convention TEXT NOT NULL DEFAULT 'negative_expense',
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );",
+        down: Some("DROP TABLE import_config_templates;"),
+        down_checks: &[DataLossCheck {
+            description: "saved import configuration templates",
+            sql: "SELECT COUNT(*) FROM import_config_templates",
+        }],
+    },
+    MigrationDef {
+        version: 6,
+        description: "change imported_files unique constraint from hash to filename",
+        sql: "CREATE TABLE imported_files_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL REFERENCES import_sources(id),
+                filename TEXT NOT NULL,
+                file_hash TEXT NOT NULL,
+                import_date DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                row_count INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'completed',
+                notes TEXT,
+                UNIQUE(source_id, filename)
+            );
+            INSERT INTO imported_files_new SELECT * FROM imported_files;
+            DROP TABLE imported_files;
+            ALTER TABLE imported_files_new RENAME TO imported_files;",
+        down: Some(
+            "CREATE TABLE imported_files_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL,
+                filename TEXT NOT NULL,
+                file_hash TEXT NOT NULL,
+                import_date DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                row_count INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'completed',
+                notes TEXT,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE CASCADE,
+                UNIQUE(source_id, file_hash)
+            );
+            INSERT INTO imported_files_old SELECT * FROM imported_files;
+            DROP TABLE imported_files;
+            ALTER TABLE imported_files_old RENAME TO imported_files;
+            CREATE INDEX IF NOT EXISTS idx_imported_files_source ON imported_files(source_id);",
+        ),
+        down_checks: &[DataLossCheck {
+            description: "imported files sharing the same content within a source",
+            sql: "SELECT COUNT(*) FROM imported_files f
+                  WHERE EXISTS (SELECT 1 FROM imported_files o WHERE o.source_id = f.source_id
+                                AND o.file_hash = f.file_hash AND o.id < f.id)",
+        }],
+    },
+    MigrationDef {
+        version: 7,
+        description: "add level-3 insurance subcategories",
+        sql: "INSERT OR IGNORE INTO categories (id, name, parent_id, type, color, sort_order) VALUES (310, 'Assurance-auto', 31, 'expense', '#14b8a6', 1);
+            INSERT OR IGNORE INTO categories (id, name, parent_id, type, color, sort_order) VALUES (311, 'Assurance-habitation', 31, 'expense', '#0d9488', 2);
+            INSERT OR IGNORE INTO categories (id, name, parent_id, type, color, sort_order) VALUES (312, 'Assurance-vie', 31, 'expense', '#0f766e', 3);
+            UPDATE categories SET is_inputable = 0 WHERE id = 31;
+            UPDATE keywords SET category_id = 310 WHERE keyword = 'BELAIR' AND category_id = 31;
+            UPDATE keywords SET category_id = 311 WHERE keyword = 'PRYSM' AND category_id = 31;
+            UPDATE keywords SET category_id = 312 WHERE keyword = 'INS/ASS' AND category_id = 31;",
+        down: Some(
+            "UPDATE OR IGNORE keywords SET category_id = 31 WHERE category_id IN (310, 311, 312);
+            DELETE FROM keywords WHERE category_id IN (310, 311, 312);
+            UPDATE categories SET is_inputable = 1 WHERE id = 31;
+            DELETE FROM categories WHERE id IN (310, 311, 312);",
+        ),
+        down_checks: &[
+            DataLossCheck {
+                description: "transactions in the insurance subcategories",
+                sql: "SELECT COUNT(*) FROM transactions WHERE category_id IN (310, 311, 312)",
+            },
+            DataLossCheck {
+                description: "suppliers, budget or adjustment entries in the insurance subcategories",
+                sql: "SELECT (SELECT COUNT(*) FROM suppliers WHERE category_id IN (310, 311, 312))
+                      + (SELECT COUNT(*) FROM budget_entries WHERE category_id IN (310, 311, 312))
+                      + (SELECT COUNT(*) FROM budget_template_entries WHERE category_id IN (310, 311, 312))
+                      + (SELECT COUNT(*) FROM adjustment_entries WHERE category_id IN (310, 311, 312))",
+            },
+            DataLossCheck {
+                description: "categories created under the insurance subcategories",
+                sql: "SELECT COUNT(*) FROM categories WHERE parent_id IN (310, 311, 312)",
+            },
+        ],
+    },
+    MigrationDef {
+        version: 8,
+        description: "add adjustment occurrence exceptions",
+        sql: "CREATE TABLE IF NOT EXISTS adjustment_exceptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                adjustment_id INTEGER NOT NULL,
+                occurrence_date DATE NOT NULL,
+                is_cancelled INTEGER NOT NULL DEFAULT 0,
+                override_date DATE,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (adjustment_id) REFERENCES adjustments(id) ON DELETE CASCADE,
+                UNIQUE(adjustment_id, occurrence_date)
+            );
+            CREATE TABLE IF NOT EXISTS adjustment_exception_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                exception_id INTEGER NOT NULL,
+                category_id INTEGER NOT NULL,
+                amount REAL NOT NULL,
+                description TEXT,
+                FOREIGN KEY (exception_id) REFERENCES adjustment_exceptions(id) ON DELETE CASCADE,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_adjustment_exceptions_adjustment ON adjustment_exceptions(adjustment_id);
+            CREATE INDEX IF NOT EXISTS idx_adjustment_exception_entries_exception ON adjustment_exception_entries(exception_id);",
+        down: Some(
+            "DROP TABLE IF EXISTS adjustment_exception_entries;
+            DROP TABLE IF EXISTS adjustment_exceptions;",
+        ),
+        down_checks: &[DataLossCheck {
+            description: "skipped or modified occurrences of recurring adjustments",
+            sql: "SELECT COUNT(*) FROM adjustment_exceptions",
+        }],
+    },
+    MigrationDef {
+        version: 9,
+        description: "add account metadata and statement balances",
+        sql: "ALTER TABLE import_sources ADD COLUMN account_type TEXT NOT NULL DEFAULT 'chequing';
+            ALTER TABLE import_sources ADD COLUMN opening_balance REAL NOT NULL DEFAULT 0;
+            ALTER TABLE import_sources ADD COLUMN opening_balance_date DATE;
+            ALTER TABLE import_sources ADD COLUMN currency TEXT NOT NULL DEFAULT 'CAD';
+            ALTER TABLE import_sources ADD COLUMN is_closed INTEGER NOT NULL DEFAULT 0;
+            CREATE TABLE IF NOT EXISTS statement_balances (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL,
+                file_id INTEGER,
+                balance_date DATE NOT NULL,
+                balance REAL NOT NULL,
+                currency TEXT,
+                origin TEXT NOT NULL DEFAULT 'manual', -- 'ofx', 'camt', 'manual'
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE CASCADE,
+                FOREIGN KEY (file_id) REFERENCES imported_files(id) ON DELETE SET NULL,
+                UNIQUE(source_id, balance_date, origin)
+            );
+            CREATE INDEX IF NOT EXISTS idx_statement_balances_source ON statement_balances(source_id, balance_date);",
+        down: Some(
+            "DROP TABLE IF EXISTS statement_balances;
+            ALTER TABLE import_sources DROP COLUMN is_closed;
+            ALTER TABLE import_sources DROP COLUMN currency;
+            ALTER TABLE import_sources DROP COLUMN opening_balance_date;
+            ALTER TABLE import_sources DROP COLUMN opening_balance;
+            ALTER TABLE import_sources DROP COLUMN account_type;",
+        ),
+        down_checks: &[
+            DataLossCheck {
+                description: "statement balances recorded for accounts",
+                sql: "SELECT COUNT(*) FROM statement_balances",
+            },
+            DataLossCheck {
+                description: "import sources with account settings",
+                sql: "SELECT COUNT(*) FROM import_sources
+                      WHERE account_type != 'chequing' OR opening_balance != 0
+                         OR opening_balance_date IS NOT NULL OR currency != 'CAD' OR is_closed != 0",
+            },
+        ],
+    },
+    MigrationDef {
+        version: 10,
+        description: "add statement reconciliations",
+        sql: "CREATE TABLE IF NOT EXISTS reconciliations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL,
+                statement_date DATE NOT NULL,
+                statement_balance REAL NOT NULL,
+                computed_balance REAL NOT NULL,
+                transaction_count INTEGER NOT NULL DEFAULT 0,
+                notes TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE CASCADE,
+                UNIQUE(source_id, statement_date)
+            );
+            CREATE INDEX IF NOT EXISTS idx_reconciliations_source ON reconciliations(source_id, statement_date);
+            ALTER TABLE transactions ADD COLUMN reconciliation_id INTEGER;
+            CREATE INDEX IF NOT EXISTS idx_transactions_reconciliation ON transactions(reconciliation_id);
+            CREATE TRIGGER IF NOT EXISTS trg_transactions_reconciled_update
+            BEFORE UPDATE OF date, description, amount, source_id ON transactions
+            WHEN OLD.reconciliation_id IS NOT NULL
+            BEGIN
+                SELECT RAISE(ABORT, 'Transaction is reconciled and locked');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_transactions_reconciled_delete
+            BEFORE DELETE ON transactions
+            WHEN OLD.reconciliation_id IS NOT NULL
+            BEGIN
+                SELECT RAISE(ABORT, 'Transaction is reconciled and locked');
+            END;",
+        down: Some(
+            "DROP TRIGGER IF EXISTS trg_transactions_reconciled_delete;
+            DROP TRIGGER IF EXISTS trg_transactions_reconciled_update;
+            DROP INDEX IF EXISTS idx_transactions_reconciliation;
+            ALTER TABLE transactions DROP COLUMN reconciliation_id;
+            DROP TABLE IF EXISTS reconciliations;",
+        ),
+        down_checks: &[DataLossCheck {
+            description: "statement reconciliations",
+            sql: "SELECT COUNT(*) FROM reconciliations",
+        }],
+    },
+    MigrationDef {
+        version: 11,
+        description: "add transaction currencies and exchange rates",
+        // The `currency` preference becomes the base currency. Nothing read it before,
+        // so it is aligned with the accounts to keep every amount unconverted.
+        sql: "ALTER TABLE transactions ADD COLUMN currency TEXT;
+            ALTER TABLE transactions ADD COLUMN base_amount REAL;
+            CREATE TABLE IF NOT EXISTS exchange_rates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                from_currency TEXT NOT NULL,
+                to_currency TEXT NOT NULL,
+                rate_date DATE NOT NULL,
+                rate REAL NOT NULL, -- 1 from_currency = rate to_currency
+                source TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(from_currency, to_currency, rate_date)
+            );
+            CREATE INDEX IF NOT EXISTS idx_exchange_rates_pair ON exchange_rates(from_currency, to_currency, rate_date);
+            INSERT OR IGNORE INTO user_preferences (key, value) VALUES ('currency', 'CAD');
+            UPDATE user_preferences SET value = COALESCE(
+                (SELECT currency FROM import_sources GROUP BY currency ORDER BY COUNT(*) DESC, currency LIMIT 1),
+                'CAD'
+            ), updated_at = CURRENT_TIMESTAMP
+            WHERE key = 'currency';
+            UPDATE transactions SET base_amount = amount
+            WHERE COALESCE(
+                (SELECT s.currency FROM import_sources s WHERE s.id = transactions.source_id),
+                (SELECT value FROM user_preferences WHERE key = 'currency')
+            ) = (SELECT value FROM user_preferences WHERE key = 'currency');",
+        down: Some(
+            "DROP INDEX IF EXISTS idx_exchange_rates_pair;
+            DROP TABLE IF EXISTS exchange_rates;
+            ALTER TABLE transactions DROP COLUMN base_amount;
+            ALTER TABLE transactions DROP COLUMN currency;",
+        ),
+        down_checks: &[
+            DataLossCheck {
+                description: "exchange rates",
+                sql: "SELECT COUNT(*) FROM exchange_rates",
+            },
+            DataLossCheck {
+                description: "transactions with their own currency",
+                sql: "SELECT COUNT(*) FROM transactions WHERE currency IS NOT NULL",
+            },
+        ],
+    },
+    MigrationDef {
+        version: 12,
+        description: "store amounts as integer cents",
+        // Tables are rebuilt (as in v6) since SQLite cannot change a column's type.
+        // Nothing references the rebuilt tables but transactions itself, whose new copy
+        // references itself so that dropping the old one cascades nothing. The conversion
+        // is checked row by row before the old tables are dropped: a non-numeric amount,
+        // or one moving by more than half a cent, makes the CHECK fail and the migration
+        // roll back.
+        sql: "CREATE TABLE transactions_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                date DATE NOT NULL,
+                description TEXT NOT NULL,
+                amount INTEGER NOT NULL, -- cents
+                category_id INTEGER,
+                supplier_id INTEGER,
+                source_id INTEGER,
+                file_id INTEGER,
+                original_description TEXT,
+                notes TEXT,
+                is_manually_categorized INTEGER NOT NULL DEFAULT 0,
+                is_split INTEGER NOT NULL DEFAULT 0,
+                parent_transaction_id INTEGER,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                reconciliation_id INTEGER,
+                currency TEXT,
+                base_amount INTEGER, -- cents
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
+                FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON DELETE SET NULL,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE SET NULL,
+                FOREIGN KEY (file_id) REFERENCES imported_files(id) ON DELETE SET NULL,
+                FOREIGN KEY (parent_transaction_id) REFERENCES transactions_new(id) ON DELETE CASCADE
+            );
+            INSERT INTO transactions_new (id, date, description, amount, category_id, supplier_id,
+                source_id, file_id, original_description, notes, is_manually_categorized, is_split,
+                parent_transaction_id, created_at, updated_at, reconciliation_id, currency, base_amount)
+            SELECT id, date, description, CAST(ROUND(amount * 100) AS INTEGER), category_id,
+                supplier_id, source_id, file_id, original_description, notes, is_manually_categorized,
+                is_split, parent_transaction_id, created_at, updated_at, reconciliation_id, currency,
+                CAST(ROUND(base_amount * 100) AS INTEGER)
+            FROM transactions ORDER BY id;
+            CREATE TABLE adjustment_entries_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                adjustment_id INTEGER NOT NULL,
+                category_id INTEGER NOT NULL,
+                amount INTEGER NOT NULL, -- cents
+                description TEXT,
+                FOREIGN KEY (adjustment_id) REFERENCES adjustments(id) ON DELETE CASCADE,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+            );
+            INSERT INTO adjustment_entries_new (id, adjustment_id, category_id, amount, description)
+            SELECT id, adjustment_id, category_id, CAST(ROUND(amount * 100) AS INTEGER), description
+            FROM adjustment_entries;
+            CREATE TABLE adjustment_exception_entries_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                exception_id INTEGER NOT NULL,
+                category_id INTEGER NOT NULL,
+                amount INTEGER NOT NULL, -- cents
+                description TEXT,
+                FOREIGN KEY (exception_id) REFERENCES adjustment_exceptions(id) ON DELETE CASCADE,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+            );
+            INSERT INTO adjustment_exception_entries_new (id, exception_id, category_id, amount, description)
+            SELECT id, exception_id, category_id, CAST(ROUND(amount * 100) AS INTEGER), description
+            FROM adjustment_exception_entries;
+            CREATE TABLE budget_entries_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                category_id INTEGER NOT NULL,
+                year INTEGER NOT NULL,
+                month INTEGER NOT NULL, -- 1-12
+                amount INTEGER NOT NULL, -- cents
+                notes TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+                UNIQUE(category_id, year, month)
+            );
+            INSERT INTO budget_entries_new (id, category_id, year, month, amount, notes, created_at, updated_at)
+            SELECT id, category_id, year, month, CAST(ROUND(amount * 100) AS INTEGER), notes,
+                created_at, updated_at
+            FROM budget_entries;
+            CREATE TABLE budget_template_entries_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                template_id INTEGER NOT NULL,
+                category_id INTEGER NOT NULL,
+                amount INTEGER NOT NULL, -- cents
+                FOREIGN KEY (template_id) REFERENCES budget_templates(id) ON DELETE CASCADE,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+                UNIQUE(template_id, category_id)
+            );
+            INSERT INTO budget_template_entries_new (id, template_id, category_id, amount)
+            SELECT id, template_id, category_id, CAST(ROUND(amount * 100) AS INTEGER)
+            FROM budget_template_entries;
+            CREATE TABLE statement_balances_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL,
+                file_id INTEGER,
+                balance_date DATE NOT NULL,
+                balance INTEGER NOT NULL, -- cents
+                currency TEXT,
+                origin TEXT NOT NULL DEFAULT 'manual', -- 'ofx', 'camt', 'manual'
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE CASCADE,
+                FOREIGN KEY (file_id) REFERENCES imported_files(id) ON DELETE SET NULL,
+                UNIQUE(source_id, balance_date, origin)
+            );
+            INSERT INTO statement_balances_new (id, source_id, file_id, balance_date, balance,
+                currency, origin, created_at)
+            SELECT id, source_id, file_id, balance_date, CAST(ROUND(balance * 100) AS INTEGER),
+                currency, origin, created_at
+            FROM statement_balances;
+            CREATE TABLE reconciliations_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL,
+                statement_date DATE NOT NULL,
+                statement_balance INTEGER NOT NULL, -- cents
+                computed_balance INTEGER NOT NULL, -- cents
+                transaction_count INTEGER NOT NULL DEFAULT 0,
+                notes TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE CASCADE,
+                UNIQUE(source_id, statement_date)
+            );
+            INSERT INTO reconciliations_new (id, source_id, statement_date, statement_balance,
+                computed_balance, transaction_count, notes, created_at)
+            SELECT id, source_id, statement_date, CAST(ROUND(statement_balance * 100) AS INTEGER),
+                CAST(ROUND(computed_balance * 100) AS INTEGER), transaction_count, notes, created_at
+            FROM reconciliations;
+            ALTER TABLE import_sources ADD COLUMN opening_balance_cents INTEGER NOT NULL DEFAULT 0;
+            UPDATE import_sources SET opening_balance_cents = CAST(ROUND(opening_balance * 100) AS INTEGER);
+
+            CREATE TEMP TABLE money_conversion_check (
+                altered_amounts INTEGER NOT NULL CHECK (altered_amounts = 0)
+            );
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM transactions o JOIN transactions_new n ON n.id = o.id
+            WHERE typeof(o.amount) NOT IN ('integer', 'real') OR ABS(n.amount - o.amount * 100) > 0.5
+               OR typeof(o.base_amount) NOT IN ('integer', 'real', 'null')
+               OR ABS(n.base_amount - o.base_amount * 100) > 0.5;
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM adjustment_entries o JOIN adjustment_entries_new n ON n.id = o.id
+            WHERE typeof(o.amount) NOT IN ('integer', 'real') OR ABS(n.amount - o.amount * 100) > 0.5;
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM adjustment_exception_entries o
+                JOIN adjustment_exception_entries_new n ON n.id = o.id
+            WHERE typeof(o.amount) NOT IN ('integer', 'real') OR ABS(n.amount - o.amount * 100) > 0.5;
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM budget_entries o JOIN budget_entries_new n ON n.id = o.id
+            WHERE typeof(o.amount) NOT IN ('integer', 'real') OR ABS(n.amount - o.amount * 100) > 0.5;
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM budget_template_entries o JOIN budget_template_entries_new n ON n.id = o.id
+            WHERE typeof(o.amount) NOT IN ('integer', 'real') OR ABS(n.amount - o.amount * 100) > 0.5;
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM statement_balances o JOIN statement_balances_new n ON n.id = o.id
+            WHERE typeof(o.balance) NOT IN ('integer', 'real') OR ABS(n.balance - o.balance * 100) > 0.5;
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM reconciliations o JOIN reconciliations_new n ON n.id = o.id
+            WHERE typeof(o.statement_balance) NOT IN ('integer', 'real')
+               OR typeof(o.computed_balance) NOT IN ('integer', 'real')
+               OR ABS(n.statement_balance - o.statement_balance * 100) > 0.5
+               OR ABS(n.computed_balance - o.computed_balance * 100) > 0.5;
+            INSERT INTO money_conversion_check
+            SELECT COUNT(*) FROM import_sources
+            WHERE typeof(opening_balance) NOT IN ('integer', 'real')
+               OR ABS(opening_balance_cents - opening_balance * 100) > 0.5;
+            DROP TABLE money_conversion_check;
+
+            -- Ids of deleted rows are not handed out again
+            UPDATE sqlite_sequence SET seq = MAX(seq, COALESCE(
+                (SELECT o.seq FROM sqlite_sequence o WHERE o.name || '_new' = sqlite_sequence.name), 0))
+            WHERE name LIKE '%\\_new' ESCAPE '\\';
+            DROP TABLE transactions;
+            ALTER TABLE transactions_new RENAME TO transactions;
+            DROP TABLE adjustment_entries;
+            ALTER TABLE adjustment_entries_new RENAME TO adjustment_entries;
+            DROP TABLE adjustment_exception_entries;
+            ALTER TABLE adjustment_exception_entries_new RENAME TO adjustment_exception_entries;
+            DROP TABLE budget_entries;
+            ALTER TABLE budget_entries_new RENAME TO budget_entries;
+            DROP TABLE budget_template_entries;
+            ALTER TABLE budget_template_entries_new RENAME TO budget_template_entries;
+            DROP TABLE statement_balances;
+            ALTER TABLE statement_balances_new RENAME TO statement_balances;
+            DROP TABLE reconciliations;
+            ALTER TABLE reconciliations_new RENAME TO reconciliations;
+            ALTER TABLE import_sources DROP COLUMN opening_balance;
+            ALTER TABLE import_sources RENAME COLUMN opening_balance_cents TO opening_balance;
+            CREATE INDEX IF NOT EXISTS idx_transactions_date ON transactions(date);
+            CREATE INDEX IF NOT EXISTS idx_transactions_category ON transactions(category_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_supplier ON transactions(supplier_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_source ON transactions(source_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_file ON transactions(file_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_parent ON transactions(parent_transaction_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_reconciliation ON transactions(reconciliation_id);
+            CREATE INDEX IF NOT EXISTS idx_adjustment_entries_adjustment ON adjustment_entries(adjustment_id);
+            CREATE INDEX IF NOT EXISTS idx_adjustment_exception_entries_exception ON adjustment_exception_entries(exception_id);
+            CREATE INDEX IF NOT EXISTS idx_budget_entries_period ON budget_entries(year, month);
+            CREATE INDEX IF NOT EXISTS idx_statement_balances_source ON statement_balances(source_id, balance_date);
+            CREATE INDEX IF NOT EXISTS idx_reconciliations_source ON reconciliations(source_id, statement_date);
+            CREATE TRIGGER IF NOT EXISTS trg_transactions_reconciled_update
+            BEFORE UPDATE OF date, description, amount, source_id ON transactions
+            WHEN OLD.reconciliation_id IS NOT NULL
+            BEGIN
+                SELECT RAISE(ABORT, 'Transaction is reconciled and locked');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_transactions_reconciled_delete
+            BEFORE DELETE ON transactions
+            WHEN OLD.reconciliation_id IS NOT NULL
+            BEGIN
+                SELECT RAISE(ABORT, 'Transaction is reconciled and locked');
+            END;",
+        down: Some(
+            "CREATE TABLE transactions_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                date DATE NOT NULL,
+                description TEXT NOT NULL,
+                amount REAL NOT NULL,
+                category_id INTEGER,
+                supplier_id INTEGER,
+                source_id INTEGER,
+                file_id INTEGER,
+                original_description TEXT,
+                notes TEXT,
+                is_manually_categorized INTEGER NOT NULL DEFAULT 0,
+                is_split INTEGER NOT NULL DEFAULT 0,
+                parent_transaction_id INTEGER,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                reconciliation_id INTEGER,
+                currency TEXT,
+                base_amount REAL,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
+                FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON DELETE SET NULL,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE SET NULL,
+                FOREIGN KEY (file_id) REFERENCES imported_files(id) ON DELETE SET NULL,
+                FOREIGN KEY (parent_transaction_id) REFERENCES transactions_old(id) ON DELETE CASCADE
+            );
+            INSERT INTO transactions_old (id, date, description, amount, category_id, supplier_id,
+                source_id, file_id, original_description, notes, is_manually_categorized, is_split,
+                parent_transaction_id, created_at, updated_at, reconciliation_id, currency, base_amount)
+            SELECT id, date, description, amount / 100.0, category_id, supplier_id, source_id,
+                file_id, original_description, notes, is_manually_categorized, is_split,
+                parent_transaction_id, created_at, updated_at, reconciliation_id, currency,
+                base_amount / 100.0
+            FROM transactions ORDER BY id;
+            CREATE TABLE adjustment_entries_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                adjustment_id INTEGER NOT NULL,
+                category_id INTEGER NOT NULL,
+                amount REAL NOT NULL,
+                description TEXT,
+                FOREIGN KEY (adjustment_id) REFERENCES adjustments(id) ON DELETE CASCADE,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+            );
+            INSERT INTO adjustment_entries_old (id, adjustment_id, category_id, amount, description)
+            SELECT id, adjustment_id, category_id, amount / 100.0, description FROM adjustment_entries;
+            CREATE TABLE adjustment_exception_entries_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                exception_id INTEGER NOT NULL,
+                category_id INTEGER NOT NULL,
+                amount REAL NOT NULL,
+                description TEXT,
+                FOREIGN KEY (exception_id) REFERENCES adjustment_exceptions(id) ON DELETE CASCADE,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+            );
+            INSERT INTO adjustment_exception_entries_old (id, exception_id, category_id, amount, description)
+            SELECT id, exception_id, category_id, amount / 100.0, description
+            FROM adjustment_exception_entries;
+            CREATE TABLE budget_entries_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                category_id INTEGER NOT NULL,
+                year INTEGER NOT NULL,
+                month INTEGER NOT NULL, -- 1-12
+                amount REAL NOT NULL,
+                notes TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+                UNIQUE(category_id, year, month)
+            );
+            INSERT INTO budget_entries_old (id, category_id, year, month, amount, notes, created_at, updated_at)
+            SELECT id, category_id, year, month, amount / 100.0, notes, created_at, updated_at
+            FROM budget_entries;
+            CREATE TABLE budget_template_entries_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                template_id INTEGER NOT NULL,
+                category_id INTEGER NOT NULL,
+                amount REAL NOT NULL,
+                FOREIGN KEY (template_id) REFERENCES budget_templates(id) ON DELETE CASCADE,
+                FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+                UNIQUE(template_id, category_id)
+            );
+            INSERT INTO budget_template_entries_old (id, template_id, category_id, amount)
+            SELECT id, template_id, category_id, amount / 100.0 FROM budget_template_entries;
+            CREATE TABLE statement_balances_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL,
+                file_id INTEGER,
+                balance_date DATE NOT NULL,
+                balance REAL NOT NULL,
+                currency TEXT,
+                origin TEXT NOT NULL DEFAULT 'manual', -- 'ofx', 'camt', 'manual'
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE CASCADE,
+                FOREIGN KEY (file_id) REFERENCES imported_files(id) ON DELETE SET NULL,
+                UNIQUE(source_id, balance_date, origin)
+            );
+            INSERT INTO statement_balances_old (id, source_id, file_id, balance_date, balance,
+                currency, origin, created_at)
+            SELECT id, source_id, file_id, balance_date, balance / 100.0, currency, origin, created_at
+            FROM statement_balances;
+            CREATE TABLE reconciliations_old (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id INTEGER NOT NULL,
+                statement_date DATE NOT NULL,
+                statement_balance REAL NOT NULL,
+                computed_balance REAL NOT NULL,
+                transaction_count INTEGER NOT NULL DEFAULT 0,
+                notes TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (source_id) REFERENCES import_sources(id) ON DELETE CASCADE,
+                UNIQUE(source_id, statement_date)
+            );
+            INSERT INTO reconciliations_old (id, source_id, statement_date, statement_balance,
+                computed_balance, transaction_count, notes, created_at)
+            SELECT id, source_id, statement_date, statement_balance / 100.0, computed_balance / 100.0,
+                transaction_count, notes, created_at
+            FROM reconciliations;
+            ALTER TABLE import_sources ADD COLUMN opening_balance_real REAL NOT NULL DEFAULT 0;
+            UPDATE import_sources SET opening_balance_real = opening_balance / 100.0;
+
+            UPDATE sqlite_sequence SET seq = MAX(seq, COALESCE(
+                (SELECT o.seq FROM sqlite_sequence o WHERE o.name || '_old' = sqlite_sequence.name), 0))
+            WHERE name LIKE '%\\_old' ESCAPE '\\';
+            DROP TABLE transactions;
+            ALTER TABLE transactions_old RENAME TO transactions;
+            DROP TABLE adjustment_entries;
+            ALTER TABLE adjustment_entries_old RENAME TO adjustment_entries;
+            DROP TABLE adjustment_exception_entries;
+            ALTER TABLE adjustment_exception_entries_old RENAME TO adjustment_exception_entries;
+            DROP TABLE budget_entries;
+            ALTER TABLE budget_entries_old RENAME TO budget_entries;
+            DROP TABLE budget_template_entries;
+            ALTER TABLE budget_template_entries_old RENAME TO budget_template_entries;
+            DROP TABLE statement_balances;
+            ALTER TABLE statement_balances_old RENAME TO statement_balances;
+            DROP TABLE reconciliations;
+            ALTER TABLE reconciliations_old RENAME TO reconciliations;
+            ALTER TABLE import_sources DROP COLUMN opening_balance;
+            ALTER TABLE import_sources RENAME COLUMN opening_balance_real TO opening_balance;
+            CREATE INDEX IF NOT EXISTS idx_transactions_date ON transactions(date);
+            CREATE INDEX IF NOT EXISTS idx_transactions_category ON transactions(category_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_supplier ON transactions(supplier_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_source ON transactions(source_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_file ON transactions(file_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_parent ON transactions(parent_transaction_id);
+            CREATE INDEX IF NOT EXISTS idx_transactions_reconciliation ON transactions(reconciliation_id);
+            CREATE INDEX IF NOT EXISTS idx_adjustment_entries_adjustment ON adjustment_entries(adjustment_id);
+            CREATE INDEX IF NOT EXISTS idx_adjustment_exception_entries_exception ON adjustment_exception_entries(exception_id);
+            CREATE INDEX IF NOT EXISTS idx_budget_entries_period ON budget_entries(year, month);
+            CREATE INDEX IF NOT EXISTS idx_statement_balances_source ON statement_balances(source_id, balance_date);
+            CREATE INDEX IF NOT EXISTS idx_reconciliations_source ON reconciliations(source_id, statement_date);
+            CREATE TRIGGER IF NOT EXISTS trg_transactions_reconciled_update
+            BEFORE UPDATE OF date, description, amount, source_id ON transactions
+            WHEN OLD.reconciliation_id IS NOT NULL
+            BEGIN
+                SELECT RAISE(ABORT, 'Transaction is reconciled and locked');
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_transactions_reconciled_delete
+            BEFORE DELETE ON transactions
+            WHEN OLD.reconciliation_id IS NOT NULL
+            BEGIN
+                SELECT RAISE(ABORT, 'Transaction is reconciled and locked');
+            END;",
+        ),
+        down_checks: &[],
+    },
+    MigrationDef {
+        version: 13,
+        description: "add budget rollover and envelope transfers",
+        sql: "ALTER TABLE categories ADD COLUMN budget_rollover INTEGER NOT NULL DEFAULT 0;
+            CREATE TABLE IF NOT EXISTS budget_transfers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                year INTEGER NOT NULL,
+                month INTEGER NOT NULL, -- 1-12
+                from_category_id INTEGER NOT NULL,
+                to_category_id INTEGER NOT NULL,
+                amount INTEGER NOT NULL CHECK (amount > 0), -- cents
+                notes TEXT,
+                reversed_transfer_id INTEGER UNIQUE, -- the transfer this one undoes
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (from_category_id) REFERENCES categories(id) ON DELETE CASCADE,
+                FOREIGN KEY (to_category_id) REFERENCES categories(id) ON DELETE CASCADE,
+                FOREIGN KEY (reversed_transfer_id) REFERENCES budget_transfers(id) ON DELETE SET NULL,
+                CHECK (from_category_id != to_category_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_budget_transfers_period ON budget_transfers(year, month);",
+        down: Some(
+            "DROP INDEX IF EXISTS idx_budget_transfers_period;
+            DROP TABLE IF EXISTS budget_transfers;
+            ALTER TABLE categories DROP COLUMN budget_rollover;",
+        ),
+        down_checks: &[
+            DataLossCheck {
+                description: "money moved between budget envelopes",
+                sql: "SELECT COUNT(*) FROM budget_transfers",
+            },
+            DataLossCheck {
+                description: "categories whose budget rolls over",
+                sql: "SELECT COUNT(*) FROM categories WHERE budget_rollover != 0",
+            },
+        ],
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub db_filename: String,
+    pub from_version: i64,
+    pub to_version: i64,
+    pub latest_version: i64,
+    /// True when a database created from the consolidated schema was stamped with the
+    /// versions that schema already includes.
+    pub baselined: bool,
+    pub applied: Vec<AppliedMigration>,
+    /// Set when a migration failed; it was rolled back and later ones were not attempted.
+    pub error: Option<String>,
+}
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// SHA-384 of the migration SQL, as stored by sqlx in `_sqlx_migrations.checksum`.
+pub fn checksum(sql: &str) -> Vec<u8> {
+    Sha384::digest(sql.as_bytes()).to_vec()
+}
+
+fn ensure_migrations_table(conn: &Connection) -> Result<(), String> {
+    // Same definition as sqlx, which creates it with IF NOT EXISTS as well
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+            version BIGINT PRIMARY KEY,
+            description TEXT NOT NULL,
+            installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            success BOOLEAN NOT NULL,
+            checksum BLOB NOT NULL,
+            execution_time BIGINT NOT NULL
+        );",
+    )
+    .map_err(|e| format!("Cannot create migrations table: {}", e))
+}
+
+fn has_table(conn: &Connection, name: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [name],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Cannot inspect database: {}", e))
+}
+
+fn record_migration(
+    conn: &Connection,
+    migration: &MigrationDef,
+    execution_time: i64,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+         VALUES (?1, ?2, 1, ?3, ?4)",
+        params![
+            migration.version,
+            migration.description,
+            checksum(migration.sql),
+            execution_time
+        ],
+    )
+    .map_err(|e| format!("Cannot record migration {}: {}", migration.version, e))?;
+    Ok(())
+}
+
+/// Stamps a database created from the consolidated schema (tables present, no migration
+/// history) with the versions that schema already covers.
+fn baseline_consolidated(conn: &Connection) -> Result<bool, String> {
+    if has_table(conn, "_sqlx_migrations")? || !has_table(conn, "categories")? {
+        return Ok(false);
+    }
+    ensure_migrations_table(conn)?;
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|m| m.version <= CONSOLIDATED_SCHEMA_VERSION)
+    {
+        record_migration(conn, migration, 0)?;
+    }
+    Ok(true)
+}
+
+fn apply_migration(conn: &mut Connection, migration: &MigrationDef) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    let started = Instant::now();
+    tx.execute_batch(migration.sql).map_err(|e| {
+        format!(
+            "Migration {} ({}) failed: {}",
+            migration.version, migration.description, e
+        )
+    })?;
+    let elapsed = i64::try_from(started.elapsed().as_nanos()).unwrap_or(i64::MAX);
+    record_migration(&tx, migration, elapsed)?;
+    tx.commit()
+        .map_err(|e| format!("Cannot commit migration {}: {}", migration.version, e))
+}
+
+/// Versions that `migrate_db` would apply to this database.
+pub fn pending_versions(conn: &Connection) -> Result<Vec<i64>, String> {
+    let current = if !has_table(conn, "_sqlx_migrations")? && has_table(conn, "categories")? {
+        CONSOLIDATED_SCHEMA_VERSION
+    } else {
+        super::get_schema_version(conn)?
+    };
+    Ok(MIGRATIONS
+        .iter()
+        .map(|m| m.version)
+        .filter(|&v| v > current)
+        .collect())
+}
+
+/// True for a database without any table yet, which has nothing worth saving.
+pub fn is_blank(conn: &Connection) -> Result<bool, String> {
+    conn.query_row("SELECT COUNT(*) = 0 FROM sqlite_master", [], |row| {
+        row.get(0)
+    })
+    .map_err(|e| format!("Cannot inspect database: {}", e))
+}
+
+/// Applies every pending migration to an open database, one transaction per migration.
+/// Stops at the first failure, which is reported in `MigrationReport::error`.
+pub fn migrate_db(conn: &mut Connection, db_filename: &str) -> Result<MigrationReport, String> {
+    let baselined = baseline_consolidated(conn)?;
+    ensure_migrations_table(conn)?;
+
+    let from_version = super::get_schema_version(conn)?;
+    let mut report = MigrationReport {
+        db_filename: db_filename.to_string(),
+        from_version,
+        to_version: from_version,
+        latest_version: latest_version(),
+        baselined,
+        applied: Vec::new(),
+        error: None,
+    };
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from_version) {
+        if let Err(e) = apply_migration(conn, migration) {
+            report.error = Some(e);
+            break;
+        }
+        report.to_version = migration.version;
+        report.applied.push(AppliedMigration {
+            version: migration.version,
+            description: migration.description.to_string(),
+        });
+    }
+    Ok(report)
+}
+
+/// In-memory database migrated to the latest schema, for unit tests.
+#[cfg(test)]
+pub(crate) fn migrated_test_db() -> Connection {
+    let mut conn = Connection::open_in_memory().unwrap();
+    let report = migrate_db(&mut conn, "fixture.db").unwrap();
+    assert!(report.error.is_none(), "{:?}", report.error);
+    conn
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RollbackStep {
+    pub version: i64,
+    pub description: String,
+    /// What would be lost by reverting this migration, e.g. "3 saved import configuration
+    /// templates". Any entry makes the rollback refused.
+    pub data_loss: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RollbackReport {
+    pub db_filename: String,
+    pub from_version: i64,
+    pub target_version: i64,
+    pub dry_run: bool,
+    pub steps: Vec<RollbackStep>,
+    /// True when the rollback was committed, i.e. not a dry run and no data loss.
+    pub applied: bool,
+}
+
+/// Reverts migrations down to `target_version`, newest first, in a single transaction.
+/// Every step is actually executed, so a dry run validates the down SQL too; the
+/// transaction is only committed when `dry_run` is false and no step would lose data.
+pub fn rollback_db(
+    conn: &mut Connection,
+    db_filename: &str,
+    target_version: i64,
+    dry_run: bool,
+) -> Result<RollbackReport, String> {
+    let from_version = super::get_schema_version(conn)?;
+    if target_version < 0 || target_version >= from_version {
+        return Err(format!(
+            "Target version must be below the current version ({})",
+            from_version
+        ));
+    }
+    let to_revert: Vec<&MigrationDef> = MIGRATIONS
+        .iter()
+        .rev()
+        .filter(|m| m.version > target_version && m.version <= from_version)
+        .collect();
+    if let Some(m) = to_revert.iter().find(|m| m.down.is_none()) {
+        return Err(format!(
+            "Migration {} ({}) cannot be rolled back",
+            m.version, m.description
+        ));
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Cannot start transaction: {}", e))?;
+    let mut steps = Vec::new();
+    for migration in to_revert {
+        let mut data_loss = Vec::new();
+        for check in migration.down_checks {
+            let count: i64 = tx
+                .query_row(check.sql, [], |row| row.get(0))
+                .map_err(|e| format!("Data loss check failed: {}", e))?;
+            if count > 0 {
+                data_loss.push(format!("{} {}", count, check.description));
+            }
+        }
+        tx.execute_batch(migration.down.unwrap_or_default())
+            .and_then(|_| {
+                tx.execute(
+                    "DELETE FROM _sqlx_migrations WHERE version = ?1",
+                    [migration.version],
+                )
+            })
+            .map_err(|e| {
+                format!(
+                    "Rolling back migration {} ({}) failed: {}",
+                    migration.version, migration.description, e
+                )
+            })?;
+        steps.push(RollbackStep {
+            version: migration.version,
+            description: migration.description.to_string(),
+            data_loss,
+        });
+    }
+
+    let applied = !dry_run && steps.iter().all(|s| s.data_loss.is_empty());
+    if applied {
+        tx.commit()
+            .map_err(|e| format!("Cannot commit rollback: {}", e))?;
+    }
+    // Otherwise the transaction is rolled back when dropped
+    Ok(RollbackReport {
+        db_filename: db_filename.to_string(),
+        from_version,
+        target_version,
+        dry_run,
+        steps,
+        applied,
+    })
+}
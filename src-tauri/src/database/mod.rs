@@ -1,3 +1,14 @@
+pub mod connection;
+pub mod date_audit;
+pub mod encryption;
+pub mod merge;
+pub mod migrations;
+pub mod preferences;
+pub mod schema_check;
+
+pub use connection::*;
+pub use preferences::*;
+
 pub const SCHEMA: &str = include_str!("schema.sql");
 pub const SEED_CATEGORIES: &str = include_str!("seed_categories.sql");
 pub const CONSOLIDATED_SCHEMA: &str = include_str!("consolidated_schema.sql");
@@ -0,0 +1,22 @@
+use rusqlite::{Connection, OptionalExtension};
+
+pub fn get_preference(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT value FROM user_preferences WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("Cannot read preference {}: {}", key, e))
+}
+
+pub fn set_preference(conn: &Connection, key: &str, value: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO user_preferences (key, value, updated_at)
+         VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = CURRENT_TIMESTAMP",
+        [key, value],
+    )
+    .map_err(|e| format!("Cannot write preference {}: {}", key, e))?;
+    Ok(())
+}
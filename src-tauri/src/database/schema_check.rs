@@ -0,0 +1,218 @@
+//! Schema drift detection.
+//!
+//! sqlx refuses to run any migration once the checksum of an applied one no longer matches
+//! the embedded SQL (e.g. after `schema.sql` is edited), without telling the user why. These
+//! checks make such problems visible: checksums of applied migrations, the consolidated
+//! schema against the migration chain, and each database against the schema its version
+//! implies.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::migrations::{self, MIGRATIONS};
+
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnInfo {
+    col_type: String,
+    not_null: bool,
+    default_value: Option<String>,
+    primary_key: bool,
+}
+
+type SchemaMap = BTreeMap<String, BTreeMap<String, ColumnInfo>>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecksumIssue {
+    pub version: i64,
+    pub description: String,
+    /// `mismatch`, `unknown` (applied but not embedded in this build) or `failed`.
+    pub kind: String,
+}
+
+fn read_schema(conn: &Connection) -> Result<SchemaMap, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table'
+             AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations'",
+        )
+        .map_err(|e| format!("Cannot read schema: {}", e))?;
+    let tables: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Cannot read schema: {}", e))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Cannot read schema: {}", e))?;
+
+    let mut schema = SchemaMap::new();
+    for table in tables {
+        let mut stmt = conn
+            .prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1)")
+            .map_err(|e| format!("Cannot read columns of {}: {}", table, e))?;
+        let columns = stmt
+            .query_map([&table], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    ColumnInfo {
+                        col_type: row.get::<_, String>(1)?.to_uppercase(),
+                        not_null: row.get(2)?,
+                        default_value: row
+                            .get::<_, Option<String>>(3)?
+                            .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" ")),
+                        primary_key: row.get::<_, i64>(4)? > 0,
+                    },
+                ))
+            })
+            .map_err(|e| format!("Cannot read columns of {}: {}", table, e))?
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| format!("Cannot read columns of {}: {}", table, e))?;
+        schema.insert(table, columns);
+    }
+    Ok(schema)
+}
+
+fn describe_column(column: &ColumnInfo) -> String {
+    let mut parts = vec![if column.col_type.is_empty() {
+        "(no type)".to_string()
+    } else {
+        column.col_type.clone()
+    }];
+    if column.primary_key {
+        parts.push("PRIMARY KEY".to_string());
+    }
+    if column.not_null {
+        parts.push("NOT NULL".to_string());
+    }
+    if let Some(default) = &column.default_value {
+        parts.push(format!("DEFAULT {}", default));
+    }
+    parts.join(" ")
+}
+
+/// Lists the differences between two schemas, table by table and column by column.
+fn diff_schemas(expected: &SchemaMap, actual: &SchemaMap, actual_label: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let tables: BTreeSet<&String> = expected.keys().chain(actual.keys()).collect();
+    for table in tables {
+        let (expected_cols, actual_cols) = match (expected.get(table), actual.get(table)) {
+            (Some(e), Some(a)) => (e, a),
+            (Some(_), None) => {
+                problems.push(format!("Table {} is missing from {}", table, actual_label));
+                continue;
+            }
+            (None, _) => {
+                problems.push(format!("Table {} only exists in {}", table, actual_label));
+                continue;
+            }
+        };
+        let columns: BTreeSet<&String> = expected_cols.keys().chain(actual_cols.keys()).collect();
+        for column in columns {
+            match (expected_cols.get(column), actual_cols.get(column)) {
+                (Some(e), Some(a)) if e != a => problems.push(format!(
+                    "Column {}.{} differs in {}: expected {}, found {}",
+                    table,
+                    column,
+                    actual_label,
+                    describe_column(e),
+                    describe_column(a)
+                )),
+                (Some(_), None) => problems.push(format!(
+                    "Column {}.{} is missing from {}",
+                    table, column, actual_label
+                )),
+                (None, Some(_)) => problems.push(format!(
+                    "Column {}.{} only exists in {}",
+                    table, column, actual_label
+                )),
+                _ => {}
+            }
+        }
+    }
+    problems
+}
+
+/// Builds an in-memory database by running the migration chain up to `version`.
+fn migrated_reference(version: i64) -> Result<Connection, String> {
+    let conn = Connection::open_in_memory()
+        .map_err(|e| format!("Cannot create reference database: {}", e))?;
+    for migration in MIGRATIONS.iter().filter(|m| m.version <= version) {
+        conn.execute_batch(migration.sql).map_err(|e| {
+            format!(
+                "Migration {} ({}) does not apply to a fresh database: {}",
+                migration.version, migration.description, e
+            )
+        })?;
+    }
+    Ok(conn)
+}
+
+/// Compares the consolidated schema used for new profiles with the schema produced by
+/// the migration chain up to `CONSOLIDATED_SCHEMA_VERSION`.
+pub fn check_consolidated_schema() -> Result<Vec<String>, String> {
+    let reference = migrated_reference(migrations::CONSOLIDATED_SCHEMA_VERSION)?;
+    let consolidated = Connection::open_in_memory()
+        .map_err(|e| format!("Cannot create reference database: {}", e))?;
+    consolidated
+        .execute_batch(super::CONSOLIDATED_SCHEMA)
+        .and_then(|_| consolidated.execute_batch(super::SEED_CATEGORIES))
+        .map_err(|e| format!("The consolidated schema does not apply: {}", e))?;
+
+    Ok(diff_schemas(
+        &read_schema(&reference)?,
+        &read_schema(&consolidated)?,
+        "the consolidated schema",
+    ))
+}
+
+/// Compares the checksums recorded in `_sqlx_migrations` with the embedded migrations.
+pub fn check_checksums(conn: &Connection) -> Result<Vec<ChecksumIssue>, String> {
+    if super::get_schema_version(conn)? == 0 {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn
+        .prepare(
+            "SELECT version, description, success, checksum FROM _sqlx_migrations ORDER BY version",
+        )
+        .map_err(|e| format!("Cannot read applied migrations: {}", e))?;
+    let applied = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, Vec<u8>>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Cannot read applied migrations: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read applied migrations: {}", e))?;
+
+    let mut issues = Vec::new();
+    for (version, description, success, checksum) in applied {
+        let kind = match MIGRATIONS.iter().find(|m| m.version == version) {
+            None => "unknown",
+            Some(_) if !success => "failed",
+            Some(m) if migrations::checksum(m.sql) != checksum => "mismatch",
+            Some(_) => continue,
+        };
+        issues.push(ChecksumIssue {
+            version,
+            description,
+            kind: kind.to_string(),
+        });
+    }
+    Ok(issues)
+}
+
+/// Compares a database with the schema the migration chain produces at its version.
+pub fn check_db_schema(conn: &Connection) -> Result<Vec<String>, String> {
+    let version = super::get_schema_version(conn)?;
+    if version == 0 {
+        return Ok(Vec::new());
+    }
+    let reference = migrated_reference(version)?;
+    Ok(diff_schemas(
+        &read_schema(&reference)?,
+        &read_schema(conn)?,
+        "the database",
+    ))
+}
@@ -0,0 +1,279 @@
+//! Parsing of the dates found in imported files, which are stored as ISO `YYYY-MM-DD`.
+//!
+//! Formats are written in either of the two notations the app has used: strftime, as in
+//! `import_sources.date_format` (`%d/%m/%Y`), or tokens, as in import templates and the
+//! wizard (`DD/MM/YYYY`). Only the order of the day, month and year fields and whether
+//! they are separated matter: values may use `/`, `-` or `.` between fields, pad them or
+//! not, and use two-digit years. A trailing time (`2025-01-06 00:00:00`) is ignored.
+//!
+//! Dates that do not exist (February 30th, a 13th month) are rejected rather than
+//! rolled over.
+
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldOrder {
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateFormat {
+    pub order: FieldOrder,
+    /// Fields without separators, as in `YYYYMMDD`.
+    pub compact: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Day,
+    Month,
+    Year,
+}
+
+impl DateFormat {
+    /// Reads a format in strftime (`%d/%m/%Y`) or token (`DD/MM/YYYY`) notation.
+    pub fn parse(notation: &str) -> Result<Self, String> {
+        let invalid = || format!("Unsupported date format: {}", notation);
+        let mut fields = Vec::new();
+        let mut separated = false;
+        let mut chars = notation.trim().chars().peekable();
+        while let Some(c) = chars.next() {
+            let field = match c {
+                '%' => match chars.next() {
+                    Some('d') | Some('e') => Field::Day,
+                    Some('m') => Field::Month,
+                    Some('Y') | Some('y') => Field::Year,
+                    _ => return Err(invalid()),
+                },
+                '/' | '-' | '.' | ' ' => {
+                    separated = true;
+                    continue;
+                }
+                _ => {
+                    let field = match c.to_ascii_uppercase() {
+                        'D' => Field::Day,
+                        'M' => Field::Month,
+                        'Y' => Field::Year,
+                        _ => return Err(invalid()),
+                    };
+                    // DD, MM, YY and YYYY are one field each
+                    while chars.peek().map(|n| n.to_ascii_uppercase())
+                        == Some(c.to_ascii_uppercase())
+                    {
+                        chars.next();
+                    }
+                    field
+                }
+            };
+            fields.push(field);
+        }
+        let order = match fields.as_slice() {
+            [Field::Day, Field::Month, Field::Year] => FieldOrder::DayMonthYear,
+            [Field::Month, Field::Day, Field::Year] => FieldOrder::MonthDayYear,
+            [Field::Year, Field::Month, Field::Day] => FieldOrder::YearMonthDay,
+            _ => return Err(invalid()),
+        };
+        Ok(DateFormat {
+            order,
+            compact: !separated,
+        })
+    }
+
+    /// The format in token notation, with `separator` between fields.
+    pub fn notation(&self, separator: char) -> String {
+        let fields: [&str; 3] = match self.order {
+            FieldOrder::DayMonthYear => ["DD", "MM", "YYYY"],
+            FieldOrder::MonthDayYear => ["MM", "DD", "YYYY"],
+            FieldOrder::YearMonthDay => ["YYYY", "MM", "DD"],
+        };
+        if self.compact {
+            fields.concat()
+        } else {
+            fields.join(&separator.to_string())
+        }
+    }
+
+    /// The same format with day and month swapped, for day-first and month-first ones.
+    pub fn swapped(&self) -> Option<Self> {
+        let order = match self.order {
+            FieldOrder::DayMonthYear => FieldOrder::MonthDayYear,
+            FieldOrder::MonthDayYear => FieldOrder::DayMonthYear,
+            FieldOrder::YearMonthDay => return None,
+        };
+        Some(DateFormat { order, ..*self })
+    }
+
+    pub fn parse_value(&self, value: &str) -> Result<NaiveDate, String> {
+        let invalid = || format!("Invalid date: {}", value);
+        let date = value
+            .trim()
+            .split(|c: char| c.is_whitespace() || c == 'T')
+            .next()
+            .unwrap_or_default();
+        let parts: Vec<&str> = if self.compact {
+            if date.len() != 8 || !date.is_ascii() {
+                return Err(invalid());
+            }
+            match self.order {
+                FieldOrder::YearMonthDay => vec![&date[..4], &date[4..6], &date[6..]],
+                _ => vec![&date[..2], &date[2..4], &date[4..]],
+            }
+        } else {
+            date.split(['/', '-', '.']).collect()
+        };
+        let [a, b, c] = parts.as_slice() else {
+            return Err(invalid());
+        };
+        let (day, month, year) = match self.order {
+            FieldOrder::DayMonthYear => (a, b, c),
+            FieldOrder::MonthDayYear => (b, a, c),
+            FieldOrder::YearMonthDay => (c, b, a),
+        };
+        let year_len = year.len();
+        let number = |part: &str, max_len: usize| {
+            if part.is_empty() || part.len() > max_len || !part.bytes().all(|b| b.is_ascii_digit())
+            {
+                None
+            } else {
+                part.parse::<u32>().ok()
+            }
+        };
+        let (Some(day), Some(month), Some(year)) =
+            (number(day, 2), number(month, 2), number(year, 4))
+        else {
+            return Err(invalid());
+        };
+        let year = match year_len {
+            2 if year > 50 => 1900 + year,
+            2 => 2000 + year,
+            4 => year,
+            _ => return Err(invalid()),
+        };
+        NaiveDate::from_ymd_opt(year as i32, month, day)
+            .ok_or_else(|| format!("Impossible date: {}", value))
+    }
+}
+
+/// Converts `value` to an ISO date, as read with `format`.
+pub fn to_iso(value: &str, format: &DateFormat) -> Result<String, String> {
+    format
+        .parse_value(value)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// True for dates already stored in the canonical `YYYY-MM-DD` form.
+pub fn is_iso(value: &str) -> bool {
+    value.len() == 10 && NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+/// Whether `values`, all valid in a day-first or month-first `format`, are also all valid
+/// with day and month swapped and then give other dates. Such a file cannot tell which
+/// of the two formats it uses: only a date past the 12th would.
+pub fn is_ambiguous(values: &[&str], format: &DateFormat) -> bool {
+    let Some(swapped) = format.swapped() else {
+        return false;
+    };
+    let mut differs = false;
+    for value in values.iter().filter(|v| !v.trim().is_empty()) {
+        match (format.parse_value(value), swapped.parse_value(value)) {
+            (Ok(date), Ok(other)) => differs |= date != other,
+            _ => return false,
+        }
+    }
+    differs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(notation: &str) -> DateFormat {
+        DateFormat::parse(notation).unwrap()
+    }
+
+    fn iso(value: &str, notation: &str) -> Result<String, String> {
+        to_iso(value, &format(notation))
+    }
+
+    #[test]
+    fn reads_both_notations() {
+        assert_eq!(format("%d/%m/%Y"), format("DD/MM/YYYY"));
+        assert_eq!(format("%m-%d-%y"), format("MM/DD/YY"));
+        assert_eq!(format("YYYYMMDD").order, FieldOrder::YearMonthDay);
+        assert!(format("YYYYMMDD").compact);
+        assert_eq!(format("%e.%m.%Y").notation('/'), "DD/MM/YYYY");
+        assert!(DateFormat::parse("%d/%m").is_err());
+        assert!(DateFormat::parse("DD/MM/YYYY HH:mm").is_err());
+    }
+
+    #[test]
+    fn reads_day_first_and_month_first_values() {
+        assert_eq!(iso("06/01/2025", "DD/MM/YYYY").unwrap(), "2025-01-06");
+        assert_eq!(iso("06/01/2025", "MM/DD/YYYY").unwrap(), "2025-06-01");
+        assert_eq!(iso("6-1-2025", "DD/MM/YYYY").unwrap(), "2025-01-06");
+        assert_eq!(iso("2025.01.06", "YYYY-MM-DD").unwrap(), "2025-01-06");
+        assert_eq!(iso("20250106", "YYYYMMDD").unwrap(), "2025-01-06");
+        assert_eq!(iso("06012025", "DDMMYYYY").unwrap(), "2025-01-06");
+        assert_eq!(
+            iso("2025-01-06 00:00:00", "YYYY-MM-DD").unwrap(),
+            "2025-01-06"
+        );
+        assert_eq!(
+            iso("2025-01-06T10:30:00", "YYYY-MM-DD").unwrap(),
+            "2025-01-06"
+        );
+    }
+
+    #[test]
+    fn reads_two_digit_years() {
+        assert_eq!(iso("06/01/25", "DD/MM/YY").unwrap(), "2025-01-06");
+        assert_eq!(iso("06/01/50", "DD/MM/YY").unwrap(), "2050-01-06");
+        assert_eq!(iso("06/01/51", "DD/MM/YY").unwrap(), "1951-01-06");
+        assert_eq!(iso("06/01/99", "DD/MM/YYYY").unwrap(), "1999-01-06");
+        assert!(iso("06/01/025", "DD/MM/YYYY").is_err());
+        assert!(iso("06/01/20255", "DD/MM/YYYY").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_days() {
+        assert_eq!(
+            iso("30/02/2025", "DD/MM/YYYY").unwrap_err(),
+            "Impossible date: 30/02/2025"
+        );
+        assert_eq!(
+            iso("29/02/2025", "DD/MM/YYYY").unwrap_err(),
+            "Impossible date: 29/02/2025"
+        );
+        assert_eq!(iso("29/02/2024", "DD/MM/YYYY").unwrap(), "2024-02-29");
+        assert!(iso("31/04/2025", "DD/MM/YYYY").is_err());
+        assert!(iso("13/13/2025", "MM/DD/YYYY").is_err());
+        assert!(iso("00/01/2025", "DD/MM/YYYY").is_err());
+        assert_eq!(iso("1/2", "DD/MM/YYYY").unwrap_err(), "Invalid date: 1/2");
+        assert!(iso("aa/01/2025", "DD/MM/YYYY").is_err());
+        assert!(iso("2025016", "YYYYMMDD").is_err());
+    }
+
+    #[test]
+    fn detects_day_month_ambiguity() {
+        let dmy = format("DD/MM/YYYY");
+        let mdy = format("MM/DD/YYYY");
+        assert!(is_ambiguous(&["06/01/2025", "07/02/2025"], &dmy));
+        assert!(is_ambiguous(&["06/01/2025"], &mdy));
+        // A day past the 12th settles it
+        assert!(!is_ambiguous(&["06/01/2025", "13/01/2025"], &dmy));
+        // Dates that read the same either way do not
+        assert!(!is_ambiguous(&["01/01/2025", "05/05/2025", ""], &dmy));
+        assert!(!is_ambiguous(&["2025-01-06"], &format("YYYY-MM-DD")));
+        assert_eq!(dmy.swapped(), Some(mdy));
+    }
+
+    #[test]
+    fn recognizes_iso_dates() {
+        assert!(is_iso("2025-01-06"));
+        assert!(!is_iso("2025-1-6"));
+        assert!(!is_iso("2025-02-30"));
+        assert!(!is_iso("06/01/2025"));
+    }
+}
@@ -1,85 +1,27 @@
+mod amounts;
 mod commands;
+mod currency;
 mod database;
+mod dates;
+mod money;
+mod pivot;
+mod recurrence;
 
 use tauri_plugin_sql::{Migration, MigrationKind};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let migrations = vec![
-        Migration {
-            version: 1,
-            description: "create initial schema",
-            sql: database::SCHEMA,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 2,
-            description: "seed categories and keywords",
-            sql: database::SEED_CATEGORIES,
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 3,
-            description: "add has_header to import_sources",
-            sql: "ALTER TABLE import_sources ADD COLUMN has_header INTEGER NOT NULL DEFAULT 1;",
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 4,
-            description: "add is_inputable to categories",
-            sql: "ALTER TABLE categories ADD COLUMN is_inputable INTEGER NOT NULL DEFAULT 1;",
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 5,
-            description: "create import_config_templates table",
-            sql: "CREATE TABLE IF NOT EXISTS import_config_templates (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL UNIQUE,
-                delimiter TEXT NOT NULL DEFAULT ';',
-                encoding TEXT NOT NULL DEFAULT 'utf-8',
-                date_format TEXT NOT NULL DEFAULT 'DD/MM/YYYY',
-                skip_lines INTEGER NOT NULL DEFAULT 0,
-                has_header INTEGER NOT NULL DEFAULT 1,
-                column_mapping TEXT NOT NULL,
-                amount_mode TEXT NOT NULL DEFAULT 'single',
-                sign_convention TEXT NOT NULL DEFAULT 'negative_expense',
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            );",
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 6,
-            description: "change imported_files unique constraint from hash to filename",
-            sql: "CREATE TABLE imported_files_new (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                source_id INTEGER NOT NULL REFERENCES import_sources(id),
-                filename TEXT NOT NULL,
-                file_hash TEXT NOT NULL,
-                import_date DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                row_count INTEGER NOT NULL DEFAULT 0,
-                status TEXT NOT NULL DEFAULT 'completed',
-                notes TEXT,
-                UNIQUE(source_id, filename)
-            );
-            INSERT INTO imported_files_new SELECT * FROM imported_files;
-            DROP TABLE imported_files;
-            ALTER TABLE imported_files_new RENAME TO imported_files;",
-            kind: MigrationKind::Up,
-        },
-        Migration {
-            version: 7,
-            description: "add level-3 insurance subcategories",
-            sql: "INSERT OR IGNORE INTO categories (id, name, parent_id, type, color, sort_order) VALUES (310, 'Assurance-auto', 31, 'expense', '#14b8a6', 1);
-            INSERT OR IGNORE INTO categories (id, name, parent_id, type, color, sort_order) VALUES (311, 'Assurance-habitation', 31, 'expense', '#0d9488', 2);
-            INSERT OR IGNORE INTO categories (id, name, parent_id, type, color, sort_order) VALUES (312, 'Assurance-vie', 31, 'expense', '#0f766e', 3);
-            UPDATE categories SET is_inputable = 0 WHERE id = 31;
-            UPDATE keywords SET category_id = 310 WHERE keyword = 'BELAIR' AND category_id = 31;
-            UPDATE keywords SET category_id = 311 WHERE keyword = 'PRYSM' AND category_id = 31;
-            UPDATE keywords SET category_id = 312 WHERE keyword = 'INS/ASS' AND category_id = 31;",
+    database::encryption::install_key_hook();
+
+    let migrations = database::migrations::MIGRATIONS
+        .iter()
+        .map(|m| Migration {
+            version: m.version,
+            description: m.description,
+            sql: m.sql,
             kind: MigrationKind::Up,
-        },
-    ];
+        })
+        .collect();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -88,6 +30,11 @@ pub fn run() {
         .setup(|app| {
             #[cfg(desktop)]
             app.handle().plugin(tauri_plugin_updater::Builder::new().build())?;
+            commands::run_schema_self_check(app.handle().clone());
+            if let Err(e) = commands::purge_expired_profiles(app.handle()) {
+                eprintln!("Cannot purge deleted profiles: {}", e);
+            }
+            commands::start_backup_scheduler(app.handle().clone());
             Ok(())
         })
         .plugin(
@@ -107,13 +54,80 @@ pub fn run() {
             commands::write_export_file,
             commands::read_import_file,
             commands::is_file_encrypted,
+            commands::get_export_file_format,
+            commands::get_export_public_key,
             commands::load_profiles,
             commands::save_profiles,
+            commands::rebuild_profiles,
             commands::delete_profile_db,
+            commands::list_deleted_profiles,
+            commands::restore_deleted_profile,
+            commands::purge_deleted_profile,
             commands::get_new_profile_init_sql,
+            commands::clone_profile,
+            commands::export_profile_bundle,
+            commands::import_profile_bundle,
+            commands::merge_profile,
+            commands::get_household_monthly_trends,
+            commands::get_household_expenses_by_category,
+            commands::get_adjustment_occurrences,
+            commands::preview_recurrence_rule,
+            commands::get_pivot_report,
+            commands::get_pivot_filter_values,
+            commands::get_account_balances,
+            commands::get_account_balance_at,
+            commands::get_running_balances,
+            commands::record_statement_balance,
+            commands::get_statement_checks,
+            commands::delete_statement_balance,
+            commands::preview_reconciliation,
+            commands::complete_reconciliation,
+            commands::get_reconciliations,
+            commands::undo_reconciliation,
+            commands::import_exchange_rates,
+            commands::get_exchange_rate_coverage,
+            commands::delete_exchange_rates,
+            commands::get_base_currency,
+            commands::set_base_currency,
+            commands::set_transaction_currency,
+            commands::refresh_base_amounts,
+            commands::parse_import_dates,
+            commands::parse_import_amounts,
+            commands::audit_profile_dates,
+            commands::get_budget_envelopes,
+            commands::move_budget_money,
+            commands::get_budget_transfers,
+            commands::reverse_budget_transfer,
+            commands::set_budget_rollover,
+            commands::propose_budget,
             commands::hash_pin,
-            commands::verify_pin,
+            commands::verify_profile_pin,
+            commands::backup_profile,
+            commands::read_backup_manifest,
+            commands::restore_profile,
+            commands::get_backup_settings,
+            commands::save_backup_settings,
+            commands::get_backup_history,
+            commands::run_backup_now,
+            commands::unlock_profile_db,
+            commands::lock_profile_db,
+            commands::get_profile_db_encryption,
+            commands::encrypt_profile_db,
+            commands::decrypt_profile_db,
+            commands::change_profile_db_password,
+            commands::migrate_profile_db,
+            commands::check_schema,
+            commands::get_startup_schema_report,
+            commands::rollback_profile_db,
+            commands::snapshot_all_profiles,
+            commands::list_profile_snapshots,
+            commands::restore_previous_version,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                commands::run_scheduled_backups(app, commands::BackupTrigger::Exit);
+            }
+        });
 }
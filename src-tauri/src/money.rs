@@ -0,0 +1,134 @@
+//! Amounts of money in integer minor units (cents).
+//!
+//! Money columns are stored as INTEGER cents since schema v12, so sums are exact and
+//! amounts can be compared for equality. The frontend keeps exchanging decimal numbers
+//! (`-12.34`): `Money` serializes to one and deserializes from one, rounding to the
+//! nearest cent, which is lossless for any amount with at most two decimals.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub};
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Largest decimal amount converted to cents; beyond it an `f64` no longer holds every
+/// cent exactly.
+const MAX_DECIMAL: f64 = 90_000_000_000_000.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    /// Nearest cent to a decimal amount; `None` when it is not a finite, representable
+    /// amount.
+    pub fn from_decimal(value: f64) -> Option<Self> {
+        if value.is_finite() && value.abs() < MAX_DECIMAL {
+            Some(Money((value * 100.0).round() as i64))
+        } else {
+            None
+        }
+    }
+
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    pub fn to_decimal(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn abs(self) -> Self {
+        Money(self.0.abs())
+    }
+
+    /// Converts at `rate` units of the target currency per unit, to the nearest cent.
+    pub fn convert(self, rate: f64) -> Self {
+        Money((self.0 as f64 * rate).round() as i64)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+/// `-1234.50`, as in error messages and merge conflicts.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, cents / 100, cents % 100)
+    }
+}
+
+impl ToSql for Money {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0))
+    }
+}
+
+impl FromSql for Money {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Integer(cents) => Ok(Money(cents)),
+            // Whole values read back from an expression such as ROUND()
+            ValueRef::Real(cents) if cents.fract() == 0.0 && cents.abs() < i64::MAX as f64 => {
+                Ok(Money(cents as i64))
+            }
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_decimal())
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = f64::deserialize(deserializer)?;
+        Money::from_decimal(value)
+            .ok_or_else(|| serde::de::Error::custom(format!("Invalid amount: {}", value)))
+    }
+}
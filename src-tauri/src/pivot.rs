@@ -0,0 +1,643 @@
+//! Pivot engine behind the dynamic report. A `PivotConfig` picks row and column
+//! dimensions, filters and measures; field ids are checked against `FIELDS` before any
+//! SQL is built, and filter values are always bound as parameters.
+//!
+//! Subtotals are computed for every prefix of the row dimensions (all but the last),
+//! per column, and grand totals per column. Like the report table, they add up the
+//! measures of the underlying rows.
+//!
+//! Adjustment occurrences up to today count like transactions on the date they apply to.
+
+use chrono::Local;
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::commands::adjustment_commands::expand_adjustments;
+use crate::money::Money;
+
+/// Separator of composite column keys, as expected by the report table.
+const COLUMN_KEY_SEPARATOR: &str = "\0";
+
+struct PivotField {
+    id: &'static str,
+    select: &'static str,
+    label: &'static str,
+    needs_category_join: bool,
+}
+
+const FIELDS: &[PivotField] = &[
+    PivotField {
+        id: "year",
+        select: "strftime('%Y', t.date)",
+        label: "Année",
+        needs_category_join: false,
+    },
+    PivotField {
+        id: "month",
+        select: "strftime('%Y-%m', t.date)",
+        label: "Mois",
+        needs_category_join: false,
+    },
+    PivotField {
+        id: "type",
+        select: "COALESCE(c.type, 'expense')",
+        label: "Type",
+        needs_category_join: true,
+    },
+    PivotField {
+        id: "level1",
+        select: "COALESCE(grandparent_cat.name, parent_cat.name, c.name, 'Uncategorized')",
+        label: "Catégorie (Niveau 1)",
+        needs_category_join: true,
+    },
+    PivotField {
+        id: "level2",
+        select: "CASE WHEN grandparent_cat.id IS NOT NULL THEN parent_cat.name \
+                 WHEN parent_cat.id IS NOT NULL THEN c.name ELSE NULL END",
+        label: "Catégorie (Niveau 2)",
+        needs_category_join: true,
+    },
+    PivotField {
+        id: "level3",
+        select: "CASE WHEN grandparent_cat.id IS NOT NULL THEN c.name ELSE NULL END",
+        label: "Catégorie (Niveau 3)",
+        needs_category_join: true,
+    },
+];
+
+const MEASURE_PERIODIC: &str = "periodic";
+const MEASURE_YTD: &str = "ytd";
+
+const MEASURES: &[(&str, &str)] = &[
+    (MEASURE_PERIODIC, "Montant périodique"),
+    (MEASURE_YTD, "Cumul annuel (YTD)"),
+];
+
+const CATEGORY_JOIN: &str = "LEFT JOIN categories c ON t.category_id = c.id
+     LEFT JOIN categories parent_cat ON c.parent_id = parent_cat.id
+     LEFT JOIN categories grandparent_cat ON parent_cat.parent_id = grandparent_cat.id";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PivotFilterEntry {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PivotConfig {
+    pub rows: Vec<String>,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub filters: HashMap<String, PivotFilterEntry>,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PivotResultRow {
+    pub keys: BTreeMap<String, String>,
+    pub measures: BTreeMap<String, Money>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PivotResult {
+    pub rows: Vec<PivotResultRow>,
+    /// Distinct column keys, sorted; several column dimensions are joined with `\0`.
+    pub column_values: Vec<String>,
+    pub dimension_labels: BTreeMap<String, String>,
+    /// One row per row-dimension prefix and column, keyed by both.
+    pub subtotals: Vec<PivotResultRow>,
+    /// One row per column, keyed by the column dimensions.
+    pub grand_totals: Vec<PivotResultRow>,
+}
+
+fn field(id: &str) -> Result<&'static PivotField, String> {
+    FIELDS
+        .iter()
+        .find(|f| f.id == id)
+        .ok_or_else(|| format!("Unknown pivot field: {}", id))
+}
+
+/// Checks every field and measure id of the configuration.
+fn validate(config: &PivotConfig) -> Result<(), String> {
+    let dimensions: Vec<&String> = config.rows.iter().chain(&config.columns).collect();
+    for (index, id) in dimensions.iter().enumerate() {
+        field(id)?;
+        if dimensions[..index].contains(id) {
+            return Err(format!("Pivot field used twice: {}", id));
+        }
+    }
+    for id in config.filters.keys() {
+        field(id)?;
+    }
+    if config.values.is_empty() {
+        return Err("Select at least one measure".to_string());
+    }
+    for id in &config.values {
+        if !MEASURES.iter().any(|(m, _)| m == id) {
+            return Err(format!("Unknown pivot measure: {}", id));
+        }
+    }
+    Ok(())
+}
+
+fn filter_clauses(config: &PivotConfig) -> Result<(Vec<String>, Vec<Value>), String> {
+    let mut clauses = Vec::new();
+    let mut params = Vec::new();
+    // Sorted so that the generated SQL does not depend on map order
+    let mut filters: Vec<_> = config.filters.iter().collect();
+    filters.sort_by_key(|(id, _)| id.as_str());
+    for (id, entry) in filters {
+        let def = field(id)?;
+        for (values, operator) in [(&entry.include, "IN"), (&entry.exclude, "NOT IN")] {
+            if values.is_empty() {
+                continue;
+            }
+            let placeholders: Vec<String> = values
+                .iter()
+                .map(|v| {
+                    params.push(Value::Text(v.clone()));
+                    format!("?{}", params.len())
+                })
+                .collect();
+            clauses.push(format!(
+                "{} {} ({})",
+                def.select,
+                operator,
+                placeholders.join(", ")
+            ));
+        }
+    }
+    Ok((clauses, params))
+}
+
+/// Sums measures into `groups` under `key`, keeping first-seen order.
+fn accumulate(
+    groups: &mut Vec<PivotResultRow>,
+    index: &mut HashMap<Vec<String>, usize>,
+    key: Vec<String>,
+    keys: BTreeMap<String, String>,
+    measures: &BTreeMap<String, Money>,
+) {
+    let position = *index.entry(key).or_insert_with(|| {
+        groups.push(PivotResultRow {
+            keys,
+            measures: measures.keys().map(|m| (m.clone(), Money::ZERO)).collect(),
+        });
+        groups.len() - 1
+    });
+    for (measure, value) in measures {
+        *groups[position]
+            .measures
+            .entry(measure.clone())
+            .or_default() += *value;
+    }
+}
+
+/// Year a row belongs to, for the YTD measure.
+fn row_year(row: &PivotResultRow) -> String {
+    match row.keys.get("year") {
+        Some(year) => year.clone(),
+        None => row
+            .keys
+            .get("month")
+            .map(|m| m.chars().take(4).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// YTD: running total of the periodic amount within each year, per combination of
+/// the other dimensions.
+fn compute_ytd(rows: &mut [PivotResultRow], periodic: &[Money], config: &PivotConfig) {
+    let group_dims: Vec<&String> = config
+        .rows
+        .iter()
+        .chain(&config.columns)
+        .filter(|d| d.as_str() != "month")
+        .collect();
+
+    let mut order: Vec<usize> = (0..rows.len()).collect();
+    order.sort_by_cached_key(|&i| {
+        let month = rows[i].keys.get("month").cloned().unwrap_or_default();
+        format!("{}{}", row_year(&rows[i]), month)
+    });
+
+    let mut running: HashMap<(Vec<String>, String), Money> = HashMap::new();
+    for i in order {
+        let group: Vec<String> = group_dims
+            .iter()
+            .map(|d| rows[i].keys.get(*d).cloned().unwrap_or_default())
+            .collect();
+        let total = running.entry((group, row_year(&rows[i]))).or_default();
+        *total += periodic[i];
+        rows[i].measures.insert(MEASURE_YTD.to_string(), *total);
+    }
+}
+
+/// Transactions and adjustment occurrences as a single row source aliased `t`. The
+/// occurrence values are dates and integers computed here, not request input, so they
+/// are written into the SQL.
+fn transaction_source(conn: &Connection) -> Result<String, String> {
+    let occurrences = expand_adjustments(conn, None, Local::now().date_naive())?;
+    let values: Vec<String> = occurrences
+        .iter()
+        .flat_map(|o| {
+            o.entries.iter().map(move |e| {
+                format!(
+                    "('{}', {}, {}, NULL)",
+                    o.date,
+                    e.category_id,
+                    e.amount.cents()
+                )
+            })
+        })
+        .collect();
+    if values.is_empty() {
+        return Ok("transactions t".to_string());
+    }
+    Ok(format!(
+        "(SELECT date, category_id, amount, base_amount FROM transactions
+          UNION ALL VALUES {}) t",
+        values.join(", ")
+    ))
+}
+
+/// Runs the pivot query over the transactions and adjustment occurrences of `conn`.
+pub fn run_pivot(conn: &Connection, config: &PivotConfig) -> Result<PivotResult, String> {
+    validate(config)?;
+
+    let dimensions: Vec<&PivotField> = config
+        .rows
+        .iter()
+        .chain(&config.columns)
+        .map(|id| field(id))
+        .collect::<Result<_, _>>()?;
+    let needs_join = dimensions
+        .iter()
+        .copied()
+        .chain(config.filters.keys().filter_map(|id| field(id).ok()))
+        .any(|f| f.needs_category_join);
+
+    let mut select: Vec<String> = dimensions
+        .iter()
+        .map(|f| format!("{} AS {}", f.select, f.id))
+        .collect();
+    select.push("ABS(SUM(COALESCE(t.base_amount, t.amount))) AS periodic".to_string());
+    let group_by: Vec<&str> = dimensions.iter().map(|f| f.id).collect();
+    let (where_clauses, params) = filter_clauses(config)?;
+
+    let mut sql = format!(
+        "SELECT {} FROM {}",
+        select.join(", "),
+        transaction_source(conn)?
+    );
+    if needs_join {
+        sql.push(' ');
+        sql.push_str(CATEGORY_JOIN);
+    }
+    if !where_clauses.is_empty() {
+        sql.push_str(&format!(" WHERE {}", where_clauses.join(" AND ")));
+    }
+    if !group_by.is_empty() {
+        sql.push_str(&format!(" GROUP BY {0} ORDER BY {0}", group_by.join(", ")));
+    }
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Cannot build pivot query: {}", e))?;
+    let raw_rows = stmt
+        .query_map(rusqlite::params_from_iter(params), |row| {
+            let mut keys = BTreeMap::new();
+            for (i, f) in dimensions.iter().enumerate() {
+                let value: Option<String> = row.get(i)?;
+                keys.insert(f.id.to_string(), value.unwrap_or_default());
+            }
+            let periodic: Option<Money> = row.get(dimensions.len())?;
+            Ok((keys, periodic.unwrap_or_default()))
+        })
+        .map_err(|e| format!("Cannot run pivot query: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot run pivot query: {}", e))?;
+
+    let wants_periodic = config.values.iter().any(|m| m == MEASURE_PERIODIC);
+    let periodic: Vec<Money> = raw_rows.iter().map(|(_, amount)| *amount).collect();
+    let mut rows: Vec<PivotResultRow> = raw_rows
+        .into_iter()
+        .map(|(keys, amount)| {
+            let mut measures = BTreeMap::new();
+            if wants_periodic {
+                measures.insert(MEASURE_PERIODIC.to_string(), amount);
+            }
+            PivotResultRow { keys, measures }
+        })
+        .collect();
+    if config.values.iter().any(|m| m == MEASURE_YTD) {
+        compute_ytd(&mut rows, &periodic, config);
+    }
+
+    let key_of = |row: &PivotResultRow, dims: &[&String]| -> Vec<String> {
+        dims.iter()
+            .map(|d| row.keys.get(*d).cloned().unwrap_or_default())
+            .collect()
+    };
+    let column_dims: Vec<&String> = config.columns.iter().collect();
+
+    let mut column_values: Vec<String> = Vec::new();
+    if !column_dims.is_empty() {
+        for row in &rows {
+            let key = key_of(row, &column_dims).join(COLUMN_KEY_SEPARATOR);
+            if !column_values.contains(&key) {
+                column_values.push(key);
+            }
+        }
+        column_values.sort();
+    }
+
+    let mut subtotals = Vec::new();
+    for depth in 1..config.rows.len() {
+        let dims: Vec<&String> = config.rows[..depth].iter().chain(&config.columns).collect();
+        let mut index = HashMap::new();
+        for row in &rows {
+            let key = key_of(row, &dims);
+            let keys = dims.iter().cloned().cloned().zip(key.clone()).collect();
+            accumulate(&mut subtotals, &mut index, key, keys, &row.measures);
+        }
+    }
+
+    let mut grand_totals = Vec::new();
+    let mut index = HashMap::new();
+    for row in &rows {
+        let key = key_of(row, &column_dims);
+        let keys = column_dims
+            .iter()
+            .cloned()
+            .cloned()
+            .zip(key.clone())
+            .collect();
+        accumulate(&mut grand_totals, &mut index, key, keys, &row.measures);
+    }
+
+    let dimension_labels = FIELDS
+        .iter()
+        .map(|f| (f.id.to_string(), f.label.to_string()))
+        .chain(
+            MEASURES
+                .iter()
+                .map(|(id, l)| (id.to_string(), l.to_string())),
+        )
+        .collect();
+
+    Ok(PivotResult {
+        rows,
+        column_values,
+        dimension_labels,
+        subtotals,
+        grand_totals,
+    })
+}
+
+/// Distinct values of a field, for the filter pickers.
+pub fn filter_values(conn: &Connection, field_id: &str) -> Result<Vec<String>, String> {
+    let def = field(field_id)?;
+    let join = if def.needs_category_join {
+        CATEGORY_JOIN
+    } else {
+        ""
+    };
+    let sql = format!(
+        "SELECT DISTINCT {} AS val FROM {} {} ORDER BY val",
+        def.select,
+        transaction_source(conn)?,
+        join
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Cannot read filter values: {}", e))?;
+    let values = stmt
+        .query_map([], |row| row.get::<_, Option<String>>(0))
+        .map_err(|e| format!("Cannot read filter values: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Cannot read filter values: {}", e))?;
+    Ok(values.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::migrated_test_db;
+
+    fn money(value: f64) -> Money {
+        Money::from_decimal(value).unwrap()
+    }
+
+    /// Seeded schema with a few transactions over two years. Categories come from the
+    /// seed: 1 Revenus (income) > 10 Paie; 2 Dépenses récurrentes > 20 Loyer and
+    /// 2 Dépenses récurrentes > 31 Assurances > 310 Assurance-auto.
+    fn fixture() -> Connection {
+        let conn = migrated_test_db();
+        let transactions = [
+            ("2024-12-15", -900.0, Some(20)),
+            ("2025-01-01", 3000.0, Some(10)),
+            ("2025-01-05", -1000.0, Some(20)),
+            ("2025-01-20", -120.0, Some(310)),
+            ("2025-02-05", -1000.0, Some(20)),
+            ("2025-02-20", -80.0, Some(310)),
+            ("2025-02-21", -15.0, None),
+        ];
+        for (date, amount, category_id) in transactions {
+            conn.execute(
+                "INSERT INTO transactions (date, description, amount, category_id)
+                 VALUES (?1, 'fixture', ?2, ?3)",
+                rusqlite::params![date, money(amount), category_id],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn config(rows: &[&str], columns: &[&str], values: &[&str]) -> PivotConfig {
+        let strings = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect();
+        PivotConfig {
+            rows: strings(rows),
+            columns: strings(columns),
+            filters: HashMap::new(),
+            values: strings(values),
+        }
+    }
+
+    fn keys(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn find<'a>(rows: &'a [PivotResultRow], pairs: &[(&str, &str)]) -> &'a PivotResultRow {
+        let wanted = keys(pairs);
+        rows.iter()
+            .find(|r| r.keys == wanted)
+            .unwrap_or_else(|| panic!("no row {:?} in {:#?}", wanted, rows))
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_measures() {
+        let conn = fixture();
+        let err = run_pivot(
+            &conn,
+            &config(&["t.amount; DROP TABLE x"], &[], &["periodic"]),
+        );
+        assert!(err.unwrap_err().starts_with("Unknown pivot field"));
+        let err = run_pivot(&conn, &config(&["year"], &[], &["median"]));
+        assert!(err.unwrap_err().starts_with("Unknown pivot measure"));
+        let err = run_pivot(&conn, &config(&["year"], &["year"], &["periodic"]));
+        assert!(err.unwrap_err().starts_with("Pivot field used twice"));
+
+        let mut with_filter = config(&["year"], &[], &["periodic"]);
+        with_filter
+            .filters
+            .insert("1=1) OR (1".to_string(), PivotFilterEntry::default());
+        assert!(run_pivot(&conn, &with_filter).is_err());
+        assert!(filter_values(&conn, "description").is_err());
+    }
+
+    #[test]
+    fn groups_rows_and_columns() {
+        let conn = fixture();
+        let result = run_pivot(&conn, &config(&["level1"], &["year"], &["periodic"])).unwrap();
+        assert_eq!(result.column_values, vec!["2024", "2025"]);
+        let recurring = find(
+            &result.rows,
+            &[("level1", "Dépenses récurrentes"), ("year", "2025")],
+        );
+        assert_eq!(recurring.measures["periodic"], money(2200.0));
+        let other = find(
+            &result.rows,
+            &[("level1", "Uncategorized"), ("year", "2025")],
+        );
+        assert_eq!(other.measures["periodic"], money(15.0));
+        assert!(!recurring.measures.contains_key("ytd"));
+    }
+
+    #[test]
+    fn computes_subtotals_and_grand_totals() {
+        let conn = fixture();
+        let result = run_pivot(
+            &conn,
+            &config(&["level1", "level2"], &["year"], &["periodic"]),
+        )
+        .unwrap();
+
+        let recurring = find(
+            &result.subtotals,
+            &[("level1", "Dépenses récurrentes"), ("year", "2025")],
+        );
+        assert_eq!(recurring.measures["periodic"], money(2200.0));
+        let insurance = find(
+            &result.rows,
+            &[
+                ("level1", "Dépenses récurrentes"),
+                ("level2", "Assurances"),
+                ("year", "2025"),
+            ],
+        );
+        assert_eq!(insurance.measures["periodic"], money(200.0));
+        // Subtotals only exist for strict prefixes of the row dimensions
+        assert!(result
+            .subtotals
+            .iter()
+            .all(|r| !r.keys.contains_key("level2")));
+
+        let total_2025 = find(&result.grand_totals, &[("year", "2025")]);
+        assert_eq!(
+            total_2025.measures["periodic"],
+            money(3000.0 + 2000.0 + 200.0 + 15.0)
+        );
+        let total_2024 = find(&result.grand_totals, &[("year", "2024")]);
+        assert_eq!(total_2024.measures["periodic"], money(900.0));
+    }
+
+    #[test]
+    fn accumulates_ytd_within_each_year() {
+        let conn = fixture();
+        let result = run_pivot(&conn, &config(&["level2"], &["month"], &["ytd"])).unwrap();
+        let rent = |month: &str| {
+            find(&result.rows, &[("level2", "Loyer"), ("month", month)]).measures["ytd"]
+        };
+        assert_eq!(rent("2024-12"), money(900.0));
+        assert_eq!(rent("2025-01"), money(1000.0));
+        assert_eq!(rent("2025-02"), money(2000.0));
+        assert!(result
+            .rows
+            .iter()
+            .all(|r| !r.measures.contains_key("periodic")));
+    }
+
+    #[test]
+    fn applies_include_and_exclude_filters() {
+        let conn = fixture();
+        let mut cfg = config(&["level1"], &[], &["periodic"]);
+        cfg.filters.insert(
+            "type".to_string(),
+            PivotFilterEntry {
+                include: vec!["expense".to_string()],
+                exclude: Vec::new(),
+            },
+        );
+        cfg.filters.insert(
+            "year".to_string(),
+            PivotFilterEntry {
+                include: Vec::new(),
+                exclude: vec!["2024".to_string()],
+            },
+        );
+        let result = run_pivot(&conn, &cfg).unwrap();
+        assert!(result.rows.iter().all(|r| r.keys["level1"] != "Revenus"));
+        let recurring = find(&result.rows, &[("level1", "Dépenses récurrentes")]);
+        assert_eq!(recurring.measures["periodic"], money(2200.0));
+        assert_eq!(
+            result.grand_totals,
+            vec![PivotResultRow {
+                keys: BTreeMap::new(),
+                measures: BTreeMap::from([("periodic".to_string(), money(2215.0))]),
+            }]
+        );
+    }
+
+    #[test]
+    fn lists_filter_values() {
+        let conn = fixture();
+        assert_eq!(filter_values(&conn, "year").unwrap(), vec!["2024", "2025"]);
+        assert_eq!(
+            filter_values(&conn, "type").unwrap(),
+            vec!["expense", "income"]
+        );
+    }
+
+    #[test]
+    fn counts_adjustment_occurrences_up_to_today() {
+        let conn = fixture();
+        conn.execute_batch(
+            "INSERT INTO adjustments (id, name, date, is_recurring, recurrence_rule)
+             VALUES (1, 'Parking', '2025-01-10', 1, 'FREQ=MONTHLY;COUNT=2'),
+                    (2, 'Later', '2099-01-01', 0, NULL);
+             INSERT INTO adjustment_entries (adjustment_id, category_id, amount)
+             VALUES (1, 20, -5000), (2, 20, -5000);",
+        )
+        .unwrap();
+
+        let result = run_pivot(&conn, &config(&["level1"], &["month"], &["periodic"])).unwrap();
+        for month in ["2025-01", "2025-02"] {
+            let recurring = find(
+                &result.rows,
+                &[("level1", "Dépenses récurrentes"), ("month", month)],
+            );
+            let transactions = if month == "2025-01" { 1120.0 } else { 1080.0 };
+            assert_eq!(recurring.measures["periodic"], money(transactions + 50.0));
+        }
+        assert_eq!(filter_values(&conn, "year").unwrap(), vec!["2024", "2025"]);
+    }
+}
@@ -0,0 +1,363 @@
+//! RFC 5545 recurrence rules (`RRULE`), limited to the parts adjustments need: `FREQ`
+//! (daily to yearly), `INTERVAL`, `BYMONTHDAY`, `UNTIL` and `COUNT`. Occurrences are
+//! plain dates; times in `UNTIL` are ignored.
+//!
+//! As in RFC 5545, the start date only counts as an occurrence when it matches the rule,
+//! and days that do not exist in a period (the 31st in a 30-day month, February 29th in
+//! a common year) are skipped rather than moved.
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// Periods walked at most while expanding a rule, as a guard against rules whose
+/// `BYMONTHDAY` never matches.
+const MAX_PERIODS: i64 = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// Days of the month, 1 to 31, or -1 (last day) to -31.
+    pub by_month_day: Vec<i32>,
+    pub until: Option<NaiveDate>,
+    pub count: Option<u32>,
+}
+
+/// `UNTIL` is a date (`20251231`) or a date-time (`20251231T235959Z`).
+fn parse_until(value: &str) -> Result<NaiveDate, String> {
+    value
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .filter(|_| value.len() == 8 || value[8..].starts_with('T'))
+        .ok_or_else(|| format!("Invalid UNTIL date: {}", value))
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map_or(28, |d| d.day())
+}
+
+impl RecurrenceRule {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_month_day = Vec::new();
+        let mut until = None;
+        let mut count = None;
+
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid recurrence rule part: {}", part))?;
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("Unsupported frequency: {}", value)),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse()
+                        .ok()
+                        .filter(|i| *i > 0)
+                        .ok_or_else(|| format!("Invalid INTERVAL: {}", value))?
+                }
+                "BYMONTHDAY" => {
+                    for day in value.split(',') {
+                        let day: i32 = day
+                            .parse()
+                            .ok()
+                            .filter(|d: &i32| *d != 0 && (-31..=31).contains(d))
+                            .ok_or_else(|| format!("Invalid BYMONTHDAY: {}", value))?;
+                        by_month_day.push(day);
+                    }
+                }
+                "UNTIL" => until = Some(parse_until(value)?),
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|c| *c > 0)
+                            .ok_or_else(|| format!("Invalid COUNT: {}", value))?,
+                    )
+                }
+                // Only meaningful with BYDAY or BYWEEKNO, which are not supported
+                "WKST" => {}
+                other => return Err(format!("Unsupported recurrence rule part: {}", other)),
+            }
+        }
+
+        if until.is_some() && count.is_some() {
+            return Err("UNTIL and COUNT cannot be combined".to_string());
+        }
+        Ok(RecurrenceRule {
+            frequency: frequency.ok_or_else(|| "FREQ is required".to_string())?,
+            interval,
+            by_month_day,
+            until,
+            count,
+        })
+    }
+
+    /// Resolves the `BYMONTHDAY` days that exist in a month, in order.
+    fn month_days(&self, year: i32, month: u32) -> Vec<NaiveDate> {
+        let last = days_in_month(year, month) as i32;
+        let mut days: Vec<NaiveDate> = self
+            .by_month_day
+            .iter()
+            .map(|d| if *d > 0 { *d } else { last + d + 1 })
+            .filter(|d| (1..=last).contains(d))
+            .filter_map(|d| NaiveDate::from_ymd_opt(year, month, d as u32))
+            .collect();
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    fn matches_month_day(&self, date: NaiveDate) -> bool {
+        self.by_month_day.is_empty() || self.month_days(date.year(), date.month()).contains(&date)
+    }
+
+    /// First day of the `n`-th period after the start and the candidate dates in it.
+    fn period(&self, dtstart: NaiveDate, n: i64) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+        let step = n * self.interval as i64;
+        match self.frequency {
+            Frequency::Daily | Frequency::Weekly => {
+                let days = if self.frequency == Frequency::Daily {
+                    step
+                } else {
+                    step * 7
+                };
+                let date = dtstart.checked_add_signed(Duration::try_days(days)?)?;
+                let dates = if self.matches_month_day(date) {
+                    vec![date]
+                } else {
+                    Vec::new()
+                };
+                Some((date, dates))
+            }
+            Frequency::Monthly => {
+                let months = dtstart.year() as i64 * 12 + dtstart.month0() as i64 + step;
+                let year = i32::try_from(months.div_euclid(12)).ok()?;
+                let month = months.rem_euclid(12) as u32 + 1;
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let dates = if self.by_month_day.is_empty() {
+                    NaiveDate::from_ymd_opt(year, month, dtstart.day())
+                        .into_iter()
+                        .collect()
+                } else {
+                    self.month_days(year, month)
+                };
+                Some((first, dates))
+            }
+            Frequency::Yearly => {
+                let year = i32::try_from(dtstart.year() as i64 + step).ok()?;
+                let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let dates = if self.by_month_day.is_empty() {
+                    NaiveDate::from_ymd_opt(year, dtstart.month(), dtstart.day())
+                        .into_iter()
+                        .collect()
+                } else {
+                    // Without BYMONTH, BYMONTHDAY applies to every month of the year
+                    (1..=12).flat_map(|m| self.month_days(year, m)).collect()
+                };
+                Some((first, dates))
+            }
+        }
+    }
+
+    /// All occurrences from `dtstart` up to `limit` (inclusive), stopping earlier at
+    /// `UNTIL` or once `COUNT` occurrences were produced.
+    pub fn occurrences(&self, dtstart: NaiveDate, limit: NaiveDate) -> Vec<NaiveDate> {
+        let end = self.until.map_or(limit, |until| until.min(limit));
+        let mut occurrences = Vec::new();
+        for n in 0..MAX_PERIODS {
+            let Some((period_start, dates)) = self.period(dtstart, n) else {
+                break;
+            };
+            if period_start > end {
+                break;
+            }
+            for date in dates.into_iter().filter(|d| *d >= dtstart) {
+                if date > end {
+                    return occurrences;
+                }
+                occurrences.push(date);
+                if self.count.is_some_and(|c| occurrences.len() >= c as usize) {
+                    return occurrences;
+                }
+            }
+        }
+        occurrences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn expand(rule: &str, start: &str, limit: &str) -> Vec<String> {
+        RecurrenceRule::parse(rule)
+            .unwrap()
+            .occurrences(date(start), date(limit))
+            .iter()
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn applies_interval() {
+        assert_eq!(
+            expand("FREQ=WEEKLY;INTERVAL=2", "2025-01-06", "2025-02-10"),
+            vec!["2025-01-06", "2025-01-20", "2025-02-03"]
+        );
+        assert_eq!(
+            expand("RRULE:FREQ=MONTHLY;INTERVAL=3", "2025-01-15", "2025-12-31"),
+            vec!["2025-01-15", "2025-04-15", "2025-07-15", "2025-10-15"]
+        );
+        assert_eq!(
+            expand("FREQ=DAILY;INTERVAL=10", "2025-12-25", "2026-01-15"),
+            vec!["2025-12-25", "2026-01-04", "2026-01-14"]
+        );
+    }
+
+    #[test]
+    fn counts_negative_month_days_from_the_end() {
+        assert_eq!(
+            expand("FREQ=MONTHLY;BYMONTHDAY=-1", "2024-01-01", "2024-04-30"),
+            vec!["2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30"]
+        );
+        assert_eq!(
+            expand("FREQ=MONTHLY;BYMONTHDAY=1,-2", "2025-02-01", "2025-03-31"),
+            vec!["2025-02-01", "2025-02-27", "2025-03-01", "2025-03-30"]
+        );
+    }
+
+    #[test]
+    fn skips_days_missing_from_february() {
+        // Days past the end of the month are skipped, not moved to the last day
+        assert_eq!(
+            expand("FREQ=MONTHLY;BYMONTHDAY=30,31", "2025-01-01", "2025-03-31"),
+            vec!["2025-01-30", "2025-01-31", "2025-03-30", "2025-03-31"]
+        );
+        assert_eq!(
+            expand("FREQ=MONTHLY", "2025-01-31", "2025-05-31"),
+            vec!["2025-01-31", "2025-03-31", "2025-05-31"]
+        );
+        assert_eq!(
+            expand("FREQ=YEARLY", "2024-02-29", "2032-12-31"),
+            vec!["2024-02-29", "2028-02-29", "2032-02-29"]
+        );
+    }
+
+    #[test]
+    fn counts_the_start_only_when_it_matches() {
+        assert_eq!(
+            expand("FREQ=MONTHLY;BYMONTHDAY=1", "2025-01-15", "2025-03-31"),
+            vec!["2025-02-01", "2025-03-01"]
+        );
+    }
+
+    #[test]
+    fn stops_at_until_count_or_limit() {
+        assert_eq!(
+            expand("FREQ=MONTHLY;UNTIL=20250315", "2025-01-15", "2025-12-31"),
+            vec!["2025-01-15", "2025-02-15", "2025-03-15"]
+        );
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;UNTIL=20250314T235959Z",
+                "2025-01-15",
+                "2025-12-31"
+            ),
+            vec!["2025-01-15", "2025-02-15"]
+        );
+        // The limit wins over a later UNTIL or a larger COUNT
+        assert_eq!(
+            expand("FREQ=MONTHLY;UNTIL=20251231", "2025-01-15", "2025-02-28"),
+            vec!["2025-01-15", "2025-02-15"]
+        );
+        assert_eq!(
+            expand("FREQ=WEEKLY;COUNT=3", "2025-01-06", "2025-12-31"),
+            vec!["2025-01-06", "2025-01-13", "2025-01-20"]
+        );
+        assert_eq!(
+            expand("FREQ=WEEKLY;COUNT=10", "2025-01-06", "2025-01-14"),
+            vec!["2025-01-06", "2025-01-13"]
+        );
+        // COUNT includes every date of a period with several BYMONTHDAY values
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYMONTHDAY=1,15;COUNT=3",
+                "2025-01-01",
+                "2025-12-31"
+            ),
+            vec!["2025-01-01", "2025-01-15", "2025-02-01"]
+        );
+
+        let err = RecurrenceRule::parse("FREQ=MONTHLY;COUNT=3;UNTIL=20251231").unwrap_err();
+        assert_eq!(err, "UNTIL and COUNT cannot be combined");
+    }
+
+    #[test]
+    fn rejects_unsupported_rules() {
+        let err = |rule: &str| RecurrenceRule::parse(rule).unwrap_err();
+        assert_eq!(err("FREQ=HOURLY"), "Unsupported frequency: HOURLY");
+        assert_eq!(
+            err("FREQ=SECONDLY;COUNT=2"),
+            "Unsupported frequency: SECONDLY"
+        );
+        assert_eq!(
+            err("FREQ=MONTHLY;BYDAY=MO"),
+            "Unsupported recurrence rule part: BYDAY"
+        );
+        assert_eq!(err("INTERVAL=2"), "FREQ is required");
+        assert_eq!(err("FREQ=WEEKLY;INTERVAL=0"), "Invalid INTERVAL: 0");
+        assert_eq!(err("FREQ=MONTHLY;BYMONTHDAY=0"), "Invalid BYMONTHDAY: 0");
+        assert_eq!(err("FREQ=MONTHLY;BYMONTHDAY=32"), "Invalid BYMONTHDAY: 32");
+        assert_eq!(
+            err("FREQ=MONTHLY;UNTIL=2025-12-31"),
+            "Invalid UNTIL date: 2025-12-31"
+        );
+        assert_eq!(err("FREQ"), "Invalid recurrence rule part: FREQ");
+        assert!(RecurrenceRule::parse("freq=weekly;wkst=MO").is_ok());
+    }
+
+    #[test]
+    fn stops_after_max_periods() {
+        let daily = RecurrenceRule::parse("FREQ=DAILY").unwrap();
+        assert_eq!(
+            daily.occurrences(date("2025-01-01"), NaiveDate::MAX).len(),
+            MAX_PERIODS as usize
+        );
+        // Every period is a February, so the 30th never occurs
+        let never = RecurrenceRule::parse("FREQ=MONTHLY;INTERVAL=12;BYMONTHDAY=30").unwrap();
+        assert!(never
+            .occurrences(date("2025-02-01"), NaiveDate::MAX)
+            .is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 import { BrowserRouter, Routes, Route } from "react-router-dom";
 import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
 import { useProfile } from "./contexts/ProfileContext";
 import AppShell from "./components/layout/AppShell";
 import DashboardPage from "./pages/DashboardPage";
@@ -12,20 +13,46 @@ import ReportsPage from "./pages/ReportsPage";
 import SettingsPage from "./pages/SettingsPage";
 import DocsPage from "./pages/DocsPage";
 import ProfileSelectionPage from "./pages/ProfileSelectionPage";
+import DbPasswordDialog from "./components/profile/DbPasswordDialog";
+import { getProfileDbEncryption } from "./services/profileService";
 
 export default function App() {
+  const { t } = useTranslation();
   const { activeProfile, isLoading, refreshKey, connectActiveProfile } = useProfile();
   const [dbReady, setDbReady] = useState(false);
+  const [connectError, setConnectError] = useState<string | null>(null);
+  const [needsPassword, setNeedsPassword] = useState(false);
 
   useEffect(() => {
     if (activeProfile && !isLoading) {
       setDbReady(false);
-      connectActiveProfile()
-        .then(() => setDbReady(true))
-        .catch((err) => console.error("Failed to connect profile:", err));
+      setConnectError(null);
+      setNeedsPassword(false);
+      // An encrypted database stays locked until its password is entered in this session
+      const locked = activeProfile.db_key_salt
+        ? getProfileDbEncryption(activeProfile.id).then((state) => state.encrypted && !state.unlocked)
+        : Promise.resolve(false);
+      locked
+        .then((isLocked) => {
+          if (isLocked) {
+            setNeedsPassword(true);
+            return;
+          }
+          return connectActiveProfile().then(() => setDbReady(true));
+        })
+        .catch((err) => {
+          console.error("Failed to connect profile:", err);
+          setConnectError(String(err));
+        });
     }
   }, [activeProfile, isLoading, connectActiveProfile]);
 
+  const handlePassword = async (password: string) => {
+    await connectActiveProfile(password);
+    setNeedsPassword(false);
+    setDbReady(true);
+  };
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center h-screen bg-[var(--background)]">
@@ -38,6 +65,23 @@ export default function App() {
     return <ProfileSelectionPage />;
   }
 
+  if (connectError) {
+    return (
+      <div className="flex flex-col items-center justify-center h-screen gap-2 p-6 bg-[var(--background)] text-center">
+        <p className="font-semibold text-[var(--foreground)]">{t("profile.connectFailed")}</p>
+        <p className="text-sm text-[var(--negative)]">{connectError}</p>
+      </div>
+    );
+  }
+
+  if (needsPassword) {
+    return (
+      <div className="h-screen bg-[var(--background)]">
+        <DbPasswordDialog profileName={activeProfile.name} onSubmit={handlePassword} />
+      </div>
+    );
+  }
+
   if (!dbReady) {
     return (
       <div className="flex items-center justify-center h-screen bg-[var(--background)]">
@@ -1,13 +1,24 @@
+import { useState } from "react";
 import { useTranslation } from "react-i18next";
-import { Pencil, RefreshCw } from "lucide-react";
-import type { Adjustment, Category } from "../../shared/types";
-import type { AdjustmentEntryWithCategory } from "../../services/adjustmentService";
+import { Pencil, RefreshCw, RotateCcw, SkipForward } from "lucide-react";
+import type {
+  Adjustment,
+  AdjustmentException,
+  AdjustmentOccurrence,
+  Category,
+} from "../../shared/types";
+import type {
+  AdjustmentEntryWithCategory,
+  overrideOccurrence,
+} from "../../services/adjustmentService";
 import type { AdjustmentFormData, EntryFormData } from "../../hooks/useAdjustments";
 import AdjustmentForm from "./AdjustmentForm";
 
 interface Props {
   selectedAdjustment: Adjustment | null;
   entries: AdjustmentEntryWithCategory[];
+  upcomingOccurrences: AdjustmentOccurrence[];
+  exceptions: AdjustmentException[];
   categories: Category[];
   editingAdjustment: AdjustmentFormData | null;
   editingEntries: EntryFormData[];
@@ -17,11 +28,25 @@ interface Props {
   onCancelEditing: () => void;
   onSave: (data: AdjustmentFormData, entries: EntryFormData[]) => void;
   onDelete: (id: number) => void;
+  onSetOccurrenceSkipped: (occurrenceDate: string, skipped: boolean) => void;
+  onModifyOccurrence: (
+    occurrenceDate: string,
+    override: Parameters<typeof overrideOccurrence>[2]
+  ) => void;
+}
+
+/** Occurrence being modified: its new date and one amount per entry, as typed. */
+interface OccurrenceEdit {
+  occurrenceDate: string;
+  date: string;
+  amounts: string[];
 }
 
 export default function AdjustmentDetailPanel({
   selectedAdjustment,
   entries,
+  upcomingOccurrences,
+  exceptions,
   categories,
   editingAdjustment,
   editingEntries,
@@ -31,8 +56,11 @@ export default function AdjustmentDetailPanel({
   onCancelEditing,
   onSave,
   onDelete,
+  onSetOccurrenceSkipped,
+  onModifyOccurrence,
 }: Props) {
   const { t } = useTranslation();
+  const [occurrenceEdit, setOccurrenceEdit] = useState<OccurrenceEdit | null>(null);
 
   const handleDelete = () => {
     if (!selectedAdjustment) return;
@@ -90,6 +118,30 @@ export default function AdjustmentDetailPanel({
 
   // Read-only view
   const total = entries.reduce((sum, e) => sum + e.amount, 0);
+  const skipped = exceptions.filter((e) => e.is_cancelled);
+
+  const saveOccurrence = (occurrence: AdjustmentOccurrence) => {
+    if (!occurrenceEdit) return;
+    const amounts = occurrenceEdit.amounts.map((a) => parseFloat(a) || 0);
+    // Amounts equal to the adjustment's own keep following it if it is edited later
+    const keepsOwnAmounts =
+      occurrence.entries.length === entries.length &&
+      occurrence.entries.every((entry, i) =>
+        entries.some((e) => e.category_id === entry.category_id && e.amount === amounts[i])
+      );
+    onModifyOccurrence(occurrence.occurrence_date, {
+      date:
+        occurrenceEdit.date !== occurrence.occurrence_date ? occurrenceEdit.date : undefined,
+      entries: keepsOwnAmounts
+        ? undefined
+        : occurrence.entries.map((entry, i) => ({
+            category_id: entry.category_id,
+            amount: amounts[i],
+            description: entry.description ?? undefined,
+          })),
+    });
+    setOccurrenceEdit(null);
+  };
 
   return (
     <div className="flex-1 bg-[var(--card)] rounded-xl border border-[var(--border)] p-6 overflow-y-auto">
@@ -116,7 +168,11 @@ export default function AdjustmentDetailPanel({
         </div>
         <div>
           <span className="text-[var(--muted-foreground)]">{t("adjustments.recurring")}</span>
-          <p className="font-medium">{selectedAdjustment.is_recurring ? "Yes" : "No"}</p>
+          <p className="font-medium">
+            {selectedAdjustment.is_recurring
+              ? selectedAdjustment.recurrence_rule || t("adjustments.frequencies.MONTHLY")
+              : "No"}
+          </p>
         </div>
         {selectedAdjustment.description && (
           <div className="col-span-2">
@@ -182,6 +238,149 @@ export default function AdjustmentDetailPanel({
           </table>
         )}
       </div>
+
+      {selectedAdjustment.is_recurring && (
+        <div className="border-t border-[var(--border)] pt-4 mt-6">
+          <h3 className="text-sm font-semibold mb-3">{t("adjustments.upcomingOccurrences")}</h3>
+          {upcomingOccurrences.length === 0 ? (
+            <p className="text-xs text-[var(--muted-foreground)]">{t("adjustments.noOccurrences")}</p>
+          ) : (
+            <div className="flex flex-col gap-1">
+              {upcomingOccurrences.map((occurrence) =>
+                occurrenceEdit?.occurrenceDate === occurrence.occurrence_date ? (
+                  <div
+                    key={occurrence.occurrence_date}
+                    className="flex flex-col gap-2 text-sm py-2 px-3 rounded-lg bg-[var(--muted)]"
+                  >
+                    <label className="flex items-center justify-between gap-2">
+                      <span className="text-[var(--muted-foreground)]">{t("adjustments.date")}</span>
+                      <input
+                        type="date"
+                        value={occurrenceEdit.date}
+                        onChange={(e) =>
+                          setOccurrenceEdit({ ...occurrenceEdit, date: e.target.value })
+                        }
+                        className="px-2 py-1 rounded-lg bg-[var(--background)] border border-[var(--border)] text-sm"
+                      />
+                    </label>
+                    {occurrence.entries.map((entry, i) => (
+                      <label
+                        key={`${entry.category_id}-${i}`}
+                        className="flex items-center justify-between gap-2"
+                      >
+                        <span className="flex items-center gap-2">
+                          <span
+                            className="w-2.5 h-2.5 rounded-full flex-shrink-0"
+                            style={{ backgroundColor: entry.category_color }}
+                          />
+                          {entry.category_name}
+                        </span>
+                        <input
+                          type="number"
+                          step="0.01"
+                          value={occurrenceEdit.amounts[i]}
+                          onChange={(e) =>
+                            setOccurrenceEdit({
+                              ...occurrenceEdit,
+                              amounts: occurrenceEdit.amounts.map((a, j) =>
+                                j === i ? e.target.value : a
+                              ),
+                            })
+                          }
+                          className="w-32 px-2 py-1 rounded-lg bg-[var(--background)] border border-[var(--border)] text-sm text-right"
+                        />
+                      </label>
+                    ))}
+                    <div className="flex justify-end gap-2">
+                      <button
+                        onClick={() => setOccurrenceEdit(null)}
+                        className="px-3 py-1 rounded-lg border border-[var(--border)] text-xs hover:bg-[var(--background)]"
+                      >
+                        {t("common.cancel")}
+                      </button>
+                      <button
+                        onClick={() => saveOccurrence(occurrence)}
+                        disabled={!occurrenceEdit.date}
+                        className="px-3 py-1 rounded-lg bg-[var(--primary)] text-white text-xs hover:opacity-90 disabled:opacity-50"
+                      >
+                        {t("common.save")}
+                      </button>
+                    </div>
+                  </div>
+                ) : (
+                  <div
+                    key={occurrence.occurrence_date}
+                    className="flex items-center justify-between text-sm py-1"
+                  >
+                    <span>
+                      {occurrence.date}
+                      {occurrence.is_overridden && (
+                        <span className="ml-2 text-xs text-[var(--muted-foreground)]">
+                          {t("adjustments.modifiedOccurrence", { date: occurrence.occurrence_date })}
+                        </span>
+                      )}
+                    </span>
+                    <div className="flex items-center gap-1">
+                      <button
+                        onClick={() =>
+                          setOccurrenceEdit({
+                            occurrenceDate: occurrence.occurrence_date,
+                            date: occurrence.date,
+                            amounts: occurrence.entries.map((e) => String(e.amount)),
+                          })
+                        }
+                        className="flex items-center gap-1 px-2 py-1 rounded-lg text-xs hover:bg-[var(--muted)]"
+                      >
+                        <Pencil size={12} />
+                        {t("adjustments.modifyOccurrence")}
+                      </button>
+                      {occurrence.is_overridden && (
+                        <button
+                          onClick={() => onSetOccurrenceSkipped(occurrence.occurrence_date, false)}
+                          className="flex items-center gap-1 px-2 py-1 rounded-lg text-xs hover:bg-[var(--muted)]"
+                        >
+                          <RotateCcw size={12} />
+                          {t("adjustments.resetOccurrence")}
+                        </button>
+                      )}
+                      <button
+                        onClick={() => onSetOccurrenceSkipped(occurrence.occurrence_date, true)}
+                        className="flex items-center gap-1 px-2 py-1 rounded-lg text-xs hover:bg-[var(--muted)]"
+                      >
+                        <SkipForward size={12} />
+                        {t("adjustments.skipOccurrence")}
+                      </button>
+                    </div>
+                  </div>
+                )
+              )}
+            </div>
+          )}
+
+          {skipped.length > 0 && (
+            <>
+              <h3 className="text-sm font-semibold mt-4 mb-3">{t("adjustments.skippedOccurrences")}</h3>
+              <div className="flex flex-col gap-1">
+                {skipped.map((exception) => (
+                  <div
+                    key={exception.id}
+                    className="flex items-center justify-between text-sm py-1 text-[var(--muted-foreground)]"
+                  >
+                    <span className="line-through">{exception.occurrence_date}</span>
+                    <button
+                      onClick={() => onSetOccurrenceSkipped(exception.occurrence_date, false)}
+                      className="flex items-center gap-1 px-2 py-1 rounded-lg text-xs hover:bg-[var(--muted)]"
+                    >
+                      <RotateCcw size={12} />
+                      {t("adjustments.restoreOccurrence")}
+                    </button>
+                  </div>
+                ))}
+              </div>
+            </>
+          )}
+        </div>
+      )}
     </div>
   );
 }
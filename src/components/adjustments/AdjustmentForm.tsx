@@ -3,6 +3,17 @@ import { useTranslation } from "react-i18next";
 import { Plus, Trash2 } from "lucide-react";
 import type { Category } from "../../shared/types";
 import type { AdjustmentFormData, EntryFormData } from "../../hooks/useAdjustments";
+import { previewRecurrenceRule } from "../../services/adjustmentService";
+import {
+  buildRecurrenceRule,
+  parseRecurrenceRule,
+  type RecurrenceFrequency,
+  type RecurrenceOptions,
+} from "../../utils/recurrenceRule";
+
+const FREQUENCIES: RecurrenceFrequency[] = ["DAILY", "WEEKLY", "MONTHLY", "YEARLY"];
+const inputClass =
+  "px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--card)] text-sm focus:outline-none focus:ring-1 focus:ring-[var(--primary)]";
 
 interface Props {
   initialData: AdjustmentFormData;
@@ -28,16 +39,52 @@ export default function AdjustmentForm({
   const { t } = useTranslation();
   const [form, setForm] = useState<AdjustmentFormData>(initialData);
   const [entries, setEntries] = useState<EntryFormData[]>(initialEntries);
+  const [preview, setPreview] = useState<string[]>([]);
+  const [previewError, setPreviewError] = useState<string | null>(null);
 
   useEffect(() => {
     setForm(initialData);
     setEntries(initialEntries);
   }, [initialData, initialEntries]);
 
+  const recurrence = parseRecurrenceRule(form.recurrence_rule);
+  const rule = buildRecurrenceRule(recurrence);
+
+  useEffect(() => {
+    if (!form.is_recurring || !form.date) {
+      setPreview([]);
+      setPreviewError(null);
+      return;
+    }
+    let cancelled = false;
+    previewRecurrenceRule(rule, form.date)
+      .then((dates) => {
+        if (cancelled) return;
+        setPreview(dates);
+        setPreviewError(null);
+      })
+      .catch((e) => {
+        if (cancelled) return;
+        setPreview([]);
+        setPreviewError(String(e));
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [form.is_recurring, form.date, rule]);
+
+  const updateRecurrence = (changes: Partial<RecurrenceOptions>) => {
+    setForm({ ...form, recurrence_rule: buildRecurrenceRule({ ...recurrence, ...changes }) });
+  };
+
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
     if (!form.name.trim()) return;
-    onSave({ ...form, name: form.name.trim() }, entries);
+    if (form.is_recurring && previewError) return;
+    onSave(
+      { ...form, name: form.name.trim(), recurrence_rule: form.is_recurring ? rule : "" },
+      entries
+    );
   };
 
   const addEntry = () => {
@@ -96,6 +143,96 @@ export default function AdjustmentForm({
         {t("adjustments.recurring")}
       </label>
 
+      {form.is_recurring && (
+        <div className="flex flex-col gap-3 pl-6">
+          <div className="flex items-center gap-2 text-sm">
+            <span>{t("adjustments.every")}</span>
+            <input
+              type="number"
+              min={1}
+              value={recurrence.interval}
+              onChange={(e) => updateRecurrence({ interval: Math.max(1, Number(e.target.value) || 1) })}
+              className={`w-16 ${inputClass}`}
+            />
+            <select
+              value={recurrence.frequency}
+              onChange={(e) => updateRecurrence({ frequency: e.target.value as RecurrenceFrequency })}
+              className={inputClass}
+            >
+              {FREQUENCIES.map((f) => (
+                <option key={f} value={f}>
+                  {t(`adjustments.frequencies.${f}`)}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          {(recurrence.frequency === "MONTHLY" || recurrence.frequency === "YEARLY") && (
+            <div className="flex items-center gap-2 text-sm">
+              <span>{t("adjustments.onDay")}</span>
+              <select
+                value={recurrence.monthDay ?? ""}
+                onChange={(e) =>
+                  updateRecurrence({ monthDay: e.target.value === "" ? null : Number(e.target.value) })
+                }
+                className={inputClass}
+              >
+                <option value="">{t("adjustments.sameDayAsStart")}</option>
+                {Array.from({ length: 31 }, (_, i) => i + 1).map((day) => (
+                  <option key={day} value={day}>
+                    {day}
+                  </option>
+                ))}
+                <option value={-1}>{t("adjustments.lastDayOfMonth")}</option>
+              </select>
+            </div>
+          )}
+
+          <div className="flex items-center gap-2 text-sm">
+            <span>{t("adjustments.ends")}</span>
+            <select
+              value={recurrence.end}
+              onChange={(e) => updateRecurrence({ end: e.target.value as RecurrenceOptions["end"] })}
+              className={inputClass}
+            >
+              <option value="never">{t("adjustments.endNever")}</option>
+              <option value="until">{t("adjustments.endUntil")}</option>
+              <option value="count">{t("adjustments.endCount")}</option>
+            </select>
+            {recurrence.end === "until" && (
+              <input
+                type="date"
+                value={recurrence.until}
+                onChange={(e) => updateRecurrence({ until: e.target.value })}
+                className={inputClass}
+              />
+            )}
+            {recurrence.end === "count" && (
+              <>
+                <input
+                  type="number"
+                  min={1}
+                  value={recurrence.count}
+                  onChange={(e) => updateRecurrence({ count: Math.max(1, Number(e.target.value) || 1) })}
+                  className={`w-20 ${inputClass}`}
+                />
+                <span>{t("adjustments.occurrences")}</span>
+              </>
+            )}
+          </div>
+
+          {previewError ? (
+            <p className="text-xs text-[var(--negative)]">{previewError}</p>
+          ) : (
+            preview.length > 0 && (
+              <p className="text-xs text-[var(--muted-foreground)]">
+                {t("adjustments.nextOccurrences")}: {preview.join(", ")}
+              </p>
+            )
+          )}
+        </div>
+      )}
+
       {/* Entries */}
       <div className="border-t border-[var(--border)] pt-4">
         <div className="flex items-center justify-between mb-3">
@@ -158,7 +295,7 @@ export default function AdjustmentForm({
       <div className="flex items-center gap-2 pt-2">
         <button
           type="submit"
-          disabled={isSaving || !form.name.trim()}
+          disabled={isSaving || !form.name.trim() || (form.is_recurring && !!previewError)}
           className="px-4 py-2 rounded-lg bg-[var(--primary)] text-white text-sm font-medium hover:opacity-90 disabled:opacity-50"
         >
           {t("common.save")}
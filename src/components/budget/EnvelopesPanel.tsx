@@ -0,0 +1,253 @@
+import { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Wallet, ArrowRightLeft, Undo2, Loader2 } from "lucide-react";
+import type { BudgetTransfer, BudgetYearRow, Envelope } from "../../shared/types";
+import {
+  getBudgetEnvelopes,
+  getBudgetTransfers,
+  moveBudgetMoney,
+  reverseBudgetTransfer,
+  setBudgetRollover,
+} from "../../services/envelopeService";
+
+const fmt = new Intl.NumberFormat("en-CA", { style: "currency", currency: "CAD" });
+
+const MONTH_KEYS = [
+  "months.jan", "months.feb", "months.mar", "months.apr",
+  "months.may", "months.jun", "months.jul", "months.aug",
+  "months.sep", "months.oct", "months.nov", "months.dec",
+] as const;
+
+const inputClass =
+  "px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-sm";
+
+interface EnvelopesPanelProps {
+  year: number;
+  /** Budget grid rows, to recompute the envelopes when a budget amount changes. */
+  budgetRows: BudgetYearRow[];
+}
+
+export default function EnvelopesPanel({ year, budgetRows }: EnvelopesPanelProps) {
+  const { t } = useTranslation();
+  const [month, setMonth] = useState(() => {
+    const now = new Date();
+    return now.getFullYear() === year ? now.getMonth() + 1 : 1;
+  });
+  const [envelopes, setEnvelopes] = useState<Envelope[]>([]);
+  const [transfers, setTransfers] = useState<BudgetTransfer[]>([]);
+  const [fromId, setFromId] = useState<number | "">("");
+  const [toId, setToId] = useState<number | "">("");
+  const [amount, setAmount] = useState("");
+  const [notes, setNotes] = useState("");
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    const [env, history] = await Promise.all([getBudgetEnvelopes(year), getBudgetTransfers(year)]);
+    setEnvelopes(env);
+    setTransfers(history);
+  }, [year]);
+
+  useEffect(() => {
+    load().catch((e) => setError(String(e)));
+  }, [load, budgetRows]);
+
+  const run = async (action: () => Promise<void>) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      await action();
+      await load();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleMove = () =>
+    run(async () => {
+      const value = Number(amount);
+      if (fromId === "" || toId === "" || !Number.isFinite(value) || value <= 0) {
+        throw new Error(t("budget.envelopes.invalidMove"));
+      }
+      await moveBudgetMoney(year, month, fromId, toId, value, notes || undefined);
+      setAmount("");
+      setNotes("");
+    });
+
+  const colorFor = (value: number) =>
+    value < 0 ? "text-[var(--negative)]" : value > 0 ? "text-[var(--positive)]" : "";
+  const monthTransfers = transfers.filter((tr) => tr.month === month);
+
+  return (
+    <div className="mt-6 bg-[var(--card)] border border-[var(--border)] rounded-xl p-6 space-y-4">
+      <div className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-3">
+        <div>
+          <h2 className="text-lg font-semibold flex items-center gap-2">
+            <Wallet size={18} />
+            {t("budget.envelopes.title")}
+          </h2>
+          <p className="text-sm text-[var(--muted-foreground)]">
+            {t("budget.envelopes.description")}
+          </p>
+        </div>
+        <select
+          value={month}
+          onChange={(e) => setMonth(Number(e.target.value))}
+          className={inputClass}
+        >
+          {MONTH_KEYS.map((key, i) => (
+            <option key={key} value={i + 1}>
+              {t(key)} {year}
+            </option>
+          ))}
+        </select>
+      </div>
+
+      {envelopes.length === 0 ? (
+        <p className="text-sm text-[var(--muted-foreground)]">{t("budget.envelopes.empty")}</p>
+      ) : (
+        <div className="overflow-x-auto">
+          <table className="w-full text-sm whitespace-nowrap">
+            <thead>
+              <tr className="border-b border-[var(--border)] text-[var(--muted-foreground)]">
+                <th className="text-left py-2 px-2 font-medium">{t("budget.category")}</th>
+                <th className="text-center py-2 px-2 font-medium">{t("budget.envelopes.rollover")}</th>
+                <th className="text-right py-2 px-2 font-medium">{t("budget.envelopes.carriedIn")}</th>
+                <th className="text-right py-2 px-2 font-medium">{t("budget.planned")}</th>
+                <th className="text-right py-2 px-2 font-medium">{t("budget.envelopes.moved")}</th>
+                <th className="text-right py-2 px-2 font-medium">{t("budget.envelopes.spent")}</th>
+                <th className="text-right py-2 px-2 font-medium">{t("budget.envelopes.available")}</th>
+              </tr>
+            </thead>
+            <tbody>
+              {envelopes.map((env) => {
+                const m = env.months[month - 1];
+                return (
+                  <tr key={env.category_id} className="border-b border-[var(--border)] last:border-b-0">
+                    <td className="py-2 px-2">
+                      <div className="flex items-center gap-2">
+                        <span
+                          className="w-2.5 h-2.5 rounded-full shrink-0"
+                          style={{ backgroundColor: env.category_color }}
+                        />
+                        {env.category_name}
+                      </div>
+                    </td>
+                    <td className="py-2 px-2 text-center">
+                      <input
+                        type="checkbox"
+                        checked={env.rollover}
+                        disabled={isBusy}
+                        onChange={(e) =>
+                          run(() => setBudgetRollover(env.category_id, e.target.checked))
+                        }
+                        title={t("budget.envelopes.rolloverHint")}
+                      />
+                    </td>
+                    <td className={`py-2 px-2 text-right ${colorFor(m.carried_in)}`}>
+                      {fmt.format(m.carried_in)}
+                    </td>
+                    <td className="py-2 px-2 text-right">{fmt.format(m.budgeted)}</td>
+                    <td className="py-2 px-2 text-right">{fmt.format(m.transferred)}</td>
+                    <td className="py-2 px-2 text-right">{fmt.format(m.spent)}</td>
+                    <td className={`py-2 px-2 text-right font-medium ${colorFor(m.available)}`}>
+                      {fmt.format(m.available)}
+                    </td>
+                  </tr>
+                );
+              })}
+            </tbody>
+          </table>
+        </div>
+      )}
+
+      {envelopes.length > 1 && (
+        <div className="flex flex-wrap items-end gap-2">
+          <select
+            value={fromId}
+            onChange={(e) => setFromId(e.target.value === "" ? "" : Number(e.target.value))}
+            className={inputClass}
+          >
+            <option value="">{t("budget.envelopes.from")}</option>
+            {envelopes.map((env) => (
+              <option key={env.category_id} value={env.category_id}>
+                {env.category_name} ({fmt.format(env.months[month - 1].available)})
+              </option>
+            ))}
+          </select>
+          <select
+            value={toId}
+            onChange={(e) => setToId(e.target.value === "" ? "" : Number(e.target.value))}
+            className={inputClass}
+          >
+            <option value="">{t("budget.envelopes.to")}</option>
+            {envelopes
+              .filter((env) => env.category_id !== fromId)
+              .map((env) => (
+                <option key={env.category_id} value={env.category_id}>
+                  {env.category_name}
+                </option>
+              ))}
+          </select>
+          <input
+            type="number"
+            step="0.01"
+            min="0"
+            value={amount}
+            onChange={(e) => setAmount(e.target.value)}
+            placeholder={t("budget.envelopes.amount")}
+            className={`${inputClass} w-32`}
+          />
+          <input
+            value={notes}
+            onChange={(e) => setNotes(e.target.value)}
+            placeholder={t("budget.envelopes.notes")}
+            className={`${inputClass} flex-1 min-w-[10rem]`}
+          />
+          <button
+            onClick={handleMove}
+            disabled={isBusy}
+            className="flex items-center gap-2 px-4 py-2 text-sm rounded-lg bg-[var(--primary)] text-white hover:opacity-90 transition-opacity disabled:opacity-50"
+          >
+            {isBusy ? <Loader2 size={16} className="animate-spin" /> : <ArrowRightLeft size={16} />}
+            {t("budget.envelopes.move")}
+          </button>
+        </div>
+      )}
+
+      {error && <p className="text-sm text-[var(--negative)]">{error}</p>}
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-semibold">{t("budget.envelopes.history")}</h3>
+        {monthTransfers.length === 0 ? (
+          <p className="text-sm text-[var(--muted-foreground)]">{t("budget.envelopes.noTransfers")}</p>
+        ) : (
+          <ul className="space-y-1 text-sm">
+            {monthTransfers.map((tr) => (
+              <li key={tr.id} className="flex items-center justify-between gap-3">
+                <span className={tr.is_reversed ? "line-through text-[var(--muted-foreground)]" : ""}>
+                  {tr.created_at.slice(0, 10)} — {tr.from_category_name} → {tr.to_category_name}:{" "}
+                  {fmt.format(tr.amount)}
+                  {tr.reversed_transfer_id !== null && ` (${t("budget.envelopes.reversal")})`}
+                  {tr.notes && ` — ${tr.notes}`}
+                </span>
+                {!tr.is_reversed && tr.reversed_transfer_id === null && (
+                  <button
+                    onClick={() => run(async () => void (await reverseBudgetTransfer(tr.id)))}
+                    disabled={isBusy}
+                    className="flex items-center gap-1 text-xs text-[var(--muted-foreground)] hover:text-[var(--primary)]"
+                  >
+                    <Undo2 size={14} />
+                    {t("budget.envelopes.undo")}
+                  </button>
+                )}
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+    </div>
+  );
+}
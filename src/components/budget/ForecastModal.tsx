@@ -0,0 +1,269 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Loader2, Wand2, X } from "lucide-react";
+import type { BudgetForecast, ForecastLine, ForecastMethod } from "../../shared/types";
+import { proposeBudget } from "../../services/forecastService";
+
+const fmt = new Intl.NumberFormat("en-CA", { style: "currency", currency: "CAD" });
+
+const MONTH_KEYS = [
+  "months.jan", "months.feb", "months.mar", "months.apr",
+  "months.may", "months.jun", "months.jul", "months.aug",
+  "months.sep", "months.oct", "months.nov", "months.dec",
+] as const;
+
+const METHODS: ForecastMethod[] = ["auto", "average", "median", "same_month_last_year", "recurring"];
+const HISTORY_MONTHS = [6, 12, 24, 36];
+
+const inputClass =
+  "px-3 py-1.5 rounded-lg border border-[var(--border)] bg-[var(--background)] text-sm";
+
+const sum = (values: number[]) => values.reduce((total, v) => total + v, 0);
+
+interface ForecastModalProps {
+  year: number;
+  onApply: (lines: Array<{ category_id: number; months: number[] }>) => Promise<void>;
+  onClose: () => void;
+}
+
+export default function ForecastModal({ year, onApply, onClose }: ForecastModalProps) {
+  const { t } = useTranslation();
+  const [method, setMethod] = useState<ForecastMethod>("auto");
+  const [historyMonths, setHistoryMonths] = useState(12);
+  const [forecast, setForecast] = useState<BudgetForecast | null>(null);
+  const [selected, setSelected] = useState<Set<number>>(new Set());
+  const [isLoading, setIsLoading] = useState(false);
+  const [isApplying, setIsApplying] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setIsLoading(true);
+    setError(null);
+    proposeBudget(year, method, historyMonths)
+      .then((result) => {
+        if (cancelled) return;
+        setForecast(result);
+        // Lines that would change the budget are picked by default
+        setSelected(
+          new Set(
+            result.lines
+              .filter((line) => line.months.some((v, i) => v !== line.current[i]))
+              .map((line) => line.category_id)
+          )
+        );
+      })
+      .catch((e) => !cancelled && setError(String(e)))
+      .finally(() => !cancelled && setIsLoading(false));
+    return () => {
+      cancelled = true;
+    };
+  }, [year, method, historyMonths]);
+
+  const explain = (line: ForecastLine) => {
+    const count = historyMonths;
+    switch (line.method) {
+      case "average":
+        return t("budget.forecast.explain.average", { count, amount: fmt.format(line.average) });
+      case "median":
+        return t("budget.forecast.explain.median", {
+          count,
+          amount: fmt.format(line.median),
+          average: fmt.format(line.average),
+        });
+      case "same_month_last_year":
+        return t(
+          line.seasonal
+            ? "budget.forecast.explain.seasonal"
+            : "budget.forecast.explain.sameMonthLastYear"
+        );
+      case "recurring":
+        return t("budget.forecast.explain.recurring", {
+          bills: line.recurring
+            .map((bill) =>
+              t("budget.forecast.bill", {
+                description: bill.description,
+                amount: fmt.format(bill.amount),
+                count: bill.months_seen,
+              })
+            )
+            .join(", "),
+        });
+    }
+  };
+
+  const toggle = (categoryId: number) =>
+    setSelected((prev) => {
+      const next = new Set(prev);
+      if (next.has(categoryId)) next.delete(categoryId);
+      else next.add(categoryId);
+      return next;
+    });
+
+  const handleApply = async () => {
+    if (!forecast) return;
+    setIsApplying(true);
+    try {
+      await onApply(
+        forecast.lines
+          .filter((line) => selected.has(line.category_id))
+          .map((line) => ({ category_id: line.category_id, months: line.months }))
+      );
+      onClose();
+    } finally {
+      setIsApplying(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
+      <div className="bg-[var(--card)] rounded-xl shadow-xl w-full max-w-4xl border border-[var(--border)]">
+        <div className="flex items-center justify-between px-4 py-3 border-b border-[var(--border)]">
+          <div>
+            <h2 className="text-base font-semibold flex items-center gap-2">
+              <Wand2 size={18} />
+              {t("budget.forecast.title", { year })}
+            </h2>
+            <p className="text-sm text-[var(--muted-foreground)]">
+              {forecast
+                ? t("budget.forecast.history", {
+                    from: forecast.history_from,
+                    to: forecast.history_to,
+                  })
+                : t("budget.forecast.description")}
+            </p>
+          </div>
+          <button onClick={onClose} className="p-1 rounded hover:bg-[var(--muted)] transition-colors">
+            <X size={18} />
+          </button>
+        </div>
+
+        <div className="px-4 py-3 flex flex-wrap items-center gap-3 border-b border-[var(--border)]">
+          <label className="flex items-center gap-2 text-sm">
+            {t("budget.forecast.method")}
+            <select
+              value={method}
+              onChange={(e) => setMethod(e.target.value as ForecastMethod)}
+              className={inputClass}
+            >
+              {METHODS.map((m) => (
+                <option key={m} value={m}>
+                  {t(`budget.forecast.methods.${m}`)}
+                </option>
+              ))}
+            </select>
+          </label>
+          <label className="flex items-center gap-2 text-sm">
+            {t("budget.forecast.historyMonths")}
+            <select
+              value={historyMonths}
+              onChange={(e) => setHistoryMonths(Number(e.target.value))}
+              className={inputClass}
+            >
+              {HISTORY_MONTHS.map((n) => (
+                <option key={n} value={n}>
+                  {t("budget.forecast.months", { count: n })}
+                </option>
+              ))}
+            </select>
+          </label>
+          {isLoading && <Loader2 size={16} className="animate-spin text-[var(--muted-foreground)]" />}
+        </div>
+
+        <div className="px-4 py-3 max-h-[60vh] overflow-y-auto">
+          {error ? (
+            <p className="text-sm text-[var(--negative)]">{error}</p>
+          ) : forecast && forecast.lines.length === 0 ? (
+            <p className="text-sm text-[var(--muted-foreground)]">{t("budget.forecast.empty")}</p>
+          ) : (
+            forecast && (
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="border-b border-[var(--border)] text-[var(--muted-foreground)]">
+                    <th className="py-2 px-2 w-8" />
+                    <th className="text-left py-2 px-2 font-medium">{t("budget.category")}</th>
+                    <th className="text-left py-2 px-2 font-medium">{t("budget.forecast.basis")}</th>
+                    <th className="text-right py-2 px-2 font-medium whitespace-nowrap">
+                      {t("budget.forecast.current")}
+                    </th>
+                    <th className="text-right py-2 px-2 font-medium whitespace-nowrap">
+                      {t("budget.forecast.proposed")}
+                    </th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {forecast.lines.map((line) => (
+                    <tr
+                      key={line.category_id}
+                      className="border-b border-[var(--border)] last:border-b-0 align-top"
+                    >
+                      <td className="py-2 px-2">
+                        <input
+                          type="checkbox"
+                          checked={selected.has(line.category_id)}
+                          onChange={() => toggle(line.category_id)}
+                        />
+                      </td>
+                      <td className="py-2 px-2">
+                        <div className="flex items-center gap-2 whitespace-nowrap">
+                          <span
+                            className="w-2.5 h-2.5 rounded-full shrink-0"
+                            style={{ backgroundColor: line.category_color }}
+                          />
+                          {line.category_name}
+                        </div>
+                      </td>
+                      <td className="py-2 px-2">
+                        <span className="font-medium">
+                          {t(`budget.forecast.methods.${line.method}`)}
+                        </span>
+                        <p className="text-xs text-[var(--muted-foreground)]">
+                          {explain(line)}{" "}
+                          {t("budget.forecast.activity", {
+                            active: line.active_months,
+                            count: historyMonths,
+                          })}
+                        </p>
+                      </td>
+                      <td className="py-2 px-2 text-right whitespace-nowrap text-[var(--muted-foreground)]">
+                        {fmt.format(sum(line.current))}
+                      </td>
+                      <td
+                        className="py-2 px-2 text-right whitespace-nowrap font-medium"
+                        title={line.months
+                          .map((v, i) => `${t(MONTH_KEYS[i])}: ${fmt.format(v)}`)
+                          .join("\n")}
+                      >
+                        {fmt.format(sum(line.months))}
+                      </td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            )
+          )}
+        </div>
+
+        <div className="flex items-center justify-between gap-3 px-4 py-3 border-t border-[var(--border)]">
+          <p className="text-xs text-[var(--muted-foreground)]">{t("budget.forecast.overwrite")}</p>
+          <div className="flex gap-2">
+            <button
+              onClick={onClose}
+              className="px-3 py-1.5 text-sm rounded-lg border border-[var(--border)] hover:bg-[var(--muted)] transition-colors"
+            >
+              {t("common.cancel")}
+            </button>
+            <button
+              onClick={handleApply}
+              disabled={isLoading || isApplying || selected.size === 0}
+              className="flex items-center gap-2 px-3 py-1.5 text-sm rounded-lg bg-[var(--primary)] text-white hover:opacity-90 transition-opacity disabled:opacity-50"
+            >
+              {isApplying && <Loader2 size={14} className="animate-spin" />}
+              {t("budget.forecast.apply", { count: selected.size })}
+            </button>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,131 @@
+import { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { CheckCircle2, AlertTriangle, Settings2, ListChecks } from "lucide-react";
+import type { AccountBalance } from "../../shared/types";
+import { getAccountBalances } from "../../services/accountService";
+import AccountSettingsModal from "./AccountSettingsModal";
+import ReconciliationModal from "./ReconciliationModal";
+
+const formatAmount = (value: number, currency: string) =>
+  new Intl.NumberFormat("en-CA", { style: "currency", currency }).format(value);
+
+export default function AccountBalancesCard() {
+  const { t } = useTranslation();
+  const [accounts, setAccounts] = useState<AccountBalance[]>([]);
+  const [showClosed, setShowClosed] = useState(false);
+  const [editing, setEditing] = useState<AccountBalance | null>(null);
+  const [reconciling, setReconciling] = useState<AccountBalance | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      setAccounts(await getAccountBalances());
+      setError(null);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    }
+  }, []);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  if (accounts.length === 0 && !error) return null;
+
+  const closedCount = accounts.filter((a) => a.is_closed).length;
+  const visible = showClosed ? accounts : accounts.filter((a) => !a.is_closed);
+
+  return (
+    <div className="bg-[var(--card)] rounded-xl p-5 border border-[var(--border)] shadow-sm mb-8">
+      <div className="flex items-center justify-between mb-4">
+        <h2 className="text-lg font-semibold">{t("dashboard.accounts.title")}</h2>
+        {closedCount > 0 && (
+          <button
+            onClick={() => setShowClosed(!showClosed)}
+            className="text-xs text-[var(--muted-foreground)] hover:underline"
+          >
+            {showClosed
+              ? t("dashboard.accounts.hideClosed")
+              : t("dashboard.accounts.showClosed", { count: closedCount })}
+          </button>
+        )}
+      </div>
+
+      {error && <p className="text-sm text-[var(--negative)] mb-3">{error}</p>}
+
+      <div className="space-y-3">
+        {visible.map((account) => {
+          const statement = account.last_statement;
+          return (
+            <div key={account.source_id} className="flex items-center gap-3">
+              <div className="flex-1 min-w-0">
+                <p className={`text-sm truncate ${account.is_closed ? "line-through text-[var(--muted-foreground)]" : ""}`}>
+                  {account.name}
+                </p>
+                <p className="text-xs text-[var(--muted-foreground)]">
+                  {t(`dashboard.accounts.types.${account.account_type}`)}
+                  {statement &&
+                    ` · ${t("dashboard.accounts.statementOn", { date: statement.balance_date })}`}
+                </p>
+              </div>
+              {statement &&
+                (statement.matches ? (
+                  <span title={t("dashboard.accounts.statementMatches")}>
+                    <CheckCircle2 size={16} className="text-[var(--positive)]" />
+                  </span>
+                ) : (
+                  <span
+                    title={t("dashboard.accounts.statementDiffers", {
+                      amount: formatAmount(statement.difference, account.currency),
+                    })}
+                  >
+                    <AlertTriangle size={16} className="text-[var(--negative)]" />
+                  </span>
+                ))}
+              <span
+                className={`text-sm font-medium flex-shrink-0 ${
+                  account.balance >= 0 ? "text-[var(--positive)]" : "text-[var(--negative)]"
+                }`}
+              >
+                {formatAmount(account.balance, account.currency)}
+              </span>
+              <button
+                onClick={() => setReconciling(account)}
+                className="p-1 rounded hover:bg-[var(--muted)] text-[var(--muted-foreground)]"
+                title={t("dashboard.accounts.reconcile")}
+              >
+                <ListChecks size={14} />
+              </button>
+              <button
+                onClick={() => setEditing(account)}
+                className="p-1 rounded hover:bg-[var(--muted)] text-[var(--muted-foreground)]"
+                title={t("dashboard.accounts.settings")}
+              >
+                <Settings2 size={14} />
+              </button>
+            </div>
+          );
+        })}
+      </div>
+
+      {editing && (
+        <AccountSettingsModal
+          account={editing}
+          onClose={() => setEditing(null)}
+          onSaved={() => {
+            setEditing(null);
+            load();
+          }}
+        />
+      )}
+
+      {reconciling && (
+        <ReconciliationModal
+          account={reconciling}
+          onClose={() => setReconciling(null)}
+          onChanged={load}
+        />
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,206 @@
+import { useState } from "react";
+import { createPortal } from "react-dom";
+import { useTranslation } from "react-i18next";
+import { X, Loader2 } from "lucide-react";
+import type { AccountBalance, AccountType } from "../../shared/types";
+import { updateSource } from "../../services/importSourceService";
+import { recordStatementBalance } from "../../services/accountService";
+
+const ACCOUNT_TYPES: AccountType[] = [
+  "chequing",
+  "savings",
+  "credit_card",
+  "cash",
+  "investment",
+  "loan",
+  "other",
+];
+
+const inputClass =
+  "w-full px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-sm";
+
+interface AccountSettingsModalProps {
+  account: AccountBalance;
+  onClose: () => void;
+  onSaved: () => void;
+}
+
+export default function AccountSettingsModal({
+  account,
+  onClose,
+  onSaved,
+}: AccountSettingsModalProps) {
+  const { t } = useTranslation();
+  const [accountType, setAccountType] = useState<AccountType>(account.account_type);
+  const [openingBalance, setOpeningBalance] = useState(String(account.opening_balance));
+  const [openingDate, setOpeningDate] = useState(account.opening_balance_date ?? "");
+  const [currency, setCurrency] = useState(account.currency);
+  const [isClosed, setIsClosed] = useState(account.is_closed);
+  const [statementDate, setStatementDate] = useState("");
+  const [statementBalance, setStatementBalance] = useState("");
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSave = async () => {
+    const opening = Number(openingBalance);
+    if (!Number.isFinite(opening)) {
+      setError(t("dashboard.accounts.invalidAmount"));
+      return;
+    }
+    if (!/^[A-Z]{3}$/.test(currency)) {
+      setError(t("dashboard.accounts.invalidCurrency"));
+      return;
+    }
+    setIsSaving(true);
+    setError(null);
+    try {
+      await updateSource(account.source_id, {
+        account_type: accountType,
+        opening_balance: opening,
+        opening_balance_date: openingDate || null,
+        currency,
+        is_closed: isClosed,
+      });
+      if (statementDate && statementBalance !== "") {
+        await recordStatementBalance(
+          account.source_id,
+          statementDate,
+          Number(statementBalance),
+          "manual",
+          { currency }
+        );
+      }
+      onSaved();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+      setIsSaving(false);
+    }
+  };
+
+  return createPortal(
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
+      <div className="bg-[var(--card)] border border-[var(--border)] rounded-xl w-full max-w-md mx-4 shadow-xl">
+        <div className="flex items-center justify-between p-4 border-b border-[var(--border)]">
+          <h2 className="text-lg font-semibold">{account.name}</h2>
+          <button
+            onClick={onClose}
+            disabled={isSaving}
+            className="p-1 rounded hover:bg-[var(--border)] transition-colors"
+          >
+            <X size={18} />
+          </button>
+        </div>
+
+        <div className="p-4 space-y-4">
+          <div>
+            <label className="text-sm text-[var(--muted-foreground)] block mb-1">
+              {t("dashboard.accounts.type")}
+            </label>
+            <select
+              value={accountType}
+              onChange={(e) => setAccountType(e.target.value as AccountType)}
+              className={inputClass}
+            >
+              {ACCOUNT_TYPES.map((type) => (
+                <option key={type} value={type}>
+                  {t(`dashboard.accounts.types.${type}`)}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="grid grid-cols-2 gap-3">
+            <div>
+              <label className="text-sm text-[var(--muted-foreground)] block mb-1">
+                {t("dashboard.accounts.openingBalance")}
+              </label>
+              <input
+                type="number"
+                step="0.01"
+                value={openingBalance}
+                onChange={(e) => setOpeningBalance(e.target.value)}
+                className={inputClass}
+              />
+            </div>
+            <div>
+              <label className="text-sm text-[var(--muted-foreground)] block mb-1">
+                {t("dashboard.accounts.openingDate")}
+              </label>
+              <input
+                type="date"
+                value={openingDate}
+                onChange={(e) => setOpeningDate(e.target.value)}
+                className={inputClass}
+              />
+            </div>
+          </div>
+
+          <div className="grid grid-cols-2 gap-3 items-end">
+            <div>
+              <label className="text-sm text-[var(--muted-foreground)] block mb-1">
+                {t("dashboard.accounts.currency")}
+              </label>
+              <input
+                type="text"
+                maxLength={3}
+                value={currency}
+                onChange={(e) => setCurrency(e.target.value.toUpperCase())}
+                className={inputClass}
+              />
+            </div>
+            <label className="flex items-center gap-2 text-sm cursor-pointer pb-2">
+              <input
+                type="checkbox"
+                checked={isClosed}
+                onChange={(e) => setIsClosed(e.target.checked)}
+                className="rounded border-[var(--border)]"
+              />
+              {t("dashboard.accounts.closed")}
+            </label>
+          </div>
+
+          <div className="border-t border-[var(--border)] pt-4">
+            <p className="text-sm font-medium mb-2">{t("dashboard.accounts.statementBalance")}</p>
+            <div className="grid grid-cols-2 gap-3">
+              <input
+                type="date"
+                value={statementDate}
+                onChange={(e) => setStatementDate(e.target.value)}
+                className={inputClass}
+              />
+              <input
+                type="number"
+                step="0.01"
+                value={statementBalance}
+                onChange={(e) => setStatementBalance(e.target.value)}
+                placeholder={t("dashboard.accounts.statementBalancePlaceholder")}
+                className={inputClass}
+              />
+            </div>
+          </div>
+
+          {error && <p className="text-sm text-[var(--negative)]">{error}</p>}
+        </div>
+
+        <div className="flex justify-end gap-2 p-4 border-t border-[var(--border)]">
+          <button
+            onClick={onClose}
+            disabled={isSaving}
+            className="px-4 py-2 text-sm rounded-lg border border-[var(--border)] hover:bg-[var(--border)] transition-colors"
+          >
+            {t("common.cancel")}
+          </button>
+          <button
+            onClick={handleSave}
+            disabled={isSaving}
+            className="flex items-center gap-2 px-4 py-2 text-sm rounded-lg bg-[var(--primary)] text-white hover:opacity-90 transition-opacity disabled:opacity-50"
+          >
+            {isSaving && <Loader2 size={14} className="animate-spin" />}
+            {t("common.save")}
+          </button>
+        </div>
+      </div>
+    </div>,
+    document.body
+  );
+}
@@ -0,0 +1,296 @@
+import { useCallback, useEffect, useState } from "react";
+import { createPortal } from "react-dom";
+import { useTranslation } from "react-i18next";
+import { X, Loader2, CheckCircle2, Trash2, Undo2 } from "lucide-react";
+import type {
+  AccountBalance,
+  Reconciliation,
+  ReconciliationPreview,
+} from "../../shared/types";
+import {
+  completeReconciliation,
+  getReconciliations,
+  previewReconciliation,
+  undoReconciliation,
+} from "../../services/reconciliationService";
+import { deleteTransaction } from "../../services/transactionService";
+
+const inputClass =
+  "w-full px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-sm";
+
+interface ReconciliationModalProps {
+  account: AccountBalance;
+  onClose: () => void;
+  onChanged: () => void;
+}
+
+export default function ReconciliationModal({
+  account,
+  onClose,
+  onChanged,
+}: ReconciliationModalProps) {
+  const { t } = useTranslation();
+  const fmt = new Intl.NumberFormat("en-CA", { style: "currency", currency: account.currency });
+  const [statementDate, setStatementDate] = useState("");
+  const [statementBalance, setStatementBalance] = useState("");
+  const [notes, setNotes] = useState("");
+  const [preview, setPreview] = useState<ReconciliationPreview | null>(null);
+  const [history, setHistory] = useState<Reconciliation[]>([]);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadHistory = useCallback(async () => {
+    setHistory(await getReconciliations(account.source_id));
+  }, [account.source_id]);
+
+  useEffect(() => {
+    loadHistory().catch((e) => setError(String(e)));
+  }, [loadHistory]);
+
+  const run = async (action: () => Promise<void>) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleCheck = () =>
+    run(async () => {
+      const balance = Number(statementBalance);
+      if (!statementDate || statementBalance === "" || !Number.isFinite(balance)) {
+        throw new Error(t("dashboard.reconciliation.invalidStatement"));
+      }
+      setPreview(await previewReconciliation(account.source_id, statementDate, balance));
+    });
+
+  const handleDelete = (transactionId: number) =>
+    run(async () => {
+      await deleteTransaction(transactionId);
+      onChanged();
+      setPreview(
+        await previewReconciliation(account.source_id, statementDate, Number(statementBalance))
+      );
+    });
+
+  const handleComplete = () =>
+    run(async () => {
+      await completeReconciliation(
+        account.source_id,
+        statementDate,
+        Number(statementBalance),
+        notes || undefined
+      );
+      setPreview(null);
+      setStatementDate("");
+      setStatementBalance("");
+      setNotes("");
+      await loadHistory();
+      onChanged();
+    });
+
+  const handleUndo = (id: number) =>
+    run(async () => {
+      await undoReconciliation(id);
+      await loadHistory();
+      onChanged();
+    });
+
+  return createPortal(
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
+      <div className="bg-[var(--card)] border border-[var(--border)] rounded-xl w-full max-w-lg mx-4 shadow-xl max-h-[90vh] flex flex-col">
+        <div className="flex items-center justify-between p-4 border-b border-[var(--border)]">
+          <h2 className="text-lg font-semibold">
+            {t("dashboard.reconciliation.title", { account: account.name })}
+          </h2>
+          <button
+            onClick={onClose}
+            disabled={isBusy}
+            className="p-1 rounded hover:bg-[var(--border)] transition-colors"
+          >
+            <X size={18} />
+          </button>
+        </div>
+
+        <div className="p-4 space-y-4 overflow-y-auto">
+          <div className="grid grid-cols-[1fr_1fr_auto] gap-3 items-end">
+            <div>
+              <label className="text-sm text-[var(--muted-foreground)] block mb-1">
+                {t("dashboard.reconciliation.statementDate")}
+              </label>
+              <input
+                type="date"
+                value={statementDate}
+                onChange={(e) => {
+                  setStatementDate(e.target.value);
+                  setPreview(null);
+                }}
+                className={inputClass}
+              />
+            </div>
+            <div>
+              <label className="text-sm text-[var(--muted-foreground)] block mb-1">
+                {t("dashboard.reconciliation.closingBalance")}
+              </label>
+              <input
+                type="number"
+                step="0.01"
+                value={statementBalance}
+                onChange={(e) => {
+                  setStatementBalance(e.target.value);
+                  setPreview(null);
+                }}
+                className={inputClass}
+              />
+            </div>
+            <button
+              onClick={handleCheck}
+              disabled={isBusy}
+              className="px-4 py-2 text-sm rounded-lg border border-[var(--border)] hover:bg-[var(--border)] transition-colors"
+            >
+              {t("dashboard.reconciliation.check")}
+            </button>
+          </div>
+
+          {preview && (
+            <div className="space-y-3">
+              <div className="grid grid-cols-2 gap-2 text-sm">
+                <span className="text-[var(--muted-foreground)]">
+                  {t("dashboard.reconciliation.computedBalance")}
+                </span>
+                <span className="text-right font-mono">{fmt.format(preview.computed_balance)}</span>
+                <span className="text-[var(--muted-foreground)]">
+                  {t("dashboard.reconciliation.difference")}
+                </span>
+                <span
+                  className={`text-right font-mono ${
+                    preview.matches ? "text-[var(--positive)]" : "text-[var(--negative)]"
+                  }`}
+                >
+                  {fmt.format(preview.difference)}
+                </span>
+              </div>
+
+              {preview.matches ? (
+                <div className="space-y-3">
+                  <p className="flex items-center gap-2 text-sm text-[var(--positive)]">
+                    <CheckCircle2 size={16} />
+                    {t("dashboard.reconciliation.balanced", { count: preview.unreconciled_count })}
+                  </p>
+                  <input
+                    type="text"
+                    value={notes}
+                    onChange={(e) => setNotes(e.target.value)}
+                    placeholder={t("dashboard.reconciliation.notesPlaceholder")}
+                    className={inputClass}
+                  />
+                </div>
+              ) : (
+                <p className="text-sm text-[var(--muted-foreground)]">
+                  {t("dashboard.reconciliation.unbalanced")}
+                </p>
+              )}
+
+              {preview.proposals.length > 0 && (
+                <ul className="space-y-2">
+                  {preview.proposals.map((proposal, i) => (
+                    <li
+                      key={proposal.transaction_id ?? `missing-${i}`}
+                      className="flex items-center gap-3 p-2 rounded-lg border border-[var(--border)] text-sm"
+                    >
+                      <div className="flex-1 min-w-0">
+                        <p className="font-medium">
+                          {t(`dashboard.reconciliation.proposals.${proposal.kind}`)}
+                          {proposal.explains_difference && (
+                            <span className="ml-2 text-xs text-[var(--positive)]">
+                              {t("dashboard.reconciliation.explainsDifference")}
+                            </span>
+                          )}
+                        </p>
+                        <p className="text-xs text-[var(--muted-foreground)] truncate">
+                          {proposal.kind === "missing"
+                            ? t("dashboard.reconciliation.missingHint")
+                            : `${proposal.date} · ${proposal.description}`}
+                        </p>
+                      </div>
+                      <span className="font-mono whitespace-nowrap">
+                        {fmt.format(proposal.amount)}
+                      </span>
+                      {proposal.kind === "duplicate" && proposal.transaction_id !== null && (
+                        <button
+                          onClick={() => handleDelete(proposal.transaction_id!)}
+                          disabled={isBusy}
+                          className="p-1 rounded hover:bg-[var(--muted)] text-[var(--negative)]"
+                          title={t("dashboard.reconciliation.deleteDuplicate")}
+                        >
+                          <Trash2 size={14} />
+                        </button>
+                      )}
+                    </li>
+                  ))}
+                </ul>
+              )}
+            </div>
+          )}
+
+          {error && <p className="text-sm text-[var(--negative)]">{error}</p>}
+
+          <div className="border-t border-[var(--border)] pt-4">
+            <p className="text-sm font-medium mb-2">{t("dashboard.reconciliation.history")}</p>
+            {history.length === 0 ? (
+              <p className="text-sm text-[var(--muted-foreground)]">
+                {t("dashboard.reconciliation.noHistory")}
+              </p>
+            ) : (
+              <ul className="space-y-2">
+                {history.map((rec, i) => (
+                  <li key={rec.id} className="flex items-center gap-3 text-sm">
+                    <span className="w-24">{rec.statement_date}</span>
+                    <span className="flex-1 text-xs text-[var(--muted-foreground)] truncate">
+                      {t("dashboard.reconciliation.lockedCount", { count: rec.transaction_count })}
+                      {rec.notes && ` · ${rec.notes}`}
+                    </span>
+                    <span className="font-mono">{fmt.format(rec.statement_balance)}</span>
+                    {i === 0 && (
+                      <button
+                        onClick={() => handleUndo(rec.id)}
+                        disabled={isBusy}
+                        className="p-1 rounded hover:bg-[var(--muted)] text-[var(--muted-foreground)]"
+                        title={t("dashboard.reconciliation.undo")}
+                      >
+                        <Undo2 size={14} />
+                      </button>
+                    )}
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+        </div>
+
+        <div className="flex justify-end gap-2 p-4 border-t border-[var(--border)]">
+          <button
+            onClick={onClose}
+            disabled={isBusy}
+            className="px-4 py-2 text-sm rounded-lg border border-[var(--border)] hover:bg-[var(--border)] transition-colors"
+          >
+            {t("common.cancel")}
+          </button>
+          <button
+            onClick={handleComplete}
+            disabled={isBusy || !preview?.matches}
+            className="flex items-center gap-2 px-4 py-2 text-sm rounded-lg bg-[var(--primary)] text-white hover:opacity-90 transition-opacity disabled:opacity-50"
+          >
+            {isBusy && <Loader2 size={14} className="animate-spin" />}
+            {t("dashboard.reconciliation.complete")}
+          </button>
+        </div>
+      </div>
+    </div>,
+    document.body
+  );
+}
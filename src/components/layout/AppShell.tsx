@@ -1,11 +1,13 @@
 import { Outlet } from "react-router-dom";
 import Sidebar from "./Sidebar";
+import SchemaCheckBanner from "./SchemaCheckBanner";
 
 export default function AppShell() {
   return (
     <div className="flex h-screen overflow-hidden">
       <Sidebar />
       <main className="flex-1 overflow-y-auto p-6">
+        <SchemaCheckBanner />
         <Outlet />
       </main>
     </div>
@@ -0,0 +1,47 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Link } from "react-router-dom";
+import { AlertTriangle, X } from "lucide-react";
+import { getStartupSchemaReport, type SchemaCheckReport } from "../../services/profileService";
+
+const POLL_MS = 2000;
+
+/** Warns when the startup schema check found problems, until dismissed. */
+export default function SchemaCheckBanner() {
+  const { t } = useTranslation();
+  const [report, setReport] = useState<SchemaCheckReport | null>(null);
+  const [dismissed, setDismissed] = useState(false);
+
+  // The check runs in the background at startup: poll until its report is in
+  useEffect(() => {
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const poll = () => {
+      getStartupSchemaReport()
+        .then((result) => {
+          if (result) setReport(result);
+          else timer = setTimeout(poll, POLL_MS);
+        })
+        .catch((e) => console.error("Startup schema check failed:", e));
+    };
+    poll();
+    return () => clearTimeout(timer);
+  }, []);
+
+  if (!report || report.ok || dismissed) return null;
+
+  return (
+    <div className="flex items-center gap-3 mb-4 px-4 py-3 rounded-xl border border-[var(--negative)] bg-[var(--card)] text-sm">
+      <AlertTriangle size={16} className="shrink-0 text-[var(--negative)]" />
+      <p className="flex-1">{t("settings.schema.banner")}</p>
+      <Link to="/settings" className="text-[var(--primary)] hover:underline whitespace-nowrap">
+        {t("settings.schema.details")}
+      </Link>
+      <button
+        onClick={() => setDismissed(true)}
+        className="text-[var(--muted-foreground)] hover:text-[var(--foreground)]"
+      >
+        <X size={16} />
+      </button>
+    </div>
+  );
+}
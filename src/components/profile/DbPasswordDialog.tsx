@@ -0,0 +1,82 @@
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { KeyRound, Loader2, X } from "lucide-react";
+
+interface Props {
+  profileName: string;
+  /** Rejects when the password is wrong; the dialog then shows the error and stays open. */
+  onSubmit: (password: string) => Promise<void>;
+  onCancel?: () => void;
+}
+
+export default function DbPasswordDialog({ profileName, onSubmit, onCancel }: Props) {
+  const { t } = useTranslation();
+  const [password, setPassword] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [checking, setChecking] = useState(false);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!password) return;
+    setChecking(true);
+    setError(null);
+    try {
+      await onSubmit(password);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      setPassword("");
+    } finally {
+      setChecking(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
+      <form
+        onSubmit={handleSubmit}
+        className="bg-[var(--card)] rounded-xl shadow-xl w-full max-w-xs border border-[var(--border)] p-6"
+      >
+        <div className="flex items-center justify-between mb-4">
+          <h3 className="font-semibold text-[var(--foreground)] flex items-center gap-2">
+            <KeyRound size={16} />
+            {profileName}
+          </h3>
+          {onCancel && (
+            <button
+              type="button"
+              onClick={onCancel}
+              className="text-[var(--muted-foreground)] hover:text-[var(--foreground)]"
+            >
+              <X size={18} />
+            </button>
+          )}
+        </div>
+
+        <p className="text-sm text-[var(--muted-foreground)] mb-4">{t("profile.encryption.enterPassword")}</p>
+
+        <input
+          type="password"
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          onKeyDown={(e) => e.key === "Escape" && onCancel?.()}
+          disabled={checking}
+          autoFocus
+          className={`w-full px-3 py-2 rounded-lg border-2 bg-[var(--background)] text-[var(--foreground)] text-sm outline-none transition-colors ${
+            error ? "border-[var(--negative)]" : "border-[var(--border)] focus:border-[var(--primary)]"
+          }`}
+        />
+
+        {error && <p className="text-sm text-[var(--negative)] mt-2">{error}</p>}
+
+        <button
+          type="submit"
+          disabled={!password || checking}
+          className="flex items-center justify-center gap-2 w-full mt-4 px-4 py-2 rounded-lg bg-[var(--primary)] text-white text-sm font-medium hover:opacity-90 disabled:opacity-50"
+        >
+          {checking && <Loader2 size={14} className="animate-spin" />}
+          {t("profile.encryption.unlock")}
+        </button>
+      </form>
+    </div>
+  );
+}
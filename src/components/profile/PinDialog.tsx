@@ -1,25 +1,51 @@
 import { useState, useRef, useEffect } from "react";
 import { useTranslation } from "react-i18next";
 import { X } from "lucide-react";
-import { verifyPin } from "../../services/profileService";
+import { verifyProfilePin } from "../../services/profileService";
 
 interface Props {
+  profileId: string;
   profileName: string;
-  storedHash: string;
   onSuccess: () => void;
   onCancel: () => void;
 }
 
-export default function PinDialog({ profileName, storedHash, onSuccess, onCancel }: Props) {
+export default function PinDialog({ profileId, profileName, onSuccess, onCancel }: Props) {
   const { t } = useTranslation();
   const [digits, setDigits] = useState<string[]>(["", "", "", "", "", ""]);
   const [error, setError] = useState(false);
   const [checking, setChecking] = useState(false);
+  const [lockedSecs, setLockedSecs] = useState(0);
   const inputRefs = useRef<(HTMLInputElement | null)[]>([]);
 
+  // Focus the first digit whenever the inputs are (re-)enabled
   useEffect(() => {
-    inputRefs.current[0]?.focus();
-  }, []);
+    if (!checking && lockedSecs === 0) inputRefs.current[0]?.focus();
+  }, [checking, lockedSecs]);
+
+  // Count down the lockout
+  useEffect(() => {
+    if (lockedSecs <= 0) return;
+    const timer = setTimeout(() => setLockedSecs((s) => s - 1), 1000);
+    return () => clearTimeout(timer);
+  }, [lockedSecs]);
+
+  // Every submission counts as an attempt, so only submit complete PINs
+  const submit = async (pin: string) => {
+    setChecking(true);
+    try {
+      const result = await verifyProfilePin(profileId, pin);
+      if (result.valid) {
+        onSuccess();
+        return;
+      }
+      setError(true);
+      setLockedSecs(result.retry_after_secs ?? 0);
+      setDigits(["", "", "", "", "", ""]);
+    } finally {
+      setChecking(false);
+    }
+  };
 
   const handleInput = async (index: number, value: string) => {
     if (!/^\d?$/.test(value)) return;
@@ -33,26 +59,10 @@ export default function PinDialog({ profileName, storedHash, onSuccess, onCancel
       inputRefs.current[index + 1]?.focus();
     }
 
-    // Check PIN when we have at least 4 digits filled
+    // Submit on the 6th digit; shorter PINs are submitted with Enter
     const pin = newDigits.join("");
-    if (pin.length >= 4 && !newDigits.slice(0, 4).includes("")) {
-      // If all filled digits are present and we're at the last one typed
-      const filledCount = newDigits.filter((d) => d !== "").length;
-      if (value && filledCount === index + 1) {
-        setChecking(true);
-        try {
-          const valid = await verifyPin(pin.replace(/\s/g, ""), storedHash);
-          if (valid) {
-            onSuccess();
-          } else if (filledCount >= 6 || (filledCount >= 4 && index === filledCount - 1 && !value)) {
-            setError(true);
-            setDigits(["", "", "", "", "", ""]);
-            inputRefs.current[0]?.focus();
-          }
-        } finally {
-          setChecking(false);
-        }
-      }
+    if (value && pin.length === 6) {
+      await submit(pin);
     }
   };
 
@@ -66,17 +76,7 @@ export default function PinDialog({ profileName, storedHash, onSuccess, onCancel
     if (e.key === "Enter") {
       const pin = digits.join("");
       if (pin.length >= 4) {
-        setChecking(true);
-        verifyPin(pin, storedHash).then((valid) => {
-          setChecking(false);
-          if (valid) {
-            onSuccess();
-          } else {
-            setError(true);
-            setDigits(["", "", "", "", "", ""]);
-            inputRefs.current[0]?.focus();
-          }
-        });
+        submit(pin);
       }
     }
   };
@@ -104,7 +104,7 @@ export default function PinDialog({ profileName, storedHash, onSuccess, onCancel
               value={digit}
               onChange={(e) => handleInput(i, e.target.value)}
               onKeyDown={(e) => handleKeyDown(i, e)}
-              disabled={checking}
+              disabled={checking || lockedSecs > 0}
               className={`w-10 h-12 text-center text-lg font-bold rounded-lg border-2 bg-[var(--background)] text-[var(--foreground)] ${
                 error ? "border-[var(--negative)]" : "border-[var(--border)] focus:border-[var(--primary)]"
               } outline-none transition-colors`}
@@ -112,8 +112,14 @@ export default function PinDialog({ profileName, storedHash, onSuccess, onCancel
           ))}
         </div>
 
-        {error && (
-          <p className="text-sm text-[var(--negative)] text-center">{t("profile.wrongPin")}</p>
+        <p className="text-xs text-[var(--muted-foreground)] text-center mb-2">{t("profile.pinSubmitHint")}</p>
+
+        {lockedSecs > 0 ? (
+          <p className="text-sm text-[var(--negative)] text-center">
+            {t("profile.pinLocked", { seconds: lockedSecs })}
+          </p>
+        ) : (
+          error && <p className="text-sm text-[var(--negative)] text-center">{t("profile.wrongPin")}</p>
         )}
       </div>
     </div>
@@ -1,7 +1,21 @@
 import { useState } from "react";
 import { useTranslation } from "react-i18next";
-import { X, Trash2, Lock, LockOpen, Plus } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import {
+  X,
+  Trash2,
+  Lock,
+  LockOpen,
+  Plus,
+  KeyRound,
+  ShieldCheck,
+  FileDown,
+  FileUp,
+} from "lucide-react";
 import { useProfile } from "../../contexts/ProfileContext";
+import { exportProfileBundle } from "../../services/profileService";
+
+const BUNDLE_FILTERS: [string, string[]][] = [["Simpl'Result profile", ["srpb"]]];
 
 const PRESET_COLORS = [
   "#4A90A4", "#22c55e", "#ef4444", "#f59e0b", "#8b5cf6",
@@ -15,26 +29,45 @@ interface Props {
 
 export default function ProfileFormModal({ onClose, editProfileId }: Props) {
   const { t } = useTranslation();
-  const { profiles, createProfile, updateProfile, deleteProfile, setPin } = useProfile();
+  const {
+    profiles,
+    createProfile,
+    cloneProfile,
+    updateProfile,
+    deleteProfile,
+    setPin,
+    encryptProfile,
+    decryptProfile,
+    changeProfilePassword,
+    importProfileBundle,
+  } = useProfile();
 
   const editProfile = editProfileId
     ? profiles.find((p) => p.id === editProfileId)
     : null;
 
-  const [mode, setMode] = useState<"list" | "create" | "edit">(
+  const [mode, setMode] = useState<"list" | "create" | "edit" | "encryption" | "export" | "import">(
     editProfileId ? "edit" : "list"
   );
   const [selectedId, setSelectedId] = useState<string | null>(editProfileId ?? null);
   const [name, setName] = useState(editProfile?.name ?? "");
   const [color, setColor] = useState(editProfile?.color ?? PRESET_COLORS[0]);
   const [pin, setPin_] = useState("");
+  // "" for the stock categories, otherwise "<profile id>:full" or "<profile id>:structure"
+  const [source, setSource] = useState("");
   const [saving, setSaving] = useState(false);
+  const [currentPassword, setCurrentPassword] = useState("");
+  const [newPassword, setNewPassword] = useState("");
+  const [confirmPassword, setConfirmPassword] = useState("");
+  const [actionError, setActionError] = useState<string | null>(null);
+  const [importPath, setImportPath] = useState<string | null>(null);
 
   const handleCreate = () => {
     setMode("create");
     setName("");
     setColor(PRESET_COLORS[Math.floor(Math.random() * PRESET_COLORS.length)]);
     setPin_("");
+    setSource("");
     setSelectedId(null);
   };
 
@@ -48,11 +81,63 @@ export default function ProfileFormModal({ onClose, editProfileId }: Props) {
     setPin_("");
   };
 
+  const openProfileAction = (id: string, nextMode: "encryption" | "export" = "encryption") => {
+    setMode(nextMode);
+    setSelectedId(id);
+    setCurrentPassword("");
+    setNewPassword("");
+    setConfirmPassword("");
+    setActionError(null);
+  };
+
+  const runAction = async (action: () => Promise<void>) => {
+    setSaving(true);
+    setActionError(null);
+    try {
+      await action();
+      setMode("list");
+    } catch (e) {
+      setActionError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleExportBundle = async (profileId: string, profileName: string) => {
+    const filePath = await invoke<string | null>("pick_save_file", {
+      defaultName: `${profileName}.srpb`,
+      filters: BUNDLE_FILTERS,
+    });
+    if (!filePath) return;
+    await runAction(async () => {
+      await exportProfileBundle(profileId, filePath, newPassword || null);
+    });
+  };
+
+  const handleImportBundle = async () => {
+    setActionError(null);
+    const filePath = await invoke<string | null>("pick_import_file", { filters: BUNDLE_FILTERS });
+    if (!filePath) return;
+    const format = await invoke<string>("get_export_file_format", { filePath });
+    if (format === "password") {
+      setImportPath(filePath);
+      setCurrentPassword("");
+      setMode("import");
+      return;
+    }
+    await runAction(async () => {
+      await importProfileBundle(filePath);
+    });
+  };
+
   const handleSave = async () => {
     if (!name.trim()) return;
     setSaving(true);
     try {
-      if (mode === "create") {
+      if (mode === "create" && source) {
+        const [sourceId, cloneMode] = source.split(":") as [string, "full" | "structure"];
+        await cloneProfile(sourceId, name.trim(), color, cloneMode, pin.length >= 4 ? pin : undefined);
+      } else if (mode === "create") {
         await createProfile(name.trim(), color, pin.length >= 4 ? pin : undefined);
       } else if (mode === "edit" && selectedId) {
         await updateProfile(selectedId, { name: name.trim(), color });
@@ -84,6 +169,12 @@ export default function ProfileFormModal({ onClose, editProfileId }: Props) {
     }
   };
 
+  const selectedProfile = profiles.find((p) => p.id === selectedId);
+  const isEncrypted = !!selectedProfile?.db_key_salt;
+  const passwordsMatch = newPassword.length > 0 && newPassword === confirmPassword;
+  const passwordInputClass =
+    "w-full px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-[var(--foreground)] text-sm";
+
   return (
     <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
       <div className="bg-[var(--card)] rounded-xl shadow-xl w-full max-w-md border border-[var(--border)]">
@@ -93,6 +184,12 @@ export default function ProfileFormModal({ onClose, editProfileId }: Props) {
               ? t("profile.create")
               : mode === "edit"
               ? t("profile.edit")
+              : mode === "encryption"
+              ? t("profile.encryption.title")
+              : mode === "export"
+              ? t("profile.bundle.export")
+              : mode === "import"
+              ? t("profile.bundle.import")
               : t("profile.manageProfiles")}
           </h2>
           <button onClick={onClose} className="text-[var(--muted-foreground)] hover:text-[var(--foreground)]">
@@ -124,6 +221,22 @@ export default function ProfileFormModal({ onClose, editProfileId }: Props) {
                   >
                     {profile.pin_hash ? <Lock size={14} /> : <LockOpen size={14} />}
                   </button>
+                  <button
+                    onClick={() => openProfileAction(profile.id)}
+                    className={`p-1.5 rounded hover:bg-[var(--muted)] ${
+                      profile.db_key_salt ? "text-[var(--positive)]" : "text-[var(--muted-foreground)]"
+                    }`}
+                    title={profile.db_key_salt ? t("profile.encryption.encrypted") : t("profile.encryption.title")}
+                  >
+                    {profile.db_key_salt ? <ShieldCheck size={14} /> : <KeyRound size={14} />}
+                  </button>
+                  <button
+                    onClick={() => openProfileAction(profile.id, "export")}
+                    className="p-1.5 rounded hover:bg-[var(--muted)] text-[var(--muted-foreground)]"
+                    title={t("profile.bundle.export")}
+                  >
+                    <FileDown size={14} />
+                  </button>
                   <button
                     onClick={() => handleEdit(profile.id)}
                     className="text-xs px-2 py-1 rounded bg-[var(--primary)] text-white hover:opacity-90"
@@ -147,6 +260,195 @@ export default function ProfileFormModal({ onClose, editProfileId }: Props) {
                 <Plus size={16} />
                 {t("profile.create")}
               </button>
+              <button
+                onClick={handleImportBundle}
+                disabled={saving}
+                className="flex items-center gap-2 w-full p-3 rounded-lg border-2 border-dashed border-[var(--border)] hover:border-[var(--primary)] text-[var(--muted-foreground)] text-sm transition-colors disabled:opacity-50"
+              >
+                <FileUp size={16} />
+                {t("profile.bundle.import")}
+              </button>
+              {actionError && <p className="text-sm text-[var(--negative)]">{actionError}</p>}
+            </div>
+          ) : mode === "export" && selectedProfile ? (
+            <div className="space-y-4">
+              <p className="text-sm text-[var(--muted-foreground)]">
+                {t("profile.bundle.exportDescription", { name: selectedProfile.name })}
+              </p>
+              <div>
+                <label className="block text-sm font-medium text-[var(--foreground)] mb-1">
+                  {t("profile.bundle.password")}
+                </label>
+                <input
+                  type="password"
+                  value={newPassword}
+                  onChange={(e) => setNewPassword(e.target.value)}
+                  className={passwordInputClass}
+                  autoFocus
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium text-[var(--foreground)] mb-1">
+                  {t("profile.encryption.confirmPassword")}
+                </label>
+                <input
+                  type="password"
+                  value={confirmPassword}
+                  onChange={(e) => setConfirmPassword(e.target.value)}
+                  className={passwordInputClass}
+                />
+                {confirmPassword && !passwordsMatch && (
+                  <p className="text-xs text-[var(--negative)] mt-1">{t("profile.encryption.mismatch")}</p>
+                )}
+              </div>
+              <p className="text-xs text-[var(--muted-foreground)]">
+                {isEncrypted ? t("profile.bundle.passwordRequired") : t("profile.bundle.passwordOptional")}
+              </p>
+              {actionError && <p className="text-sm text-[var(--negative)]">{actionError}</p>}
+              <div className="flex gap-2 pt-2">
+                <button
+                  onClick={() => setMode("list")}
+                  className="flex-1 px-4 py-2 rounded-lg border border-[var(--border)] text-sm text-[var(--foreground)] hover:bg-[var(--muted)]"
+                >
+                  {t("common.cancel")}
+                </button>
+                <button
+                  onClick={() => handleExportBundle(selectedProfile.id, selectedProfile.name)}
+                  disabled={
+                    saving || (isEncrypted || newPassword.length > 0 ? !passwordsMatch : false)
+                  }
+                  className="flex-1 px-4 py-2 rounded-lg bg-[var(--primary)] text-white text-sm font-medium hover:opacity-90 disabled:opacity-50"
+                >
+                  {t("profile.bundle.exportButton")}
+                </button>
+              </div>
+            </div>
+          ) : mode === "import" && importPath ? (
+            <div className="space-y-4">
+              <p className="text-sm text-[var(--muted-foreground)]">
+                {t("profile.bundle.importPassword", { file: importPath.split(/[/\\]/).pop() })}
+              </p>
+              <input
+                type="password"
+                value={currentPassword}
+                onChange={(e) => setCurrentPassword(e.target.value)}
+                className={passwordInputClass}
+                autoFocus
+              />
+              {actionError && <p className="text-sm text-[var(--negative)]">{actionError}</p>}
+              <div className="flex gap-2 pt-2">
+                <button
+                  onClick={() => setMode("list")}
+                  className="flex-1 px-4 py-2 rounded-lg border border-[var(--border)] text-sm text-[var(--foreground)] hover:bg-[var(--muted)]"
+                >
+                  {t("common.cancel")}
+                </button>
+                <button
+                  onClick={() =>
+                    runAction(async () => {
+                      await importProfileBundle(importPath, currentPassword);
+                    })
+                  }
+                  disabled={!currentPassword || saving}
+                  className="flex-1 px-4 py-2 rounded-lg bg-[var(--primary)] text-white text-sm font-medium hover:opacity-90 disabled:opacity-50"
+                >
+                  {t("profile.bundle.importButton")}
+                </button>
+              </div>
+            </div>
+          ) : mode === "encryption" && selectedProfile ? (
+            <div className="space-y-4">
+              <p className="text-sm text-[var(--muted-foreground)]">
+                {isEncrypted
+                  ? t("profile.encryption.encryptedDescription", { name: selectedProfile.name })
+                  : t("profile.encryption.description", { name: selectedProfile.name })}
+              </p>
+
+              {isEncrypted && (
+                <div>
+                  <label className="block text-sm font-medium text-[var(--foreground)] mb-1">
+                    {t("profile.encryption.currentPassword")}
+                  </label>
+                  <input
+                    type="password"
+                    value={currentPassword}
+                    onChange={(e) => setCurrentPassword(e.target.value)}
+                    className={passwordInputClass}
+                    autoFocus
+                  />
+                </div>
+              )}
+
+              <div>
+                <label className="block text-sm font-medium text-[var(--foreground)] mb-1">
+                  {t("profile.encryption.newPassword")}
+                </label>
+                <input
+                  type="password"
+                  value={newPassword}
+                  onChange={(e) => setNewPassword(e.target.value)}
+                  className={passwordInputClass}
+                  autoFocus={!isEncrypted}
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium text-[var(--foreground)] mb-1">
+                  {t("profile.encryption.confirmPassword")}
+                </label>
+                <input
+                  type="password"
+                  value={confirmPassword}
+                  onChange={(e) => setConfirmPassword(e.target.value)}
+                  className={passwordInputClass}
+                />
+                {confirmPassword && !passwordsMatch && (
+                  <p className="text-xs text-[var(--negative)] mt-1">{t("profile.encryption.mismatch")}</p>
+                )}
+              </div>
+
+              <p className="text-xs text-[var(--muted-foreground)]">{t("profile.encryption.warning")}</p>
+              {actionError && <p className="text-sm text-[var(--negative)]">{actionError}</p>}
+
+              <div className="flex flex-wrap gap-2 pt-2">
+                <button
+                  onClick={() => setMode("list")}
+                  className="flex-1 px-4 py-2 rounded-lg border border-[var(--border)] text-sm text-[var(--foreground)] hover:bg-[var(--muted)]"
+                >
+                  {t("common.cancel")}
+                </button>
+                {isEncrypted ? (
+                  <>
+                    <button
+                      onClick={() =>
+                        runAction(() => decryptProfile(selectedProfile.id, currentPassword))
+                      }
+                      disabled={!currentPassword || saving}
+                      className="flex-1 px-4 py-2 rounded-lg border border-[var(--border)] text-sm text-[var(--negative)] hover:bg-[var(--muted)] disabled:opacity-50"
+                    >
+                      {t("profile.encryption.decrypt")}
+                    </button>
+                    <button
+                      onClick={() =>
+                        runAction(() =>
+                          changeProfilePassword(selectedProfile.id, currentPassword, newPassword)
+                        )
+                      }
+                      disabled={!currentPassword || !passwordsMatch || saving}
+                      className="flex-1 px-4 py-2 rounded-lg bg-[var(--primary)] text-white text-sm font-medium hover:opacity-90 disabled:opacity-50"
+                    >
+                      {t("profile.encryption.changePassword")}
+                    </button>
+                  </>
+                ) : (
+                  <button
+                    onClick={() => runAction(() => encryptProfile(selectedProfile.id, newPassword))}
+                    disabled={!passwordsMatch || saving}
+                    className="flex-1 px-4 py-2 rounded-lg bg-[var(--primary)] text-white text-sm font-medium hover:opacity-90 disabled:opacity-50"
+                  >
+                    {t("profile.encryption.encrypt")}
+                  </button>
+                )}
+              </div>
             </div>
           ) : (
             <div className="space-y-4">
@@ -182,6 +484,27 @@ export default function ProfileFormModal({ onClose, editProfileId }: Props) {
                 </div>
               </div>
 
+              {mode === "create" && (
+                <div>
+                  <label className="block text-sm font-medium text-[var(--foreground)] mb-1">
+                    {t("profile.startFrom")}
+                  </label>
+                  <select
+                    value={source}
+                    onChange={(e) => setSource(e.target.value)}
+                    className="w-full px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-[var(--foreground)] text-sm"
+                  >
+                    <option value="">{t("profile.startEmpty")}</option>
+                    {profiles.map((p) => (
+                      <optgroup key={p.id} label={p.name}>
+                        <option value={`${p.id}:structure`}>{t("profile.copyStructure")}</option>
+                        <option value={`${p.id}:full`}>{t("profile.copyFull")}</option>
+                      </optgroup>
+                    ))}
+                  </select>
+                </div>
+              )}
+
               {mode === "create" && (
                 <div>
                   <label className="block text-sm font-medium text-[var(--foreground)] mb-1">
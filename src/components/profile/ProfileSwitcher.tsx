@@ -3,6 +3,7 @@ import { useTranslation } from "react-i18next";
 import { ChevronDown, Lock, Settings } from "lucide-react";
 import { useProfile } from "../../contexts/ProfileContext";
 import PinDialog from "./PinDialog";
+import DbPasswordDialog from "./DbPasswordDialog";
 import ProfileFormModal from "./ProfileFormModal";
 import type { Profile } from "../../services/profileService";
 
@@ -11,6 +12,7 @@ export default function ProfileSwitcher() {
   const { profiles, activeProfile, switchProfile } = useProfile();
   const [open, setOpen] = useState(false);
   const [pinProfile, setPinProfile] = useState<Profile | null>(null);
+  const [passwordProfile, setPasswordProfile] = useState<Profile | null>(null);
   const [showManage, setShowManage] = useState(false);
   const ref = useRef<HTMLDivElement>(null);
 
@@ -25,6 +27,15 @@ export default function ProfileSwitcher() {
     return () => document.removeEventListener("mousedown", handleClick);
   }, [open]);
 
+  // Encrypted databases need their password before the switch
+  const openProfile = (profile: Profile) => {
+    if (profile.db_key_salt) {
+      setPasswordProfile(profile);
+    } else {
+      switchProfile(profile.id);
+    }
+  };
+
   const handleSelect = (profile: Profile) => {
     setOpen(false);
     if (profile.id === activeProfile?.id) return;
@@ -32,17 +43,23 @@ export default function ProfileSwitcher() {
     if (profile.pin_hash) {
       setPinProfile(profile);
     } else {
-      switchProfile(profile.id);
+      openProfile(profile);
     }
   };
 
   const handlePinSuccess = () => {
     if (pinProfile) {
-      switchProfile(pinProfile.id);
+      openProfile(pinProfile);
       setPinProfile(null);
     }
   };
 
+  const handlePassword = async (password: string) => {
+    if (!passwordProfile) return;
+    await switchProfile(passwordProfile.id, password);
+    setPasswordProfile(null);
+  };
+
   return (
     <>
       <div ref={ref} className="relative px-3 pb-2">
@@ -95,12 +112,20 @@ export default function ProfileSwitcher() {
       {pinProfile && (
         <PinDialog
           profileName={pinProfile.name}
-          storedHash={pinProfile.pin_hash!}
+          profileId={pinProfile.id}
           onSuccess={handlePinSuccess}
           onCancel={() => setPinProfile(null)}
         />
       )}
 
+      {passwordProfile && (
+        <DbPasswordDialog
+          profileName={passwordProfile.name}
+          onSubmit={handlePassword}
+          onCancel={() => setPasswordProfile(null)}
+        />
+      )}
+
       {showManage && (
         <ProfileFormModal onClose={() => setShowManage(false)} />
       )}
@@ -53,11 +53,17 @@ function pivotRows(
 interface GroupNode {
   key: string;
   label: string;
+  path: string[];                  // row-dimension values down to this group
   pivotedRows: PivotedRow[];
   children: GroupNode[];
 }
 
-function buildGroups(rows: PivotedRow[], rowDims: string[], depth: number): GroupNode[] {
+function buildGroups(
+  rows: PivotedRow[],
+  rowDims: string[],
+  depth: number,
+  parentPath: string[] = [],
+): GroupNode[] {
   if (depth >= rowDims.length) return [];
   const dim = rowDims[depth];
   const map = new Map<string, PivotedRow[]>();
@@ -68,29 +74,40 @@ function buildGroups(rows: PivotedRow[], rowDims: string[], depth: number): Grou
   }
   const groups: GroupNode[] = [];
   for (const [key, groupRows] of map) {
+    const path = [...parentPath, key];
     groups.push({
       key,
       label: key,
+      path,
       pivotedRows: groupRows,
-      children: buildGroups(groupRows, rowDims, depth + 1),
+      children: buildGroups(groupRows, rowDims, depth + 1, path),
     });
   }
   return groups;
 }
 
-function computeSubtotals(
-  rows: PivotedRow[],
-  measures: string[],
-  colValues: string[],
-): Record<string, Record<string, number>> {
-  const result: Record<string, Record<string, number>> = {};
-  for (const colVal of colValues) {
-    result[colVal] = {};
-    for (const m of measures) {
-      result[colVal][m] = rows.reduce((sum, r) => sum + (r.cells[colVal]?.[m] || 0), 0);
-    }
+/** Totals computed by the backend: row-dimension prefix → column key → measure → value */
+type TotalsIndex = Map<string, Record<string, Record<string, number>>>;
+
+const prefixKey = (path: string[]) => `${path.length}\u0001${path.join("\0")}`;
+
+/** Index subtotals and grand totals (the empty prefix) by row prefix and column */
+function indexTotals(
+  totals: PivotResultRow[],
+  rowDims: string[],
+  colDims: string[],
+): TotalsIndex {
+  const index: TotalsIndex = new Map();
+  for (const total of totals) {
+    const path = rowDims.filter((d) => d in total.keys).map((d) => total.keys[d]);
+    const colKey = colDims.length > 0
+      ? colDims.map((d) => total.keys[d] || "").join("\0")
+      : "__all__";
+    const key = prefixKey(path);
+    if (!index.has(key)) index.set(key, {});
+    index.get(key)![colKey] = total.measures;
   }
-  return result;
+  return index;
 }
 
 export default function DynamicReportTable({ config, result }: DynamicReportTableProps) {
@@ -131,7 +148,8 @@ export default function DynamicReportTable({ config, result }: DynamicReportTabl
   }
 
   const groups = rowDims.length > 0 ? buildGroups(pivotedRows, rowDims, 0) : [];
-  const grandTotals = computeSubtotals(pivotedRows, measures, colValues);
+  const totals = indexTotals([...result.subtotals, ...result.grandTotals], rowDims, colDims);
+  const grandTotals = totals.get(prefixKey([])) ?? {};
 
   const fieldLabel = (id: string) => t(`reports.pivot.${id === "level1" ? "level1" : id === "level2" ? "level2" : id === "type" ? "categoryType" : id}`);
   const measureLabel = (id: string) => t(`reports.pivot.${id}`);
@@ -188,6 +206,7 @@ export default function DynamicReportTable({ config, result }: DynamicReportTabl
                   rowDims={rowDims}
                   depth={0}
                   subtotalsOnTop={subtotalsOnTop}
+                  totals={totals}
                 />
               ))
             )}
@@ -218,6 +237,7 @@ function GroupRows({
   rowDims,
   depth,
   subtotalsOnTop,
+  totals,
 }: {
   group: GroupNode;
   colValues: string[];
@@ -225,9 +245,10 @@ function GroupRows({
   rowDims: string[];
   depth: number;
   subtotalsOnTop: boolean;
+  totals: TotalsIndex;
 }) {
   const isLeafLevel = depth === rowDims.length - 1;
-  const subtotals = computeSubtotals(group.pivotedRows, measures, colValues);
+  const subtotals = totals.get(prefixKey(group.path)) ?? {};
 
   const subtotalRow = rowDims.length > 1 && !isLeafLevel ? (
     <tr className="bg-[var(--muted)]/30 font-semibold border-b border-[var(--border)]/50">
@@ -282,6 +303,7 @@ function GroupRows({
       rowDims={rowDims}
       depth={depth + 1}
       subtotalsOnTop={subtotalsOnTop}
+      totals={totals}
     />
   ));
 
@@ -0,0 +1,264 @@
+import { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { invoke } from "@tauri-apps/api/core";
+import {
+  Archive,
+  FolderOpen,
+  Save,
+  Play,
+  AlertCircle,
+  CheckCircle,
+  Loader2,
+  KeyRound,
+} from "lucide-react";
+import { useProfile } from "../../contexts/ProfileContext";
+import {
+  getBackupHistory,
+  getBackupSettings,
+  runBackupNow,
+  saveBackupSettings,
+  type BackupFrequency,
+  type BackupHistoryEntry,
+  type BackupSettings,
+} from "../../services/backupService";
+
+const FREQUENCIES: BackupFrequency[] = ["on_start", "on_exit", "daily", "weekly"];
+const HISTORY_SHOWN = 10;
+
+const inputClass =
+  "px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-sm";
+
+function formatSize(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${Math.max(1, Math.round(bytes / 1024))} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+export default function BackupScheduleCard() {
+  const { t } = useTranslation();
+  const { activeProfile } = useProfile();
+  const profileId = activeProfile?.id;
+  const [settings, setSettings] = useState<BackupSettings | null>(null);
+  const [history, setHistory] = useState<BackupHistoryEntry[]>([]);
+  const [isBusy, setIsBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    if (!profileId) return;
+    const [current, runs] = await Promise.all([
+      getBackupSettings(profileId),
+      getBackupHistory(profileId),
+    ]);
+    setSettings(current);
+    setHistory(runs);
+  }, [profileId]);
+
+  useEffect(() => {
+    load().catch((e) => setError(String(e)));
+  }, [load]);
+
+  const run = async (action: () => Promise<string>) => {
+    setIsBusy(true);
+    setError(null);
+    setMessage(null);
+    try {
+      setMessage(await action());
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setIsBusy(false);
+      load().catch(() => {});
+    }
+  };
+
+  const update = (patch: Partial<BackupSettings>) =>
+    setSettings((prev) => (prev ? { ...prev, ...patch } : prev));
+
+  const handleBrowse = async () => {
+    const folder = await invoke<string | null>("pick_folder");
+    if (folder) update({ folder });
+  };
+
+  const handleSave = () =>
+    run(async () => {
+      if (!profileId || !settings) return "";
+      await saveBackupSettings(profileId, settings);
+      return t("settings.backups.saved");
+    });
+
+  const handleRunNow = () =>
+    run(async () => {
+      if (!profileId) return "";
+      const entry = await runBackupNow(profileId);
+      return t("settings.backups.done", { path: entry.file_path ?? "" });
+    });
+
+  if (!settings) return null;
+
+  const failures = history.filter((entry) => !entry.success).length;
+  const keepFields = [
+    ["keep_daily", "settings.backups.keepDaily"],
+    ["keep_weekly", "settings.backups.keepWeekly"],
+    ["keep_monthly", "settings.backups.keepMonthly"],
+  ] as const;
+
+  return (
+    <div className="bg-[var(--card)] border border-[var(--border)] rounded-xl p-6 space-y-4">
+      <h2 className="text-lg font-semibold flex items-center gap-2">
+        <Archive size={18} />
+        {t("settings.backups.title")}
+      </h2>
+      <p className="text-sm text-[var(--muted-foreground)]">{t("settings.backups.description")}</p>
+
+      <label className="flex items-center gap-2 text-sm">
+        <input
+          type="checkbox"
+          checked={settings.enabled}
+          onChange={(e) => update({ enabled: e.target.checked })}
+        />
+        {t("settings.backups.enabled")}
+      </label>
+
+      <div className="space-y-1">
+        <label className="block text-sm font-medium">{t("settings.backups.folder")}</label>
+        <div className="flex gap-2">
+          <input
+            value={settings.folder ?? ""}
+            onChange={(e) => update({ folder: e.target.value || null })}
+            placeholder={t("settings.backups.folderPlaceholder")}
+            className={`${inputClass} flex-1 min-w-0`}
+          />
+          <button
+            onClick={handleBrowse}
+            className="flex items-center gap-2 px-3 py-2 text-sm border border-[var(--border)] rounded-lg hover:bg-[var(--border)] transition-colors"
+          >
+            <FolderOpen size={16} />
+            {t("settings.backups.browse")}
+          </button>
+        </div>
+      </div>
+
+      <div className="flex flex-wrap gap-3">
+        <label className="space-y-1 text-sm">
+          <span className="block font-medium">{t("settings.backups.frequency")}</span>
+          <select
+            value={settings.frequency}
+            onChange={(e) => update({ frequency: e.target.value as BackupFrequency })}
+            className={inputClass}
+          >
+            {FREQUENCIES.map((frequency) => (
+              <option key={frequency} value={frequency}>
+                {t(`settings.backups.frequencies.${frequency}`)}
+              </option>
+            ))}
+          </select>
+        </label>
+        {keepFields.map(([field, label]) => (
+          <label key={field} className="space-y-1 text-sm">
+            <span className="block font-medium">{t(label)}</span>
+            <input
+              type="number"
+              min="0"
+              value={settings[field]}
+              onChange={(e) => update({ [field]: Math.max(0, Number(e.target.value) || 0) })}
+              className={`${inputClass} w-24`}
+            />
+          </label>
+        ))}
+      </div>
+
+      <div className="space-y-1">
+        <label className="flex items-center gap-2 text-sm font-medium">
+          <KeyRound size={14} />
+          {t("settings.backups.recipients")}
+        </label>
+        <textarea
+          rows={2}
+          value={settings.recipients.join("\n")}
+          onChange={(e) => update({ recipients: e.target.value.split("\n") })}
+          placeholder="age1..."
+          className={`${inputClass} w-full font-mono`}
+        />
+        <p
+          className={`flex items-start gap-2 text-xs ${
+            settings.recipients.some((r) => r.trim())
+              ? "text-[var(--muted-foreground)]"
+              : "text-[var(--negative)]"
+          }`}
+        >
+          <AlertCircle size={14} className="mt-0.5 shrink-0" />
+          {t("settings.backups.localKeyWarning")}
+        </p>
+      </div>
+
+      <div className="flex gap-2">
+        <button
+          onClick={handleSave}
+          disabled={isBusy}
+          className="flex items-center gap-2 px-4 py-2 text-sm rounded-lg bg-[var(--primary)] text-white hover:opacity-90 transition-opacity disabled:opacity-50"
+        >
+          {isBusy ? <Loader2 size={16} className="animate-spin" /> : <Save size={16} />}
+          {t("common.save")}
+        </button>
+        <button
+          onClick={handleRunNow}
+          disabled={isBusy || !settings.folder}
+          className="flex items-center gap-2 px-4 py-2 text-sm border border-[var(--border)] rounded-lg hover:bg-[var(--border)] transition-colors disabled:opacity-50"
+        >
+          <Play size={16} />
+          {t("settings.backups.runNow")}
+        </button>
+      </div>
+
+      {message && <p className="text-sm text-[var(--positive)]">{message}</p>}
+      {error && <p className="text-sm text-[var(--negative)]">{error}</p>}
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-semibold">{t("settings.backups.history")}</h3>
+        {history.length === 0 ? (
+          <p className="text-sm text-[var(--muted-foreground)]">{t("settings.backups.noHistory")}</p>
+        ) : (
+          <>
+            {failures > 0 && (
+              <p className="flex items-center gap-2 text-sm text-[var(--negative)]">
+                <AlertCircle size={16} className="shrink-0" />
+                {t("settings.backups.failures", { count: failures })}
+              </p>
+            )}
+            <ul className="space-y-1 text-sm max-h-64 overflow-y-auto">
+              {history.slice(0, HISTORY_SHOWN).map((entry) => (
+                <li key={`${entry.created_at}-${entry.trigger}`} className="flex items-start gap-2">
+                  {entry.success ? (
+                    <CheckCircle size={16} className="mt-0.5 shrink-0 text-[var(--positive)]" />
+                  ) : (
+                    <AlertCircle size={16} className="mt-0.5 shrink-0 text-[var(--negative)]" />
+                  )}
+                  <div className="min-w-0">
+                    <p>
+                      {entry.created_at.replace("T", " ")} —{" "}
+                      {t(`settings.backups.triggers.${entry.trigger}`)}
+                      {entry.size_bytes !== null && ` — ${formatSize(entry.size_bytes)}`}
+                    </p>
+                    {entry.error ? (
+                      <p className="text-xs text-[var(--negative)] break-all">{entry.error}</p>
+                    ) : (
+                      entry.file_path && (
+                        <p className="text-xs text-[var(--muted-foreground)] break-all">
+                          {entry.file_path}
+                          {entry.deleted_by_retention.length > 0 &&
+                            ` — ${t("settings.backups.pruned", {
+                              count: entry.deleted_by_retention.length,
+                            })}`}
+                        </p>
+                      )
+                    )}
+                  </div>
+                </li>
+              ))}
+            </ul>
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
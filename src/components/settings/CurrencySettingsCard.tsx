@@ -0,0 +1,208 @@
+import { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Coins, Upload, Trash2, AlertCircle, CheckCircle, Loader2 } from "lucide-react";
+import type { ConversionSummary, RateCoverage } from "../../shared/types";
+import {
+  deleteExchangeRates,
+  getBaseCurrency,
+  getExchangeRateCoverage,
+  importExchangeRates,
+  pickRatesFile,
+  refreshBaseAmounts,
+  setBaseCurrency,
+} from "../../services/currencyService";
+
+const inputClass =
+  "px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-sm uppercase";
+
+const isCurrencyCode = (value: string) => /^[A-Z]{3}$/.test(value);
+
+export default function CurrencySettingsCard() {
+  const { t } = useTranslation();
+  const [baseCurrency, setBaseCurrencyValue] = useState("");
+  const [savedBase, setSavedBase] = useState("");
+  const [reference, setReference] = useState("EUR");
+  const [coverage, setCoverage] = useState<RateCoverage[]>([]);
+  const [summary, setSummary] = useState<ConversionSummary | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+
+  const load = useCallback(async () => {
+    const [base, rates, conversion] = await Promise.all([
+      getBaseCurrency(),
+      getExchangeRateCoverage(),
+      refreshBaseAmounts(true),
+    ]);
+    setBaseCurrencyValue(base);
+    setSavedBase(base);
+    setCoverage(rates);
+    setSummary(conversion);
+  }, []);
+
+  useEffect(() => {
+    load().catch((e) => setError(String(e)));
+  }, [load]);
+
+  const run = async (action: () => Promise<void>) => {
+    setIsBusy(true);
+    setError(null);
+    setMessage(null);
+    try {
+      await action();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleSaveBase = () =>
+    run(async () => {
+      setSummary(await setBaseCurrency(baseCurrency));
+      setSavedBase(baseCurrency);
+      setMessage(t("settings.currency.baseSaved", { currency: baseCurrency }));
+    });
+
+  const handleImport = () =>
+    run(async () => {
+      const filePath = await pickRatesFile();
+      if (!filePath) return;
+      const report = await importExchangeRates(filePath, reference);
+      setSummary(report.conversion);
+      setCoverage(await getExchangeRateCoverage());
+      setMessage(
+        t("settings.currency.imported", {
+          count: report.rate_count,
+          from: report.date_from,
+          to: report.date_to,
+        })
+      );
+    });
+
+  const handleDelete = (rates: RateCoverage) =>
+    run(async () => {
+      setSummary(await deleteExchangeRates(rates.from_currency, rates.to_currency));
+      setCoverage(await getExchangeRateCoverage());
+    });
+
+  return (
+    <div className="bg-[var(--card)] border border-[var(--border)] rounded-xl p-6 space-y-6">
+      <h2 className="text-lg font-semibold flex items-center gap-2">
+        <Coins size={18} />
+        {t("settings.currency.title")}
+      </h2>
+
+      {/* Base currency */}
+      <div className="space-y-2">
+        <label className="text-sm block">{t("settings.currency.baseLabel")}</label>
+        <div className="flex gap-2">
+          <input
+            type="text"
+            maxLength={3}
+            value={baseCurrency}
+            onChange={(e) => setBaseCurrencyValue(e.target.value.toUpperCase())}
+            className={`${inputClass} w-24`}
+          />
+          <button
+            onClick={handleSaveBase}
+            disabled={isBusy || !isCurrencyCode(baseCurrency) || baseCurrency === savedBase}
+            className="px-4 py-2 text-sm rounded-lg bg-[var(--primary)] text-white hover:opacity-90 transition-opacity disabled:opacity-50"
+          >
+            {t("common.save")}
+          </button>
+        </div>
+        <p className="text-xs text-[var(--muted-foreground)]">
+          {t("settings.currency.baseDescription")}
+        </p>
+      </div>
+
+      {/* Exchange rates */}
+      <div className="space-y-4">
+        <h3 className="text-sm font-medium text-[var(--muted-foreground)] uppercase tracking-wider">
+          {t("settings.currency.ratesTitle")}
+        </h3>
+        <p className="text-sm text-[var(--muted-foreground)]">
+          {t("settings.currency.ratesDescription")}
+        </p>
+        <div className="flex items-end gap-2">
+          <div>
+            <label className="text-sm block mb-1">{t("settings.currency.referenceLabel")}</label>
+            <input
+              type="text"
+              maxLength={3}
+              value={reference}
+              onChange={(e) => setReference(e.target.value.toUpperCase())}
+              className={`${inputClass} w-24`}
+            />
+          </div>
+          <button
+            onClick={handleImport}
+            disabled={isBusy || !isCurrencyCode(reference)}
+            className="flex items-center gap-2 px-4 py-2 text-sm border border-[var(--border)] rounded-lg hover:bg-[var(--border)] transition-colors disabled:opacity-50"
+          >
+            {isBusy ? <Loader2 size={16} className="animate-spin" /> : <Upload size={16} />}
+            {t("settings.currency.importButton")}
+          </button>
+        </div>
+
+        {coverage.length === 0 ? (
+          <p className="text-sm text-[var(--muted-foreground)]">{t("settings.currency.noRates")}</p>
+        ) : (
+          <ul className="space-y-1">
+            {coverage.map((rates) => (
+              <li
+                key={`${rates.from_currency}-${rates.to_currency}`}
+                className="flex items-center gap-3 text-sm"
+              >
+                <span className="w-24 font-mono">
+                  {rates.from_currency}/{rates.to_currency}
+                </span>
+                <span className="flex-1 text-xs text-[var(--muted-foreground)]">
+                  {t("settings.currency.coverage", {
+                    count: rates.rate_count,
+                    from: rates.first_date,
+                    to: rates.last_date,
+                  })}
+                </span>
+                <button
+                  onClick={() => handleDelete(rates)}
+                  disabled={isBusy}
+                  className="p-1 rounded hover:bg-[var(--muted)] text-[var(--negative)]"
+                  title={t("settings.currency.deleteRates")}
+                >
+                  <Trash2 size={14} />
+                </button>
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+
+      {summary && summary.missing.length > 0 && (
+        <div className="space-y-1 text-sm text-[var(--negative)]">
+          {summary.missing.map((missing) => (
+            <p key={missing.currency} className="flex items-start gap-2">
+              <AlertCircle size={16} className="mt-0.5 shrink-0" />
+              {t("settings.currency.missingRates", {
+                count: missing.transaction_count,
+                currency: missing.currency,
+                base: summary.base_currency,
+                from: missing.first_date,
+                to: missing.last_date,
+              })}
+            </p>
+          ))}
+        </div>
+      )}
+
+      {message && (
+        <p className="flex items-center gap-2 text-sm text-[var(--positive)]">
+          <CheckCircle size={16} />
+          {message}
+        </p>
+      )}
+      {error && <p className="text-sm text-[var(--negative)]">{error}</p>}
+    </div>
+  );
+}
@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
 import {
   Database,
@@ -8,10 +8,17 @@ import {
   CheckCircle,
   AlertCircle,
   Loader2,
+  KeyRound,
+  Copy,
+  FileKey,
 } from "lucide-react";
 import { useDataExport } from "../../hooks/useDataExport";
 import { useDataImport } from "../../hooks/useDataImport";
-import type { ExportMode, ExportFormat } from "../../services/dataExportService";
+import {
+  getExportPublicKey,
+  type ExportMode,
+  type ExportFormat,
+} from "../../services/dataExportService";
 import ImportConfirmModal from "./ImportConfirmModal";
 
 export default function DataManagementCard() {
@@ -24,9 +31,18 @@ export default function DataManagementCard() {
     "transactions_with_categories"
   );
   const [exportFormat, setExportFormat] = useState<ExportFormat>("json");
-  const [encryptExport, setEncryptExport] = useState(false);
+  const [encryption, setEncryption] = useState<"none" | "password" | "recipients">("none");
   const [exportPassword, setExportPassword] = useState("");
   const [exportPasswordConfirm, setExportPasswordConfirm] = useState("");
+  const [recipientsText, setRecipientsText] = useState("");
+  const [publicKey, setPublicKey] = useState<string | null>(null);
+  const [keyCopied, setKeyCopied] = useState(false);
+
+  useEffect(() => {
+    getExportPublicKey()
+      .then(setPublicKey)
+      .catch(() => setPublicKey(null));
+  }, []);
 
   // Import password state
   const [importPassword, setImportPassword] = useState("");
@@ -38,14 +54,27 @@ export default function DataManagementCard() {
   }
 
   const passwordsMatch = exportPassword === exportPasswordConfirm;
-  const passwordValid = !encryptExport || (exportPassword.length >= 8 && passwordsMatch);
+  const recipients = recipientsText
+    .split(/[\s,]+/)
+    .map((r) => r.trim())
+    .filter((r) => r.length > 0);
+  const encryptionValid =
+    encryption === "none" ||
+    (encryption === "password" && exportPassword.length >= 8 && passwordsMatch) ||
+    (encryption === "recipients" && recipients.length > 0);
 
   const handleExport = () => {
-    exportHook.performExport(
-      exportMode,
-      exportFormat,
-      encryptExport ? exportPassword : undefined
-    );
+    exportHook.performExport(exportMode, exportFormat, {
+      password: encryption === "password" ? exportPassword : undefined,
+      recipients: encryption === "recipients" ? recipients : undefined,
+    });
+  };
+
+  const handleCopyKey = async () => {
+    if (!publicKey) return;
+    await navigator.clipboard.writeText(publicKey);
+    setKeyCopied(true);
+    setTimeout(() => setKeyCopied(false), 2000);
   };
 
   const handleImportPasswordSubmit = () => {
@@ -134,24 +163,31 @@ export default function DataManagementCard() {
 
           {/* Encryption */}
           <div className="space-y-2">
-            <label className="flex items-center gap-2 text-sm cursor-pointer">
-              <input
-                type="checkbox"
-                checked={encryptExport}
-                onChange={(e) => {
-                  setEncryptExport(e.target.checked);
-                  if (!e.target.checked) {
-                    setExportPassword("");
-                    setExportPasswordConfirm("");
-                  }
-                }}
-                className="accent-[var(--primary)]"
-              />
+            <label className="text-sm flex items-center gap-2">
               <Lock size={14} />
-              {t("settings.dataManagement.export.encryptLabel")}
+              {t("settings.dataManagement.export.encryptionLabel")}
             </label>
+            <div className="flex flex-wrap gap-4">
+              {(["none", "password", "recipients"] as const).map((value) => (
+                <label key={value} className="flex items-center gap-2 text-sm cursor-pointer">
+                  <input
+                    type="radio"
+                    name="exportEncryption"
+                    value={value}
+                    checked={encryption === value}
+                    onChange={() => {
+                      setEncryption(value);
+                      setExportPassword("");
+                      setExportPasswordConfirm("");
+                    }}
+                    className="accent-[var(--primary)]"
+                  />
+                  {t(`settings.dataManagement.export.encryption.${value}`)}
+                </label>
+              ))}
+            </div>
 
-            {encryptExport && (
+            {encryption === "password" && (
               <div className="space-y-2 ml-6">
                 <input
                   type="password"
@@ -181,13 +217,28 @@ export default function DataManagementCard() {
                   )}
               </div>
             )}
+
+            {encryption === "recipients" && (
+              <div className="space-y-1 ml-6">
+                <textarea
+                  rows={3}
+                  placeholder="age1..."
+                  value={recipientsText}
+                  onChange={(e) => setRecipientsText(e.target.value)}
+                  className="w-full px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-sm font-mono"
+                />
+                <p className="text-xs text-[var(--muted-foreground)]">
+                  {t("settings.dataManagement.export.recipientsHint")}
+                </p>
+              </div>
+            )}
           </div>
 
           {/* Export button */}
           <button
             onClick={handleExport}
             disabled={
-              exportHook.state.status === "exporting" || !passwordValid
+              exportHook.state.status === "exporting" || !encryptionValid
             }
             className="flex items-center gap-2 px-4 py-2 bg-[var(--primary)] text-white rounded-lg hover:opacity-90 transition-opacity disabled:opacity-50"
           >
@@ -214,6 +265,31 @@ export default function DataManagementCard() {
           )}
         </div>
 
+        {/* Public key */}
+        {publicKey && (
+          <div className="space-y-2">
+            <p className="text-sm flex items-center gap-2">
+              <KeyRound size={14} />
+              {t("settings.dataManagement.publicKey.label")}
+            </p>
+            <div className="flex gap-2">
+              <code className="flex-1 px-3 py-2 rounded-lg border border-[var(--border)] bg-[var(--background)] text-xs break-all">
+                {publicKey}
+              </code>
+              <button
+                onClick={handleCopyKey}
+                className="flex items-center gap-2 px-3 py-2 border border-[var(--border)] rounded-lg hover:bg-[var(--border)] transition-colors text-sm"
+              >
+                {keyCopied ? <CheckCircle size={14} /> : <Copy size={14} />}
+                {t(keyCopied ? "settings.dataManagement.publicKey.copied" : "settings.dataManagement.publicKey.copy")}
+              </button>
+            </div>
+            <p className="text-xs text-[var(--muted-foreground)]">
+              {t("settings.dataManagement.publicKey.hint")}
+            </p>
+          </div>
+        )}
+
         {/* Divider */}
         <hr className="border-[var(--border)]" />
 
@@ -280,6 +356,34 @@ export default function DataManagementCard() {
             </div>
           )}
 
+          {/* Identity file for files encrypted to another key */}
+          {importHook.state.status === "needsIdentity" && (
+            <div className="space-y-2 p-3 border border-[var(--border)] rounded-lg">
+              <p className="text-sm">
+                <KeyRound size={14} className="inline mr-1" />
+                {t("settings.dataManagement.import.identityRequired")}
+              </p>
+              {importHook.state.error && (
+                <p className="text-xs text-[var(--negative)]">{importHook.state.error}</p>
+              )}
+              <div className="flex gap-2">
+                <button
+                  onClick={importHook.readWithIdentity}
+                  className="flex items-center gap-2 px-4 py-2 bg-[var(--primary)] text-white rounded-lg hover:opacity-90 transition-opacity text-sm"
+                >
+                  <FileKey size={14} />
+                  {t("settings.dataManagement.import.chooseIdentity")}
+                </button>
+                <button
+                  onClick={importHook.reset}
+                  className="px-4 py-2 border border-[var(--border)] rounded-lg hover:bg-[var(--border)] transition-colors text-sm"
+                >
+                  {t("common.cancel")}
+                </button>
+              </div>
+            </div>
+          )}
+
           {/* Import feedback */}
           {importHook.state.status === "success" && (
             <div className="flex items-center gap-2 text-[var(--positive)] text-sm">
@@ -0,0 +1,102 @@
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { CalendarCheck, Search, Wrench, AlertCircle, CheckCircle, Loader2 } from "lucide-react";
+import type { DateAuditReport } from "../../shared/types";
+import { auditProfileDates } from "../../services/dateService";
+
+export default function DateAuditCard() {
+  const { t } = useTranslation();
+  const [report, setReport] = useState<DateAuditReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+
+  const run = async (dryRun: boolean) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      setReport(await auditProfileDates(dryRun));
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const fixable = report?.fixes.filter((f) => f.normalized) ?? [];
+  const unresolved = report?.fixes.filter((f) => f.problem) ?? [];
+
+  return (
+    <div className="bg-[var(--card)] border border-[var(--border)] rounded-xl p-6 space-y-4">
+      <h2 className="text-lg font-semibold flex items-center gap-2">
+        <CalendarCheck size={18} />
+        {t("settings.dates.title")}
+      </h2>
+      <p className="text-sm text-[var(--muted-foreground)]">{t("settings.dates.description")}</p>
+
+      <div className="flex gap-2">
+        <button
+          onClick={() => run(true)}
+          disabled={isBusy}
+          className="flex items-center gap-2 px-4 py-2 text-sm border border-[var(--border)] rounded-lg hover:bg-[var(--border)] transition-colors disabled:opacity-50"
+        >
+          {isBusy ? <Loader2 size={16} className="animate-spin" /> : <Search size={16} />}
+          {t("settings.dates.check")}
+        </button>
+        {report?.dry_run && fixable.length > 0 && (
+          <button
+            onClick={() => run(false)}
+            disabled={isBusy}
+            className="flex items-center gap-2 px-4 py-2 text-sm rounded-lg bg-[var(--primary)] text-white hover:opacity-90 transition-opacity disabled:opacity-50"
+          >
+            <Wrench size={16} />
+            {t("settings.dates.normalize", { count: fixable.length })}
+          </button>
+        )}
+      </div>
+
+      {report && (
+        <div className="space-y-2 text-sm">
+          {report.fixes.length === 0 ? (
+            <p className="flex items-center gap-2 text-[var(--positive)]">
+              <CheckCircle size={16} />
+              {t("settings.dates.allValid", { count: report.checked })}
+            </p>
+          ) : report.applied ? (
+            <p className="flex items-center gap-2 text-[var(--positive)]">
+              <CheckCircle size={16} />
+              {t("settings.dates.normalized", { count: fixable.length })}
+            </p>
+          ) : (
+            <>
+              <p>{t("settings.dates.found", { count: report.fixes.length, checked: report.checked })}</p>
+              <ul className="space-y-1 max-h-48 overflow-y-auto">
+                {fixable.map((fix) => (
+                  <li key={`${fix.table}-${fix.column}-${fix.row_id}`} className="font-mono text-xs">
+                    {fix.table}.{fix.column} #{fix.row_id}: {fix.value} → {fix.normalized}
+                  </li>
+                ))}
+              </ul>
+            </>
+          )}
+          {unresolved.length > 0 && (
+            <div className="space-y-1 text-[var(--negative)]">
+              <p className="flex items-center gap-2">
+                <AlertCircle size={16} className="shrink-0" />
+                {t("settings.dates.unresolved", { count: unresolved.length })}
+              </p>
+              <ul className="space-y-1 max-h-48 overflow-y-auto">
+                {unresolved.map((fix) => (
+                  <li key={`${fix.table}-${fix.column}-${fix.row_id}`} className="font-mono text-xs">
+                    {fix.table}.{fix.column} #{fix.row_id}: {fix.problem}
+                  </li>
+                ))}
+              </ul>
+            </div>
+          )}
+        </div>
+      )}
+
+      {error && <p className="text-sm text-[var(--negative)]">{error}</p>}
+    </div>
+  );
+}
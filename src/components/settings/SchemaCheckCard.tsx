@@ -0,0 +1,113 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import type { TFunction } from "i18next";
+import { Database, RefreshCw, AlertCircle, CheckCircle, Lock, Loader2 } from "lucide-react";
+import {
+  checkSchema,
+  getStartupSchemaReport,
+  type SchemaCheckReport,
+} from "../../services/profileService";
+
+/** Problems of one report as display lines, grouped under their profile name. */
+export function schemaProblems(
+  report: SchemaCheckReport,
+  t: TFunction
+): { title: string; lines: string[] }[] {
+  const groups: { title: string; lines: string[] }[] = [];
+  if (report.consolidated_drift.length > 0) {
+    groups.push({ title: t("settings.schema.consolidated"), lines: report.consolidated_drift });
+  }
+  for (const profile of report.profiles) {
+    const lines = [
+      ...(profile.error ? [profile.error] : []),
+      ...profile.checksum_issues.map((issue) =>
+        t(`settings.schema.checksum.${issue.kind}`, {
+          version: issue.version,
+          description: issue.description,
+        })
+      ),
+      ...profile.drift,
+    ];
+    if (lines.length > 0) groups.push({ title: profile.profile_name, lines });
+  }
+  return groups;
+}
+
+export default function SchemaCheckCard() {
+  const { t } = useTranslation();
+  const [report, setReport] = useState<SchemaCheckReport | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = async (load: () => Promise<SchemaCheckReport | null>) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      // The startup check may still be running: check now instead of waiting for it
+      setReport((await load()) ?? (await checkSchema()));
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  useEffect(() => {
+    run(getStartupSchemaReport);
+  }, []);
+
+  const problems = report ? schemaProblems(report, t) : [];
+  const locked = report?.profiles.filter((p) => p.locked) ?? [];
+
+  return (
+    <div className="bg-[var(--card)] border border-[var(--border)] rounded-xl p-6 space-y-4">
+      <h2 className="text-lg font-semibold flex items-center gap-2">
+        <Database size={18} />
+        {t("settings.schema.title")}
+      </h2>
+      <p className="text-sm text-[var(--muted-foreground)]">{t("settings.schema.description")}</p>
+
+      <button
+        onClick={() => run(checkSchema)}
+        disabled={isBusy}
+        className="flex items-center gap-2 px-4 py-2 text-sm border border-[var(--border)] rounded-lg hover:bg-[var(--border)] transition-colors disabled:opacity-50"
+      >
+        {isBusy ? <Loader2 size={16} className="animate-spin" /> : <RefreshCw size={16} />}
+        {t("settings.schema.check")}
+      </button>
+
+      {error && <p className="text-sm text-[var(--negative)]">{error}</p>}
+
+      {report && (
+        <div className="space-y-2 text-sm">
+          {report.ok ? (
+            <p className="flex items-center gap-2 text-[var(--positive)]">
+              <CheckCircle size={16} />
+              {t("settings.schema.ok", { version: report.latest_version })}
+            </p>
+          ) : (
+            problems.map((group) => (
+              <div key={group.title} className="space-y-1">
+                <p className="flex items-center gap-2 font-medium text-[var(--negative)]">
+                  <AlertCircle size={16} className="shrink-0" />
+                  {group.title}
+                </p>
+                <ul className="pl-6 list-disc text-[var(--muted-foreground)] break-words">
+                  {group.lines.map((line) => (
+                    <li key={line}>{line}</li>
+                  ))}
+                </ul>
+              </div>
+            ))
+          )}
+          {locked.length > 0 && (
+            <p className="flex items-center gap-2 text-[var(--muted-foreground)]">
+              <Lock size={14} className="shrink-0" />
+              {t("settings.schema.locked", { names: locked.map((p) => p.profile_name).join(", ") })}
+            </p>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
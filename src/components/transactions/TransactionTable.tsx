@@ -1,6 +1,6 @@
 import { Fragment, useState, useMemo } from "react";
 import { useTranslation } from "react-i18next";
-import { ChevronUp, ChevronDown, MessageSquare, Tag, Split } from "lucide-react";
+import { ChevronUp, ChevronDown, MessageSquare, Tag, Split, Lock } from "lucide-react";
 import type {
   TransactionRow,
   TransactionSort,
@@ -139,6 +139,13 @@ export default function TransactionTable({
               >
                 <td className="px-3 py-2 whitespace-nowrap">{row.date}</td>
                 <td className="px-3 py-2 max-w-xs truncate" title={row.description}>
+                  {row.reconciliation_id !== null && (
+                    <Lock
+                      size={12}
+                      className="inline mr-1 text-[var(--muted-foreground)]"
+                      aria-label={t("transactions.reconciled")}
+                    />
+                  )}
                   {row.description}
                 </td>
                 <td
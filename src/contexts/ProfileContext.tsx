@@ -5,6 +5,14 @@ import {
   deleteProfileDb,
   getNewProfileInitSql,
   hashPin,
+  cloneProfile as cloneProfileDb,
+  rebuildProfiles as rebuildProfilesFile,
+  unlockProfileDb,
+  encryptProfileDb,
+  decryptProfileDb,
+  changeProfileDbPassword,
+  importProfileBundle as importProfileBundleFile,
+  type BundleImportResult,
   type Profile,
   type ProfilesConfig,
 } from "../services/profileService";
@@ -44,12 +52,26 @@ interface ProfileContextValue {
   isLoading: boolean;
   refreshKey: number;
   error: string | null;
-  switchProfile: (id: string) => Promise<void>;
+  /** `password` unlocks the profile's database first when it is encrypted. */
+  switchProfile: (id: string, password?: string) => Promise<void>;
   createProfile: (name: string, color: string, pin?: string) => Promise<void>;
+  cloneProfile: (
+    sourceId: string,
+    name: string,
+    color: string,
+    mode: "full" | "structure",
+    pin?: string
+  ) => Promise<void>;
   updateProfile: (id: string, updates: Partial<Pick<Profile, "name" | "color">>) => Promise<void>;
   deleteProfile: (id: string) => Promise<void>;
   setPin: (id: string, pin: string | null) => Promise<void>;
-  connectActiveProfile: () => Promise<void>;
+  connectActiveProfile: (password?: string) => Promise<void>;
+  rebuildProfiles: () => Promise<void>;
+  encryptProfile: (id: string, password: string) => Promise<void>;
+  decryptProfile: (id: string, password: string) => Promise<void>;
+  changeProfilePassword: (id: string, oldPassword: string, newPassword: string) => Promise<void>;
+  /** Rejects with the backend error (wrong password, newer schema...) so it can be shown. */
+  importProfileBundle: (filePath: string, password?: string) => Promise<BundleImportResult>;
 }
 
 const ProfileContext = createContext<ProfileContextValue | null>(null);
@@ -78,25 +100,36 @@ export function ProfileProvider({ children }: { children: ReactNode }) {
       });
   }, []);
 
-  const connectActiveProfile = useCallback(async () => {
+  const connectActiveProfile = useCallback(async (password?: string) => {
     if (!state.config) return;
     const profile = state.config.profiles.find(
       (p) => p.id === state.config!.active_profile_id
     );
     if (!profile) return;
+    if (profile.db_key_salt && password) {
+      await unlockProfileDb(profile.id, password);
+    }
     await connectToProfile(profile.db_filename);
   }, [state.config]);
 
-  const switchProfile = useCallback(async (id: string) => {
+  const switchProfile = useCallback(async (id: string, password?: string) => {
     if (!state.config) return;
     const profile = state.config.profiles.find((p) => p.id === id);
     if (!profile) return;
 
+    // Unlock before closing the current database: a wrong password throws to the caller
+    // and leaves the active profile connected
+    if (profile.db_key_salt && password) {
+      await unlockProfileDb(id, password);
+    }
+
     dispatch({ type: "SET_LOADING", isLoading: true });
     try {
       await closeDb();
       await connectToProfile(profile.db_filename);
-      const newConfig = { ...state.config, active_profile_id: id };
+      // Re-read from disk: a PIN check may have upgraded the stored hash meanwhile
+      const currentConfig = await loadProfiles();
+      const newConfig = { ...currentConfig, active_profile_id: id };
       await saveProfiles(newConfig);
       dispatch({ type: "SET_CONFIG", config: newConfig });
       dispatch({ type: "INCREMENT_REFRESH" });
@@ -151,6 +184,36 @@ export function ProfileProvider({ children }: { children: ReactNode }) {
     }
   }, [state.config]);
 
+  const cloneProfile = useCallback(async (
+    sourceId: string,
+    name: string,
+    color: string,
+    mode: "full" | "structure",
+    pin?: string
+  ) => {
+    dispatch({ type: "SET_LOADING", isLoading: true });
+    try {
+      // The backend copies the database and registers the new profile
+      const profile = await cloneProfileDb(sourceId, name, color, mode);
+      let newConfig = await loadProfiles();
+      if (pin) {
+        const pinHash = await hashPin(pin);
+        newConfig = {
+          ...newConfig,
+          profiles: newConfig.profiles.map((p) =>
+            p.id === profile.id ? { ...p, pin_hash: pinHash } : p
+          ),
+        };
+        await saveProfiles(newConfig);
+      }
+      dispatch({ type: "SET_CONFIG", config: newConfig });
+    } catch (err) {
+      dispatch({ type: "SET_ERROR", error: String(err) });
+    } finally {
+      dispatch({ type: "SET_LOADING", isLoading: false });
+    }
+  }, []);
+
   const updateProfile = useCallback(async (id: string, updates: Partial<Pick<Profile, "name" | "color">>) => {
     if (!state.config) return;
 
@@ -170,7 +233,8 @@ export function ProfileProvider({ children }: { children: ReactNode }) {
 
     dispatch({ type: "SET_LOADING", isLoading: true });
     try {
-      // If deleting the active profile, switch to default first
+      // If deleting the active profile, switch to default first: the backend refuses to
+      // delete the active profile's database
       if (state.config.active_profile_id === id) {
         const defaultProfile = state.config.profiles.find(
           (p) => p.db_filename === "simpl_resultat.db"
@@ -179,21 +243,16 @@ export function ProfileProvider({ children }: { children: ReactNode }) {
           await closeDb();
           await connectToProfile(defaultProfile.db_filename);
         }
+        const newActiveId =
+          defaultProfile?.id ?? state.config.profiles.find((p) => p.id !== id)?.id ?? "default";
+        const currentConfig = await loadProfiles();
+        await saveProfiles({ ...currentConfig, active_profile_id: newActiveId });
       }
 
+      // Also removes the profile from profiles.json
       await deleteProfileDb(profile.db_filename);
 
-      const newProfiles = state.config.profiles.filter((p) => p.id !== id);
-      const newActiveId =
-        state.config.active_profile_id === id
-          ? newProfiles[0]?.id ?? "default"
-          : state.config.active_profile_id;
-
-      const newConfig: ProfilesConfig = {
-        active_profile_id: newActiveId,
-        profiles: newProfiles,
-      };
-      await saveProfiles(newConfig);
+      const newConfig = await loadProfiles();
       dispatch({ type: "SET_CONFIG", config: newConfig });
       if (state.config.active_profile_id === id) {
         dispatch({ type: "INCREMENT_REFRESH" });
@@ -217,6 +276,57 @@ export function ProfileProvider({ children }: { children: ReactNode }) {
     dispatch({ type: "SET_CONFIG", config: newConfig });
   }, [state.config]);
 
+  const rebuildProfiles = useCallback(async () => {
+    dispatch({ type: "SET_LOADING", isLoading: true });
+    try {
+      const result = await rebuildProfilesFile();
+      dispatch({ type: "SET_CONFIG", config: result.config });
+    } catch (err) {
+      dispatch({ type: "SET_ERROR", error: String(err) });
+    } finally {
+      dispatch({ type: "SET_LOADING", isLoading: false });
+    }
+  }, []);
+
+  // Encrypting, decrypting or re-keying rewrites the database file, so the active profile's
+  // connection is closed for the duration and reopened afterwards, even on failure
+  const rewriteProfileDb = useCallback(async (id: string, action: () => Promise<void>) => {
+    if (!state.config) return;
+    const profile = state.config.profiles.find((p) => p.id === id);
+    if (!profile) return;
+    const isActive = state.config.active_profile_id === id;
+
+    if (isActive) await closeDb();
+    try {
+      await action();
+    } finally {
+      if (isActive) await connectToProfile(profile.db_filename);
+      dispatch({ type: "SET_CONFIG", config: await loadProfiles() });
+    }
+  }, [state.config]);
+
+  const encryptProfile = useCallback(
+    (id: string, password: string) => rewriteProfileDb(id, () => encryptProfileDb(id, password)),
+    [rewriteProfileDb]
+  );
+
+  const decryptProfile = useCallback(
+    (id: string, password: string) => rewriteProfileDb(id, () => decryptProfileDb(id, password)),
+    [rewriteProfileDb]
+  );
+
+  const changeProfilePassword = useCallback(
+    (id: string, oldPassword: string, newPassword: string) =>
+      rewriteProfileDb(id, () => changeProfileDbPassword(id, oldPassword, newPassword)),
+    [rewriteProfileDb]
+  );
+
+  const importProfileBundle = useCallback(async (filePath: string, password?: string) => {
+    const result = await importProfileBundleFile(filePath, password);
+    dispatch({ type: "SET_CONFIG", config: await loadProfiles() });
+    return result;
+  }, []);
+
   return (
     <ProfileContext.Provider
       value={{
@@ -227,10 +337,16 @@ export function ProfileProvider({ children }: { children: ReactNode }) {
         error: state.error,
         switchProfile,
         createProfile,
+        cloneProfile,
         updateProfile,
         deleteProfile,
         setPin,
         connectActiveProfile,
+        rebuildProfiles,
+        encryptProfile,
+        decryptProfile,
+        changeProfilePassword,
+        importProfileBundle,
       }}
     >
       {children}
@@ -1,5 +1,10 @@
 import { useReducer, useCallback, useEffect, useRef } from "react";
-import type { Adjustment, Category } from "../shared/types";
+import type {
+  Adjustment,
+  AdjustmentException,
+  AdjustmentOccurrence,
+  Category,
+} from "../shared/types";
 import type { AdjustmentEntryWithCategory } from "../services/adjustmentService";
 import {
   getAllAdjustments,
@@ -10,6 +15,10 @@ import {
   createEntry,
   updateEntry,
   deleteEntry,
+  getAdjustmentOccurrences,
+  getExceptionsByAdjustmentId,
+  setOccurrenceCancelled,
+  overrideOccurrence,
 } from "../services/adjustmentService";
 import { getDb } from "../services/db";
 
@@ -18,8 +27,12 @@ export interface AdjustmentFormData {
   description: string;
   date: string;
   is_recurring: boolean;
+  recurrence_rule: string;
 }
 
+/** How far ahead the detail panel lists upcoming occurrences. */
+const UPCOMING_MONTHS = 12;
+
 export interface EntryFormData {
   id?: number;
   category_id: number;
@@ -31,6 +44,8 @@ interface AdjustmentsState {
   adjustments: Adjustment[];
   selectedAdjustmentId: number | null;
   entries: AdjustmentEntryWithCategory[];
+  upcomingOccurrences: AdjustmentOccurrence[];
+  exceptions: AdjustmentException[];
   categories: Category[];
   editingAdjustment: AdjustmentFormData | null;
   editingEntries: EntryFormData[];
@@ -48,6 +63,10 @@ type AdjustmentsAction =
   | { type: "SET_CATEGORIES"; payload: Category[] }
   | { type: "SELECT_ADJUSTMENT"; payload: number | null }
   | { type: "SET_ENTRIES"; payload: AdjustmentEntryWithCategory[] }
+  | {
+      type: "SET_OCCURRENCES";
+      payload: { occurrences: AdjustmentOccurrence[]; exceptions: AdjustmentException[] };
+    }
   | { type: "START_CREATING" }
   | { type: "START_EDITING"; payload: { adjustment: AdjustmentFormData; entries: EntryFormData[] } }
   | { type: "CANCEL_EDITING" }
@@ -57,6 +76,8 @@ const initialState: AdjustmentsState = {
   adjustments: [],
   selectedAdjustmentId: null,
   entries: [],
+  upcomingOccurrences: [],
+  exceptions: [],
   categories: [],
   editingAdjustment: null,
   editingEntries: [],
@@ -86,9 +107,17 @@ function reducer(state: AdjustmentsState, action: AdjustmentsAction): Adjustment
         editingEntries: [],
         isCreating: false,
         entries: [],
+        upcomingOccurrences: [],
+        exceptions: [],
       };
     case "SET_ENTRIES":
       return { ...state, entries: action.payload };
+    case "SET_OCCURRENCES":
+      return {
+        ...state,
+        upcomingOccurrences: action.payload.occurrences,
+        exceptions: action.payload.exceptions,
+      };
     case "START_CREATING":
       return {
         ...state,
@@ -100,6 +129,7 @@ function reducer(state: AdjustmentsState, action: AdjustmentsAction): Adjustment
           description: "",
           date: new Date().toISOString().slice(0, 10),
           is_recurring: false,
+          recurrence_rule: "",
         },
         editingEntries: [],
       };
@@ -155,18 +185,38 @@ export function useAdjustments() {
     loadCategories();
   }, [loadAdjustments, loadCategories]);
 
-  const selectAdjustment = useCallback(async (id: number | null) => {
-    dispatch({ type: "SELECT_ADJUSTMENT", payload: id });
-    if (id !== null) {
-      try {
-        const entries = await getEntriesByAdjustmentId(id);
-        dispatch({ type: "SET_ENTRIES", payload: entries });
-      } catch (e) {
-        dispatch({ type: "SET_ERROR", payload: e instanceof Error ? e.message : String(e) });
-      }
-    }
+  const loadOccurrences = useCallback(async (id: number) => {
+    const today = new Date();
+    const until = new Date(today.getFullYear(), today.getMonth() + UPCOMING_MONTHS, today.getDate());
+    const [occurrences, exceptions] = await Promise.all([
+      getAdjustmentOccurrences(toISODate(today), toISODate(until)),
+      getExceptionsByAdjustmentId(id),
+    ]);
+    dispatch({
+      type: "SET_OCCURRENCES",
+      payload: {
+        occurrences: occurrences.filter((o) => o.adjustment_id === id),
+        exceptions,
+      },
+    });
   }, []);
 
+  const selectAdjustment = useCallback(
+    async (id: number | null) => {
+      dispatch({ type: "SELECT_ADJUSTMENT", payload: id });
+      if (id !== null) {
+        try {
+          const entries = await getEntriesByAdjustmentId(id);
+          dispatch({ type: "SET_ENTRIES", payload: entries });
+          await loadOccurrences(id);
+        } catch (e) {
+          dispatch({ type: "SET_ERROR", payload: e instanceof Error ? e.message : String(e) });
+        }
+      }
+    },
+    [loadOccurrences]
+  );
+
   const startCreating = useCallback(() => {
     dispatch({ type: "START_CREATING" });
   }, []);
@@ -182,6 +232,7 @@ export function useAdjustments() {
           description: adj.description ?? "",
           date: adj.date,
           is_recurring: adj.is_recurring,
+          recurrence_rule: adj.recurrence_rule ?? "",
         },
         entries: state.entries.map((e) => ({
           id: e.id,
@@ -209,6 +260,7 @@ export function useAdjustments() {
             description: formData.description || undefined,
             date: formData.date,
             is_recurring: formData.is_recurring,
+            recurrence_rule: formData.recurrence_rule,
           });
           for (const entry of entries) {
             await createEntry({
@@ -226,6 +278,7 @@ export function useAdjustments() {
             description: formData.description || undefined,
             date: formData.date,
             is_recurring: formData.is_recurring,
+            recurrence_rule: formData.recurrence_rule,
           });
 
           // Determine which entries to create, update, or delete
@@ -283,6 +336,40 @@ export function useAdjustments() {
     [loadAdjustments]
   );
 
+  /** Skips one occurrence of the selected adjustment, or restores it. */
+  const setOccurrenceSkipped = useCallback(
+    async (occurrenceDate: string, skipped: boolean) => {
+      if (state.selectedAdjustmentId === null) return;
+      try {
+        await setOccurrenceCancelled(state.selectedAdjustmentId, occurrenceDate, skipped);
+        await loadOccurrences(state.selectedAdjustmentId);
+      } catch (e) {
+        dispatch({ type: "SET_ERROR", payload: e instanceof Error ? e.message : String(e) });
+      }
+    },
+    [state.selectedAdjustmentId, loadOccurrences]
+  );
+
+  /**
+   * Moves one occurrence of the selected adjustment and/or replaces its amounts; entries
+   * left undefined keep following the adjustment's own entries.
+   */
+  const modifyOccurrence = useCallback(
+    async (
+      occurrenceDate: string,
+      override: Parameters<typeof overrideOccurrence>[2]
+    ) => {
+      if (state.selectedAdjustmentId === null) return;
+      try {
+        await overrideOccurrence(state.selectedAdjustmentId, occurrenceDate, override);
+        await loadOccurrences(state.selectedAdjustmentId);
+      } catch (e) {
+        dispatch({ type: "SET_ERROR", payload: e instanceof Error ? e.message : String(e) });
+      }
+    },
+    [state.selectedAdjustmentId, loadOccurrences]
+  );
+
   return {
     state,
     selectAdjustment,
@@ -291,5 +378,13 @@ export function useAdjustments() {
     cancelEditing,
     saveAdjustment,
     deleteAdjustment: removeAdjustment,
+    setOccurrenceSkipped,
+    modifyOccurrence,
   };
 }
+
+function toISODate(date: Date): string {
+  const month = String(date.getMonth() + 1).padStart(2, "0");
+  const day = String(date.getDate()).padStart(2, "0");
+  return `${date.getFullYear()}-${month}-${day}`;
+}
@@ -452,6 +452,26 @@ export function useBudget() {
     [state.year, refreshData]
   );
 
+  const applyForecast = useCallback(
+    async (lines: Array<{ category_id: number; months: number[] }>) => {
+      dispatch({ type: "SET_SAVING", payload: true });
+      try {
+        for (const line of lines) {
+          await upsertBudgetEntriesForYear(line.category_id, state.year, line.months);
+        }
+        await refreshData(state.year);
+      } catch (e) {
+        dispatch({
+          type: "SET_ERROR",
+          payload: e instanceof Error ? e.message : String(e),
+        });
+      } finally {
+        dispatch({ type: "SET_SAVING", payload: false });
+      }
+    },
+    [state.year, refreshData]
+  );
+
   const deleteTemplate = useCallback(
     async (templateId: number) => {
       dispatch({ type: "SET_SAVING", payload: true });
@@ -479,5 +499,6 @@ export function useBudget() {
     applyTemplate,
     applyTemplateAllMonths,
     deleteTemplate,
+    applyForecast,
   };
 }
@@ -47,7 +47,11 @@ export function useDataExport() {
   const [state, dispatch] = useReducer(reducer, initialState);
 
   const performExport = useCallback(
-    async (mode: ExportMode, format: ExportFormat, password?: string) => {
+    async (
+      mode: ExportMode,
+      format: ExportFormat,
+      encryption: { password?: string; recipients?: string[] } = {}
+    ) => {
       dispatch({ type: "EXPORT_START" });
       try {
         const appVersion = await getVersion();
@@ -75,17 +79,21 @@ export function useDataExport() {
         }
 
         // Determine file extension and name
-        const isEncrypted = !!password && password.length > 0;
-        const ext = isEncrypted ? "sref" : defaultExt;
+        const password = encryption.password || null;
+        const recipients = encryption.recipients?.filter((r) => r.trim()) ?? [];
+        const ext = recipients.length > 0 ? "age" : password ? "sref" : defaultExt;
         const timestamp = new Date().toISOString().slice(0, 10);
         const defaultName = `simplresult_${mode}_${timestamp}.${ext}`;
 
         // Build filters
-        const filters: [string, string[]][] = isEncrypted
-          ? [["Simpl'Result Encrypted", ["sref"]]]
-          : format === "csv"
-            ? [["CSV Files", ["csv"]]]
-            : [["JSON Files", ["json"]]];
+        const filters: [string, string[]][] =
+          recipients.length > 0
+            ? [["Simpl'Result Encrypted (age)", ["age"]]]
+            : password
+              ? [["Simpl'Result Encrypted", ["sref"]]]
+              : format === "csv"
+                ? [["CSV Files", ["csv"]]]
+                : [["JSON Files", ["json"]]];
 
         // Pick save location
         const filePath = await invoke<string | null>("pick_save_file", {
@@ -102,7 +110,8 @@ export function useDataExport() {
         await invoke("write_export_file", {
           filePath,
           content,
-          password: isEncrypted ? password : null,
+          password: recipients.length > 0 ? null : password,
+          recipients: recipients.length > 0 ? recipients : null,
         });
 
         dispatch({ type: "EXPORT_SUCCESS" });
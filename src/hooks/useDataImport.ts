@@ -14,6 +14,7 @@ type ImportStatus =
   | "idle"
   | "reading"
   | "needsPassword"
+  | "needsIdentity"
   | "confirming"
   | "importing"
   | "success"
@@ -31,6 +32,7 @@ interface ImportState {
 type ImportAction =
   | { type: "READ_START" }
   | { type: "NEEDS_PASSWORD"; filePath: string }
+  | { type: "NEEDS_IDENTITY"; filePath: string; error: string }
   | {
       type: "CONFIRMING";
       filePath: string;
@@ -58,6 +60,13 @@ function reducer(state: ImportState, action: ImportAction): ImportState {
       return { ...initialState, status: "reading" };
     case "NEEDS_PASSWORD":
       return { ...initialState, status: "needsPassword", filePath: action.filePath };
+    case "NEEDS_IDENTITY":
+      return {
+        ...initialState,
+        status: "needsIdentity",
+        filePath: action.filePath,
+        error: action.error,
+      };
     case "CONFIRMING":
       return {
         ...state,
@@ -87,6 +96,7 @@ function parseContent(
     filePath.toLowerCase().endsWith(".csv") ||
     (!filePath.toLowerCase().endsWith(".json") &&
       !filePath.toLowerCase().endsWith(".sref") &&
+      !filePath.toLowerCase().endsWith(".age") &&
       content.trimStart().charAt(0) !== "{");
 
   if (isCsv) {
@@ -113,7 +123,7 @@ export function useDataImport() {
     dispatch({ type: "READ_START" });
     try {
       const filePath = await invoke<string | null>("pick_import_file", {
-        filters: [["Simpl'Result Files", ["json", "csv", "sref"]]],
+        filters: [["Simpl'Result Files", ["json", "csv", "sref", "age"]]],
       });
 
       if (!filePath) {
@@ -121,17 +131,30 @@ export function useDataImport() {
         return;
       }
 
-      const encrypted = await invoke<boolean>("is_file_encrypted", { filePath });
+      const format = await invoke<string>("get_export_file_format", { filePath });
 
-      if (encrypted) {
+      if (format === "password") {
         dispatch({ type: "NEEDS_PASSWORD", filePath });
         return;
       }
 
-      const content = await invoke<string>("read_import_file", {
-        filePath,
-        password: null,
-      });
+      let content: string;
+      try {
+        // Files encrypted to our public key open with the local identity
+        content = await invoke<string>("read_import_file", {
+          filePath,
+          password: null,
+          identityPath: null,
+        });
+      } catch (e) {
+        if (format !== "recipients") throw e;
+        dispatch({
+          type: "NEEDS_IDENTITY",
+          filePath,
+          error: e instanceof Error ? e.message : String(e),
+        });
+        return;
+      }
 
       const { summary, data, importType } = parseContent(content, filePath);
       dispatch({ type: "CONFIRMING", filePath, summary, data, importType });
@@ -165,6 +188,33 @@ export function useDataImport() {
     [state.filePath]
   );
 
+  const readWithIdentity = useCallback(async () => {
+    if (!state.filePath) return;
+    const filePath = state.filePath;
+    try {
+      const identityPath = await invoke<string | null>("pick_import_file", {
+        filters: [["age identity", ["txt", "key", "age"]]],
+      });
+      if (!identityPath) return;
+
+      dispatch({ type: "READ_START" });
+      const content = await invoke<string>("read_import_file", {
+        filePath,
+        password: null,
+        identityPath,
+      });
+
+      const { summary, data, importType } = parseContent(content, filePath);
+      dispatch({ type: "CONFIRMING", filePath, summary, data, importType });
+    } catch (e) {
+      dispatch({
+        type: "NEEDS_IDENTITY",
+        filePath,
+        error: e instanceof Error ? e.message : String(e),
+      });
+    }
+  }, [state.filePath]);
+
   const executeImport = useCallback(async () => {
     if (!state.parsedData || !state.importType) return;
     dispatch({ type: "IMPORT_START" });
@@ -192,5 +242,5 @@ export function useDataImport() {
 
   const reset = useCallback(() => dispatch({ type: "RESET" }), []);
 
-  return { state, pickAndRead, readWithPassword, executeImport, reset };
+  return { state, pickAndRead, readWithPassword, readWithIdentity, executeImport, reset };
 }
@@ -40,13 +40,19 @@ import {
   updateTemplate,
   deleteTemplate as deleteTemplateService,
 } from "../services/importConfigTemplateService";
-import { parseDate } from "../utils/dateParser";
-import { parseFrenchAmount } from "../utils/amountParser";
+import { parseImportDates } from "../services/dateService";
+import { parseImportAmounts } from "../services/amountService";
 import {
   preprocessQuotedCSV,
   autoDetectConfig as runAutoDetect,
 } from "../utils/csvAutoDetect";
 
+/** Files whose dates all read as valid dates with day and month swapped too. */
+export interface DateAmbiguity {
+  filenames: string[];
+  alternativeFormat: string;
+}
+
 interface WizardState {
   step: ImportWizardStep;
   importFolder: string | null;
@@ -57,6 +63,7 @@ interface WizardState {
   existingSource: ImportSource | null;
   parsedPreview: ParsedRow[];
   previewHeaders: string[];
+  dateAmbiguity: DateAmbiguity | null;
   duplicateResult: DuplicateCheckResult | null;
   excludedDuplicateIndices: Set<number>;
   importReport: ImportReport | null;
@@ -79,7 +86,10 @@ type WizardAction =
   | { type: "SET_SELECTED_FILES"; payload: ScannedFile[] }
   | { type: "SET_SOURCE_CONFIG"; payload: SourceConfig }
   | { type: "SET_EXISTING_SOURCE"; payload: ImportSource | null }
-  | { type: "SET_PARSED_PREVIEW"; payload: { rows: ParsedRow[]; headers: string[] } }
+  | {
+      type: "SET_PARSED_PREVIEW";
+      payload: { rows: ParsedRow[]; headers: string[]; dateAmbiguity?: DateAmbiguity | null };
+    }
   | { type: "SET_DUPLICATE_RESULT"; payload: DuplicateCheckResult }
   | { type: "TOGGLE_DUPLICATE_ROW"; payload: number }
   | { type: "SET_SKIP_ALL_DUPLICATES"; payload: boolean }
@@ -112,6 +122,7 @@ const initialState: WizardState = {
   existingSource: null,
   parsedPreview: [],
   previewHeaders: [],
+  dateAmbiguity: null,
   duplicateResult: null,
   excludedDuplicateIndices: new Set(),
   importReport: null,
@@ -149,6 +160,7 @@ function reducer(state: WizardState, action: WizardAction): WizardState {
         ...state,
         parsedPreview: action.payload.rows,
         previewHeaders: action.payload.headers,
+        dateAmbiguity: action.payload.dateAmbiguity ?? null,
         isLoading: false,
       };
     case "SET_DUPLICATE_RESULT":
@@ -458,10 +470,15 @@ export function useImportWizard() {
   }, [state.selectedSource, state.importedFilesBySource]);
 
   // Internal helper: parses selected files and returns rows + headers
-  const parseFilesInternal = useCallback(async (): Promise<{ rows: ParsedRow[]; headers: string[] }> => {
+  const parseFilesInternal = useCallback(async (): Promise<{
+    rows: ParsedRow[];
+    headers: string[];
+    dateAmbiguity: DateAmbiguity | null;
+  }> => {
     const config = state.sourceConfig;
     const allRows: ParsedRow[] = [];
     let headers: string[] = [];
+    let dateAmbiguity: DateAmbiguity | null = null;
 
     for (const file of state.selectedFiles) {
       const content = await invoke<string>("read_file_content", {
@@ -486,35 +503,44 @@ export function useImportWizard() {
         headers = firstDataRow.map((_, i) => `Col ${i}`);
       }
 
-      for (let i = startIdx; i < data.length; i++) {
-        const raw = data[i];
-        if (raw.length <= 1 && raw[0]?.trim() === "") continue;
+      const dataRows = data
+        .slice(startIdx)
+        .filter((raw) => !(raw.length <= 1 && raw[0]?.trim() === ""));
+
+      // Dates are parsed in Rust, which rejects impossible dates and flags day/month mix-ups
+      const { dates, ambiguous_with } = await parseImportDates(
+        dataRows.map((raw) => raw[config.columnMapping.date]?.trim() || ""),
+        config.dateFormat
+      );
+      if (ambiguous_with) {
+        dateAmbiguity = {
+          filenames: [...(dateAmbiguity?.filenames ?? []), file.filename],
+          alternativeFormat: ambiguous_with,
+        };
+      }
+
+      // Amounts too, with an exact error per failing cell
+      const cell = (raw: string[], column: number | undefined) => raw[column ?? 0] || "";
+      const amounts = await parseImportAmounts(
+        dataRows.map((raw) =>
+          config.amountMode === "debit_credit"
+            ? [
+                cell(raw, config.columnMapping.debitAmount),
+                cell(raw, config.columnMapping.creditAmount),
+              ]
+            : [cell(raw, config.columnMapping.amount)]
+        ),
+        config.amountMode,
+        config.signConvention
+      );
 
+      for (const [i, raw] of dataRows.entries()) {
         try {
-          const date = parseDate(
-            raw[config.columnMapping.date]?.trim() || "",
-            config.dateFormat
-          );
+          const date = dates[i];
           const description =
             raw[config.columnMapping.description]?.trim() || "";
 
-          let amount: number;
-          if (config.amountMode === "debit_credit") {
-            const debit = parseFrenchAmount(
-              raw[config.columnMapping.debitAmount ?? 0] || ""
-            );
-            const credit = parseFrenchAmount(
-              raw[config.columnMapping.creditAmount ?? 0] || ""
-            );
-            amount = isNaN(credit) ? -(isNaN(debit) ? 0 : debit) : credit;
-          } else {
-            amount = parseFrenchAmount(
-              raw[config.columnMapping.amount ?? 0] || ""
-            );
-            if (config.signConvention === "positive_expense" && !isNaN(amount)) {
-              amount = -amount;
-            }
-          }
+          const { amount, error: amountError } = amounts[i];
 
           if (!date) {
             allRows.push({
@@ -524,12 +550,12 @@ export function useImportWizard() {
               error: "Invalid date",
               sourceFilename: file.filename,
             });
-          } else if (isNaN(amount)) {
+          } else if (amount === null) {
             allRows.push({
               rowIndex: allRows.length,
               raw,
               parsed: null,
-              error: "Invalid amount",
+              error: amountError ?? "Invalid amount",
               sourceFilename: file.filename,
             });
           } else {
@@ -552,7 +578,7 @@ export function useImportWizard() {
       }
     }
 
-    return { rows: allRows, headers };
+    return { rows: allRows, headers, dateAmbiguity };
   }, [state.selectedFiles, state.sourceConfig]);
 
   // Parse files and store preview (does NOT change wizard step)
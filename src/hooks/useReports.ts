@@ -60,7 +60,7 @@ const initialState: ReportsState = {
   budgetMonth: now.getMonth() + 1,
   budgetVsActual: [],
   pivotConfig: { rows: [], columns: [], filters: {}, values: [] },
-  pivotResult: { rows: [], columnValues: [], dimensionLabels: {} },
+  pivotResult: { rows: [], columnValues: [], dimensionLabels: {}, subtotals: [], grandTotals: [] },
   isLoading: false,
   error: null,
 };
@@ -188,7 +188,7 @@ export function useReports() {
         }
         case "dynamic": {
           if (!pivotCfg || (pivotCfg.rows.length === 0 && pivotCfg.columns.length === 0) || pivotCfg.values.length === 0) {
-            dispatch({ type: "SET_PIVOT_RESULT", payload: { rows: [], columnValues: [], dimensionLabels: {} } });
+            dispatch({ type: "SET_PIVOT_RESULT", payload: { rows: [], columnValues: [], dimensionLabels: {}, subtotals: [], grandTotals: [] } });
             break;
           }
           const data = await getDynamicReportData(pivotCfg);
@@ -1,6 +1,7 @@
 import { useReducer, useCallback, useRef } from "react";
 import { check, type Update } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
+import { snapshotAllProfiles } from "../services/backupService";
 
 type UpdateStatus =
   | "idle"
@@ -86,6 +87,14 @@ export function useUpdater() {
 
     dispatch({ type: "DOWNLOAD_START" });
     try {
+      // Keep a copy of every profile to go back to if the new version misbehaves
+      const snapshots = await snapshotAllProfiles("pre-update");
+      for (const result of snapshots) {
+        if (result.error) {
+          console.warn(`Pre-update snapshot of "${result.profile_name}" skipped:`, result.error);
+        }
+      }
+
       let downloaded = 0;
       await update.downloadAndInstall((event) => {
         if (event.event === "Started") {
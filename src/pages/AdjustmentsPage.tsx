@@ -26,6 +26,8 @@ export default function AdjustmentsPage() {
     cancelEditing,
     saveAdjustment,
     deleteAdjustment,
+    setOccurrenceSkipped,
+    modifyOccurrence,
   } = useAdjustments();
 
   const [entriesMap, setEntriesMap] = useState<Map<number, AdjustmentEntryWithCategory[]>>(
@@ -166,6 +168,8 @@ export default function AdjustmentsPage() {
           <AdjustmentDetailPanel
             selectedAdjustment={selectedAdjustment}
             entries={state.entries}
+            upcomingOccurrences={state.upcomingOccurrences}
+            exceptions={state.exceptions}
             categories={state.categories}
             editingAdjustment={state.editingAdjustment}
             editingEntries={state.editingEntries}
@@ -175,6 +179,8 @@ export default function AdjustmentsPage() {
             onCancelEditing={cancelEditing}
             onSave={saveAdjustment}
             onDelete={deleteAdjustment}
+            onSetOccurrenceSkipped={setOccurrenceSkipped}
+            onModifyOccurrence={modifyOccurrence}
           />
         </div>
       )}
@@ -1,9 +1,13 @@
+import { useState } from "react";
 import { useTranslation } from "react-i18next";
+import { Wand2 } from "lucide-react";
 import { PageHelp } from "../components/shared/PageHelp";
 import { useBudget } from "../hooks/useBudget";
 import YearNavigator from "../components/budget/YearNavigator";
 import BudgetTable from "../components/budget/BudgetTable";
 import TemplateActions from "../components/budget/TemplateActions";
+import EnvelopesPanel from "../components/budget/EnvelopesPanel";
+import ForecastModal from "../components/budget/ForecastModal";
 
 export default function BudgetPage() {
   const { t } = useTranslation();
@@ -16,7 +20,9 @@ export default function BudgetPage() {
     applyTemplate,
     applyTemplateAllMonths,
     deleteTemplate,
+    applyForecast,
   } = useBudget();
+  const [showForecast, setShowForecast] = useState(false);
 
   const { year, rows, templates, isLoading, isSaving, error } = state;
 
@@ -28,6 +34,14 @@ export default function BudgetPage() {
           <PageHelp helpKey="budget" />
         </div>
         <div className="flex flex-col sm:flex-row items-start sm:items-center gap-3">
+          <button
+            onClick={() => setShowForecast(true)}
+            disabled={isSaving}
+            className="flex items-center gap-1.5 px-3 py-1.5 text-sm rounded-lg border border-[var(--border)] hover:bg-[var(--muted)] transition-colors disabled:opacity-50"
+          >
+            <Wand2 size={16} />
+            {t("budget.forecast.open")}
+          </button>
           <TemplateActions
             templates={templates}
             onApply={applyTemplate}
@@ -51,6 +65,16 @@ export default function BudgetPage() {
         onUpdatePlanned={updatePlanned}
         onSplitEvenly={splitEvenly}
       />
+
+      <EnvelopesPanel year={year} budgetRows={rows} />
+
+      {showForecast && (
+        <ForecastModal
+          year={year}
+          onApply={applyForecast}
+          onClose={() => setShowForecast(false)}
+        />
+      )}
     </div>
   );
 }
@@ -6,6 +6,7 @@ import { PageHelp } from "../components/shared/PageHelp";
 import PeriodSelector from "../components/dashboard/PeriodSelector";
 import CategoryPieChart from "../components/dashboard/CategoryPieChart";
 import RecentTransactionsList from "../components/dashboard/RecentTransactionsList";
+import AccountBalancesCard from "../components/dashboard/AccountBalancesCard";
 import TransactionDetailModal from "../components/shared/TransactionDetailModal";
 import type { CategoryBreakdownItem, DashboardPeriod } from "../shared/types";
 
@@ -125,6 +126,8 @@ export default function DashboardPage() {
         ))}
       </div>
 
+      <AccountBalancesCard />
+
       <div className="grid grid-cols-1 lg:grid-cols-2 gap-4">
         <CategoryPieChart
           data={categoryBreakdown}
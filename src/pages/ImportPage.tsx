@@ -64,6 +64,21 @@ export default function ImportPage() {
         </div>
       )}
 
+      {/* Day/month ambiguity warning */}
+      {state.dateAmbiguity &&
+        ["source-config", "duplicate-check", "confirm"].includes(state.step) && (
+          <div className="mb-4 p-3 rounded-xl bg-[var(--card)] border-2 border-[var(--accent)] flex items-center gap-2">
+            <AlertCircle size={16} className="text-[var(--accent)] shrink-0" />
+            <p className="text-sm text-[var(--foreground)]">
+              {t("import.dateAmbiguous", {
+                files: state.dateAmbiguity.filenames.join(", "),
+                format: state.sourceConfig.dateFormat,
+                alternative: state.dateAmbiguity.alternativeFormat,
+              })}
+            </p>
+          </div>
+        )}
+
       {/* Folder config - always visible */}
       <ImportFolderConfig
         folderPath={state.importFolder}
@@ -4,14 +4,26 @@ import { Lock, Plus } from "lucide-react";
 import { useProfile } from "../contexts/ProfileContext";
 import { APP_NAME } from "../shared/constants";
 import PinDialog from "../components/profile/PinDialog";
+import DbPasswordDialog from "../components/profile/DbPasswordDialog";
 import ProfileFormModal from "../components/profile/ProfileFormModal";
 
 export default function ProfileSelectionPage() {
   const { t } = useTranslation();
-  const { profiles, switchProfile } = useProfile();
+  const { profiles, error, switchProfile, rebuildProfiles } = useProfile();
   const [pinProfileId, setPinProfileId] = useState<string | null>(null);
+  const [passwordProfileId, setPasswordProfileId] = useState<string | null>(null);
   const [showCreate, setShowCreate] = useState(false);
 
+  // Encrypted databases need their password before the switch
+  const openProfile = (profileId: string) => {
+    const profile = profiles.find((p) => p.id === profileId);
+    if (profile?.db_key_salt) {
+      setPasswordProfileId(profileId);
+    } else {
+      switchProfile(profileId);
+    }
+  };
+
   const handleSelect = (profileId: string) => {
     const profile = profiles.find((p) => p.id === profileId);
     if (!profile) return;
@@ -19,24 +31,43 @@ export default function ProfileSelectionPage() {
     if (profile.pin_hash) {
       setPinProfileId(profileId);
     } else {
-      switchProfile(profileId);
+      openProfile(profileId);
     }
   };
 
   const handlePinSuccess = () => {
     if (pinProfileId) {
-      switchProfile(pinProfileId);
+      openProfile(pinProfileId);
       setPinProfileId(null);
     }
   };
 
+  const handlePassword = async (password: string) => {
+    if (!passwordProfileId) return;
+    await switchProfile(passwordProfileId, password);
+    setPasswordProfileId(null);
+  };
+
   const pinProfile = profiles.find((p) => p.id === pinProfileId);
+  const passwordProfile = profiles.find((p) => p.id === passwordProfileId);
 
   return (
     <div className="flex flex-col items-center justify-center min-h-screen bg-[var(--background)] p-8">
       <h1 className="text-3xl font-bold text-[var(--foreground)] mb-2">{APP_NAME}</h1>
       <p className="text-[var(--muted-foreground)] mb-10">{t("profile.select")}</p>
 
+      {error && profiles.length === 0 && (
+        <div className="max-w-lg w-full mb-6 p-4 rounded-xl border border-[var(--negative)] bg-[var(--card)] text-sm">
+          <p className="text-[var(--negative)] mb-3">{error}</p>
+          <button
+            onClick={rebuildProfiles}
+            className="px-3 py-1.5 rounded-lg bg-[var(--primary)] text-white hover:opacity-90"
+          >
+            {t("profile.rebuildProfiles")}
+          </button>
+        </div>
+      )}
+
       <div className="grid grid-cols-2 sm:grid-cols-3 gap-4 max-w-lg w-full">
         {profiles.map((profile) => (
           <button
@@ -73,12 +104,20 @@ export default function ProfileSelectionPage() {
       {pinProfileId && pinProfile && (
         <PinDialog
           profileName={pinProfile.name}
-          storedHash={pinProfile.pin_hash!}
+          profileId={pinProfile.id}
           onSuccess={handlePinSuccess}
           onCancel={() => setPinProfileId(null)}
         />
       )}
 
+      {passwordProfile && (
+        <DbPasswordDialog
+          profileName={passwordProfile.name}
+          onSubmit={handlePassword}
+          onCancel={() => setPasswordProfileId(null)}
+        />
+      )}
+
       {showCreate && (
         <ProfileFormModal onClose={() => setShowCreate(false)} />
       )}
@@ -18,6 +18,10 @@ import { Link } from "react-router-dom";
 import { APP_NAME } from "../shared/constants";
 import { PageHelp } from "../components/shared/PageHelp";
 import DataManagementCard from "../components/settings/DataManagementCard";
+import BackupScheduleCard from "../components/settings/BackupScheduleCard";
+import CurrencySettingsCard from "../components/settings/CurrencySettingsCard";
+import DateAuditCard from "../components/settings/DateAuditCard";
+import SchemaCheckCard from "../components/settings/SchemaCheckCard";
 
 export default function SettingsPage() {
   const { t } = useTranslation();
@@ -224,6 +228,18 @@ export default function SettingsPage() {
       {/* Data management */}
       <DataManagementCard />
 
+      {/* Automatic backups */}
+      <BackupScheduleCard />
+
+      {/* Currencies */}
+      <CurrencySettingsCard />
+
+      {/* Dates */}
+      <DateAuditCard />
+
+      {/* Database schema */}
+      <SchemaCheckCard />
+
       {/* Data safety notice */}
       <div className="flex items-start gap-2 text-sm text-[var(--muted-foreground)]">
         <ShieldCheck size={16} className="mt-0.5 shrink-0" />
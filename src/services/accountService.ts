@@ -0,0 +1,58 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  AccountBalance,
+  RunningBalanceRow,
+  StatementCheck,
+  StatementOrigin,
+} from "../shared/types";
+
+/** Balance of every account (import source) at the end of asOf; defaults to today. */
+export async function getAccountBalances(asOf?: string): Promise<AccountBalance[]> {
+  return invoke<AccountBalance[]>("get_account_balances", { asOf: asOf ?? null });
+}
+
+export async function getAccountBalanceAt(sourceId: number, date: string): Promise<number> {
+  return invoke<number>("get_account_balance_at", { sourceId, date });
+}
+
+/** Transactions of an account with the balance after each one. */
+export async function getRunningBalances(
+  sourceId: number,
+  dateFrom?: string,
+  dateTo?: string
+): Promise<RunningBalanceRow[]> {
+  return invoke<RunningBalanceRow[]>("get_running_balances", {
+    sourceId,
+    dateFrom: dateFrom ?? null,
+    dateTo: dateTo ?? null,
+  });
+}
+
+/**
+ * Stores a statement's closing balance (OFX LEDGERBAL, camt CLBD, or typed in) and
+ * returns how it compares with the computed balance.
+ */
+export async function recordStatementBalance(
+  sourceId: number,
+  balanceDate: string,
+  balance: number,
+  origin: StatementOrigin,
+  options: { currency?: string; fileId?: number } = {}
+): Promise<StatementCheck> {
+  return invoke<StatementCheck>("record_statement_balance", {
+    sourceId,
+    balanceDate,
+    balance,
+    origin,
+    currency: options.currency ?? null,
+    fileId: options.fileId ?? null,
+  });
+}
+
+export async function getStatementChecks(sourceId: number): Promise<StatementCheck[]> {
+  return invoke<StatementCheck[]>("get_statement_checks", { sourceId });
+}
+
+export async function deleteStatementBalance(id: number): Promise<void> {
+  return invoke("delete_statement_balance", { id });
+}
@@ -1,5 +1,11 @@
-import { getDb } from "./db";
-import type { Adjustment, AdjustmentEntry } from "../shared/types";
+import { invoke } from "@tauri-apps/api/core";
+import { getDb, toCents, withDecimalAmounts } from "./db";
+import type {
+  Adjustment,
+  AdjustmentEntry,
+  AdjustmentException,
+  AdjustmentOccurrence,
+} from "../shared/types";
 
 export type AdjustmentEntryWithCategory = AdjustmentEntry & {
   category_name: string;
@@ -29,25 +35,45 @@ export async function createAdjustment(data: {
   description?: string;
   date: string;
   is_recurring: boolean;
+  recurrence_rule?: string;
 }): Promise<number> {
   const db = await getDb();
   const result = await db.execute(
-    `INSERT INTO adjustments (name, description, date, is_recurring)
-     VALUES ($1, $2, $3, $4)`,
-    [data.name, data.description || null, data.date, data.is_recurring ? 1 : 0]
+    `INSERT INTO adjustments (name, description, date, is_recurring, recurrence_rule)
+     VALUES ($1, $2, $3, $4, $5)`,
+    [
+      data.name,
+      data.description || null,
+      data.date,
+      data.is_recurring ? 1 : 0,
+      data.is_recurring ? data.recurrence_rule || null : null,
+    ]
   );
   return result.lastInsertId as number;
 }
 
 export async function updateAdjustment(
   id: number,
-  data: { name: string; description?: string; date: string; is_recurring: boolean }
+  data: {
+    name: string;
+    description?: string;
+    date: string;
+    is_recurring: boolean;
+    recurrence_rule?: string;
+  }
 ): Promise<void> {
   const db = await getDb();
   await db.execute(
-    `UPDATE adjustments SET name = $1, description = $2, date = $3, is_recurring = $4, updated_at = CURRENT_TIMESTAMP
-     WHERE id = $5`,
-    [data.name, data.description || null, data.date, data.is_recurring ? 1 : 0, id]
+    `UPDATE adjustments SET name = $1, description = $2, date = $3, is_recurring = $4, recurrence_rule = $5, updated_at = CURRENT_TIMESTAMP
+     WHERE id = $6`,
+    [
+      data.name,
+      data.description || null,
+      data.date,
+      data.is_recurring ? 1 : 0,
+      data.is_recurring ? data.recurrence_rule || null : null,
+      id,
+    ]
   );
 }
 
@@ -60,7 +86,7 @@ export async function getEntriesByAdjustmentId(
   adjustmentId: number
 ): Promise<AdjustmentEntryWithCategory[]> {
   const db = await getDb();
-  return db.select<AdjustmentEntryWithCategory[]>(
+  const rows = await db.select<AdjustmentEntryWithCategory[]>(
     `SELECT ae.*, c.name AS category_name, COALESCE(c.color, '#9ca3af') AS category_color
      FROM adjustment_entries ae
      JOIN categories c ON c.id = ae.category_id
@@ -68,6 +94,7 @@ export async function getEntriesByAdjustmentId(
      ORDER BY ae.id`,
     [adjustmentId]
   );
+  return withDecimalAmounts(rows);
 }
 
 export async function createEntry(entry: {
@@ -80,7 +107,7 @@ export async function createEntry(entry: {
   const result = await db.execute(
     `INSERT INTO adjustment_entries (adjustment_id, category_id, amount, description)
      VALUES ($1, $2, $3, $4)`,
-    [entry.adjustment_id, entry.category_id, entry.amount, entry.description || null]
+    [entry.adjustment_id, entry.category_id, toCents(entry.amount), entry.description || null]
   );
   return result.lastInsertId as number;
 }
@@ -92,7 +119,7 @@ export async function updateEntry(
   const db = await getDb();
   await db.execute(
     `UPDATE adjustment_entries SET category_id = $1, amount = $2, description = $3 WHERE id = $4`,
-    [data.category_id, data.amount, data.description || null, id]
+    [data.category_id, toCents(data.amount), data.description || null, id]
   );
 }
 
@@ -100,3 +127,117 @@ export async function deleteEntry(id: number): Promise<void> {
   const db = await getDb();
   await db.execute("DELETE FROM adjustment_entries WHERE id = $1", [id]);
 }
+
+// --- Recurrence ---
+
+/** Validates an RRULE and returns its first occurrences from startDate (YYYY-MM-DD). */
+export async function previewRecurrenceRule(
+  rule: string,
+  startDate: string,
+  count?: number
+): Promise<string[]> {
+  return invoke<string[]>("preview_recurrence_rule", {
+    rule,
+    startDate,
+    count: count ?? null,
+  });
+}
+
+/** Occurrences of all adjustments in a date range; dateTo defaults to today. */
+export async function getAdjustmentOccurrences(
+  dateFrom?: string,
+  dateTo?: string
+): Promise<AdjustmentOccurrence[]> {
+  return invoke<AdjustmentOccurrence[]>("get_adjustment_occurrences", {
+    dateFrom: dateFrom ?? null,
+    dateTo: dateTo ?? null,
+  });
+}
+
+/** Sum of adjustment occurrences per category in a date range, to add to actuals. */
+export async function getAdjustmentTotalsByCategory(
+  dateFrom?: string,
+  dateTo?: string
+): Promise<Map<number, number>> {
+  const totals = new Map<number, number>();
+  for (const occurrence of await getAdjustmentOccurrences(dateFrom, dateTo)) {
+    for (const entry of occurrence.entries) {
+      totals.set(entry.category_id, (totals.get(entry.category_id) ?? 0) + entry.amount);
+    }
+  }
+  return totals;
+}
+
+export async function getExceptionsByAdjustmentId(
+  adjustmentId: number
+): Promise<AdjustmentException[]> {
+  const db = await getDb();
+  return db.select<AdjustmentException[]>(
+    "SELECT * FROM adjustment_exceptions WHERE adjustment_id = $1 ORDER BY occurrence_date",
+    [adjustmentId]
+  );
+}
+
+/** Skips a single occurrence, or restores it (skipped or modified) when cancelled is false. */
+export async function setOccurrenceCancelled(
+  adjustmentId: number,
+  occurrenceDate: string,
+  cancelled: boolean
+): Promise<void> {
+  const db = await getDb();
+  if (cancelled) {
+    await db.execute(
+      `INSERT INTO adjustment_exceptions (adjustment_id, occurrence_date, is_cancelled)
+       VALUES ($1, $2, 1)
+       ON CONFLICT(adjustment_id, occurrence_date) DO UPDATE SET is_cancelled = 1`,
+      [adjustmentId, occurrenceDate]
+    );
+  } else {
+    // Also drops the amounts of a modified occurrence, which then follows the adjustment again
+    await db.execute(
+      `DELETE FROM adjustment_exception_entries WHERE exception_id IN
+       (SELECT id FROM adjustment_exceptions WHERE adjustment_id = $1 AND occurrence_date = $2)`,
+      [adjustmentId, occurrenceDate]
+    );
+    await db.execute(
+      "DELETE FROM adjustment_exceptions WHERE adjustment_id = $1 AND occurrence_date = $2",
+      [adjustmentId, occurrenceDate]
+    );
+  }
+}
+
+/**
+ * Moves a single occurrence to another date and/or replaces its entries. Entries left
+ * undefined keep the adjustment's own entries.
+ */
+export async function overrideOccurrence(
+  adjustmentId: number,
+  occurrenceDate: string,
+  override: {
+    date?: string;
+    entries?: Array<{ category_id: number; amount: number; description?: string }>;
+  }
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    `INSERT INTO adjustment_exceptions (adjustment_id, occurrence_date, is_cancelled, override_date)
+     VALUES ($1, $2, 0, $3)
+     ON CONFLICT(adjustment_id, occurrence_date) DO UPDATE SET is_cancelled = 0, override_date = $3`,
+    [adjustmentId, occurrenceDate, override.date || null]
+  );
+  const rows = await db.select<Array<{ id: number }>>(
+    "SELECT id FROM adjustment_exceptions WHERE adjustment_id = $1 AND occurrence_date = $2",
+    [adjustmentId, occurrenceDate]
+  );
+  const exceptionId = rows[0].id;
+  await db.execute("DELETE FROM adjustment_exception_entries WHERE exception_id = $1", [
+    exceptionId,
+  ]);
+  for (const entry of override.entries ?? []) {
+    await db.execute(
+      `INSERT INTO adjustment_exception_entries (exception_id, category_id, amount, description)
+       VALUES ($1, $2, $3, $4)`,
+      [exceptionId, entry.category_id, toCents(entry.amount), entry.description || null]
+    );
+  }
+}
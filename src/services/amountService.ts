@@ -0,0 +1,15 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AmountMode, ImportAmount, SignConvention } from "../shared/types";
+
+/**
+ * Reads the amount cells of imported rows: `[amount]` per row in single mode,
+ * `[debit, credit]` in debit/credit mode. Handles `1 234,56`, `-1.234,56 $`,
+ * `(12.00)` and `12,00-`; a cell that cannot be read gets an error naming it.
+ */
+export async function parseImportAmounts(
+  rows: string[][],
+  amountMode: AmountMode,
+  signConvention: SignConvention
+): Promise<ImportAmount[]> {
+  return invoke<ImportAmount[]>("parse_import_amounts", { rows, amountMode, signConvention });
+}
@@ -0,0 +1,144 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Profile } from "./profileService";
+
+export interface BackupManifest {
+  format_version: number;
+  app_version: string;
+  created_at: string;
+  profile_name: string;
+  db_filename: string;
+  schema_version: number;
+  db_size: number;
+  db_sha256: string;
+  table_counts: Record<string, number>;
+}
+
+export interface RestoreResult {
+  profile: Profile;
+  manifest: BackupManifest;
+  created_profile: boolean;
+}
+
+export async function backupProfile(
+  profileId: string,
+  filePath: string,
+  password?: string | null,
+  recipients?: string[] | null
+): Promise<BackupManifest> {
+  return invoke<BackupManifest>("backup_profile", {
+    profileId,
+    filePath,
+    password: password ?? null,
+    recipients: recipients ?? null,
+  });
+}
+
+export async function readBackupManifest(
+  filePath: string,
+  password?: string | null,
+  identityPath?: string | null
+): Promise<BackupManifest> {
+  return invoke<BackupManifest>("read_backup_manifest", {
+    filePath,
+    password: password ?? null,
+    identityPath: identityPath ?? null,
+  });
+}
+
+export async function restoreProfile(
+  filePath: string,
+  options: {
+    password?: string | null;
+    identityPath?: string | null;
+    targetProfileId?: string | null;
+    newProfileName?: string | null;
+  } = {}
+): Promise<RestoreResult> {
+  return invoke<RestoreResult>("restore_profile", {
+    filePath,
+    password: options.password ?? null,
+    identityPath: options.identityPath ?? null,
+    targetProfileId: options.targetProfileId ?? null,
+    newProfileName: options.newProfileName ?? null,
+  });
+}
+
+export type BackupFrequency = "on_start" | "on_exit" | "daily" | "weekly";
+export type BackupTrigger = "startup" | "interval" | "exit" | "manual";
+
+export interface BackupSettings {
+  enabled: boolean;
+  folder: string | null;
+  frequency: BackupFrequency;
+  keep_daily: number;
+  keep_weekly: number;
+  keep_monthly: number;
+  /** Public keys (age1...) the backups are also encrypted to, besides this computer's key. */
+  recipients: string[];
+}
+
+export interface BackupHistoryEntry {
+  created_at: string;
+  trigger: BackupTrigger;
+  success: boolean;
+  file_path: string | null;
+  size_bytes: number | null;
+  deleted_by_retention: string[];
+  error: string | null;
+}
+
+export async function getBackupSettings(profileId: string): Promise<BackupSettings> {
+  return invoke<BackupSettings>("get_backup_settings", { profileId });
+}
+
+export async function saveBackupSettings(
+  profileId: string,
+  settings: BackupSettings
+): Promise<void> {
+  return invoke("save_backup_settings", { profileId, settings });
+}
+
+export async function getBackupHistory(profileId: string): Promise<BackupHistoryEntry[]> {
+  return invoke<BackupHistoryEntry[]>("get_backup_history", { profileId });
+}
+
+export async function runBackupNow(profileId: string): Promise<BackupHistoryEntry> {
+  return invoke<BackupHistoryEntry>("run_backup_now", { profileId });
+}
+
+export interface SnapshotInfo {
+  file_name: string;
+  db_filename: string;
+  reason: "pre-migration" | "pre-update" | "pre-restore";
+  created_at: string;
+  schema_version: number;
+  size_bytes: number;
+}
+
+export interface ProfileSnapshotResult {
+  profile_id: string;
+  profile_name: string;
+  snapshot: SnapshotInfo | null;
+  error: string | null;
+}
+
+export async function snapshotAllProfiles(
+  reason: "pre-migration" | "pre-update" = "pre-update"
+): Promise<ProfileSnapshotResult[]> {
+  return invoke<ProfileSnapshotResult[]>("snapshot_all_profiles", { reason });
+}
+
+export async function listProfileSnapshots(profileId: string): Promise<SnapshotInfo[]> {
+  return invoke<SnapshotInfo[]>("list_profile_snapshots", { profileId });
+}
+
+/** The database must be closed first (`closeDb`). Defaults to the latest pre-migration/pre-update snapshot. */
+export async function restorePreviousVersion(
+  profileId: string,
+  snapshotFile?: string
+): Promise<SnapshotInfo> {
+  return invoke<SnapshotInfo>("restore_previous_version", {
+    profileId,
+    snapshotFile: snapshotFile ?? null,
+  });
+}
@@ -1,4 +1,5 @@
-import { getDb } from "./db";
+import { getDb, BASE_AMOUNT, decimal, toCents, withDecimalAmounts } from "./db";
+import { getAdjustmentTotalsByCategory } from "./adjustmentService";
 import type {
   Category,
   BudgetEntry,
@@ -7,6 +8,24 @@ import type {
   BudgetVsActualRow,
 } from "../shared/types";
 
+type ActualRow = { category_id: number | null; actual: number };
+
+/** Adds adjustment occurrences in the range to transaction actuals. */
+async function withAdjustmentActuals(
+  rows: ActualRow[],
+  dateFrom: string,
+  dateTo: string
+): Promise<ActualRow[]> {
+  const totals = await getAdjustmentTotalsByCategory(dateFrom, dateTo);
+  const merged = rows.map((row) => ({ ...row }));
+  for (const [categoryId, amount] of totals) {
+    const row = merged.find((r) => r.category_id === categoryId);
+    if (row) row.actual += amount;
+    else merged.push({ category_id: categoryId, actual: amount });
+  }
+  return merged;
+}
+
 function computeMonthDateRange(year: number, month: number) {
   const dateFrom = `${year}-${String(month).padStart(2, "0")}-01`;
   const lastDay = new Date(year, month, 0).getDate();
@@ -33,10 +52,11 @@ export async function getBudgetEntriesForMonth(
   month: number
 ): Promise<BudgetEntry[]> {
   const db = await getDb();
-  return db.select<BudgetEntry[]>(
+  const rows = await db.select<BudgetEntry[]>(
     "SELECT * FROM budget_entries WHERE year = $1 AND month = $2",
     [year, month]
   );
+  return withDecimalAmounts(rows);
 }
 
 export async function upsertBudgetEntry(
@@ -51,7 +71,7 @@ export async function upsertBudgetEntry(
     `INSERT INTO budget_entries (category_id, year, month, amount, notes)
      VALUES ($1, $2, $3, $4, $5)
      ON CONFLICT(category_id, year, month) DO UPDATE SET amount = $4, notes = $5, updated_at = CURRENT_TIMESTAMP`,
-    [categoryId, year, month, amount, notes || null]
+    [categoryId, year, month, toCents(amount), notes || null]
   );
 }
 
@@ -73,23 +93,25 @@ export async function getActualsByCategory(
 ): Promise<Array<{ category_id: number | null; actual: number }>> {
   const db = await getDb();
   const { dateFrom, dateTo } = computeMonthDateRange(year, month);
-  return db.select<Array<{ category_id: number | null; actual: number }>>(
-    `SELECT category_id, COALESCE(SUM(amount), 0) AS actual
+  const rows = await db.select<ActualRow[]>(
+    `SELECT category_id, ${decimal(`COALESCE(SUM(${BASE_AMOUNT}), 0)`)} AS actual
      FROM transactions
      WHERE date BETWEEN $1 AND $2
      GROUP BY category_id`,
     [dateFrom, dateTo]
   );
+  return withAdjustmentActuals(rows, dateFrom, dateTo);
 }
 
 export async function getBudgetEntriesForYear(
   year: number
 ): Promise<BudgetEntry[]> {
   const db = await getDb();
-  return db.select<BudgetEntry[]>(
+  const rows = await db.select<BudgetEntry[]>(
     "SELECT * FROM budget_entries WHERE year = $1",
     [year]
   );
+  return withDecimalAmounts(rows);
 }
 
 export async function upsertBudgetEntriesForYear(
@@ -111,7 +133,7 @@ export async function upsertBudgetEntriesForYear(
         `INSERT INTO budget_entries (category_id, year, month, amount)
          VALUES ($1, $2, $3, $4)
          ON CONFLICT(category_id, year, month) DO UPDATE SET amount = $4, updated_at = CURRENT_TIMESTAMP`,
-        [categoryId, year, month, amount]
+        [categoryId, year, month, toCents(amount)]
       );
     }
   }
@@ -130,10 +152,11 @@ export async function getTemplateEntries(
   templateId: number
 ): Promise<BudgetTemplateEntry[]> {
   const db = await getDb();
-  return db.select<BudgetTemplateEntry[]>(
+  const rows = await db.select<BudgetTemplateEntry[]>(
     "SELECT * FROM budget_template_entries WHERE template_id = $1",
     [templateId]
   );
+  return withDecimalAmounts(rows);
 }
 
 export async function saveAsTemplate(
@@ -151,7 +174,7 @@ export async function saveAsTemplate(
   for (const entry of entries) {
     await db.execute(
       "INSERT INTO budget_template_entries (template_id, category_id, amount) VALUES ($1, $2, $3)",
-      [templateId, entry.category_id, entry.amount]
+      [templateId, entry.category_id, toCents(entry.amount)]
     );
   }
 
@@ -185,13 +208,14 @@ async function getActualsByCategoryRange(
   dateTo: string
 ): Promise<Array<{ category_id: number | null; actual: number }>> {
   const db = await getDb();
-  return db.select<Array<{ category_id: number | null; actual: number }>>(
-    `SELECT category_id, COALESCE(SUM(amount), 0) AS actual
+  const rows = await db.select<ActualRow[]>(
+    `SELECT category_id, ${decimal(`COALESCE(SUM(${BASE_AMOUNT}), 0)`)} AS actual
      FROM transactions
      WHERE date BETWEEN $1 AND $2
      GROUP BY category_id`,
     [dateFrom, dateTo]
   );
+  return withAdjustmentActuals(rows, dateFrom, dateTo);
 }
 
 const TYPE_ORDER: Record<string, number> = { expense: 0, income: 1, transfer: 2 };
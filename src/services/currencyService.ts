@@ -0,0 +1,56 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ConversionSummary, RateCoverage, RateImportReport } from "../shared/types";
+
+/** Asks for a rates CSV (e.g. the ECB eurofxref-hist.csv); null when cancelled. */
+export async function pickRatesFile(): Promise<string | null> {
+  return invoke<string | null>("pick_import_file", {
+    filters: [["CSV", ["csv"]]],
+  });
+}
+
+/**
+ * Imports exchange rates quoted for `reference` (EUR for ECB files) and converts all
+ * transactions again.
+ */
+export async function importExchangeRates(
+  filePath: string,
+  reference = "EUR"
+): Promise<RateImportReport> {
+  return invoke<RateImportReport>("import_exchange_rates", { filePath, reference });
+}
+
+export async function getExchangeRateCoverage(): Promise<RateCoverage[]> {
+  return invoke<RateCoverage[]>("get_exchange_rate_coverage");
+}
+
+export async function deleteExchangeRates(
+  fromCurrency: string,
+  toCurrency: string
+): Promise<ConversionSummary> {
+  return invoke<ConversionSummary>("delete_exchange_rates", { fromCurrency, toCurrency });
+}
+
+/** Currency reports and budgets aggregate in. */
+export async function getBaseCurrency(): Promise<string> {
+  return invoke<string>("get_base_currency");
+}
+
+export async function setBaseCurrency(currency: string): Promise<ConversionSummary> {
+  return invoke<ConversionSummary>("set_base_currency", { currency });
+}
+
+/** Sets a transaction's own currency; null makes it follow its account again. */
+export async function setTransactionCurrency(
+  transactionId: number,
+  currency: string | null
+): Promise<ConversionSummary> {
+  return invoke<ConversionSummary>("set_transaction_currency", { transactionId, currency });
+}
+
+/**
+ * Converts transactions to the base currency: all of them, or only those without a
+ * converted amount yet (after inserting transactions).
+ */
+export async function refreshBaseAmounts(onlyMissing = false): Promise<ConversionSummary> {
+  return invoke<ConversionSummary>("refresh_base_amounts", { onlyMissing });
+}
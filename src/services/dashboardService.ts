@@ -1,4 +1,5 @@
-import { getDb } from "./db";
+import { getDb, BASE_AMOUNT, BASE_AMOUNT_T, decimal } from "./db";
+import { getAdjustmentOccurrences } from "./adjustmentService";
 import type {
   DashboardSummary,
   CategoryBreakdownItem,
@@ -40,9 +41,9 @@ export async function getDashboardSummary(
   >(
     `SELECT
        COUNT(*) AS totalCount,
-       COALESCE(SUM(amount), 0) AS totalAmount,
-       COALESCE(SUM(CASE WHEN amount > 0 THEN amount ELSE 0 END), 0) AS incomeTotal,
-       COALESCE(SUM(CASE WHEN amount < 0 THEN amount ELSE 0 END), 0) AS expenseTotal
+       ${decimal(`COALESCE(SUM(${BASE_AMOUNT}), 0)`)} AS totalAmount,
+       ${decimal(`COALESCE(SUM(CASE WHEN amount > 0 THEN ${BASE_AMOUNT} ELSE 0 END), 0)`)} AS incomeTotal,
+       ${decimal(`COALESCE(SUM(CASE WHEN amount < 0 THEN ${BASE_AMOUNT} ELSE 0 END), 0)`)} AS expenseTotal
      FROM transactions
      ${whereSQL}`,
     params
@@ -74,19 +75,41 @@ export async function getExpensesByCategory(
 
   const whereSQL = `WHERE ${whereClauses.join(" AND ")}`;
 
-  return db.select<CategoryBreakdownItem[]>(
+  const rows = await db.select<Array<CategoryBreakdownItem & { signed_total: number }>>(
     `SELECT
        t.category_id,
        COALESCE(c.name, 'Uncategorized') AS category_name,
        COALESCE(c.color, '#9ca3af') AS category_color,
-       ABS(SUM(t.amount)) AS total
+       ${decimal(`SUM(${BASE_AMOUNT_T})`)} AS signed_total
      FROM transactions t
      LEFT JOIN categories c ON t.category_id = c.id
      ${whereSQL}
-     GROUP BY t.category_id
-     ORDER BY total DESC`,
+     GROUP BY t.category_id`,
     params
   );
+
+  // Adjustments are signed like transactions, so they net out before taking ABS
+  for (const occurrence of await getAdjustmentOccurrences(dateFrom, dateTo)) {
+    for (const entry of occurrence.entries) {
+      if (entry.category_type !== "expense") continue;
+      const row = rows.find((r) => r.category_id === entry.category_id);
+      if (row) {
+        row.signed_total += entry.amount;
+      } else {
+        rows.push({
+          category_id: entry.category_id,
+          category_name: entry.category_name,
+          category_color: entry.category_color,
+          total: 0,
+          signed_total: entry.amount,
+        });
+      }
+    }
+  }
+
+  return rows
+    .map(({ signed_total, ...item }) => ({ ...item, total: Math.abs(signed_total) }))
+    .sort((a, b) => b.total - a.total);
 }
 
 export async function getTransactionsByCategory(
@@ -123,7 +146,7 @@ export async function getTransactionsByCategory(
 
   return db.select<TransactionRow[]>(
     `SELECT
-       t.id, t.date, t.description, t.amount,
+       t.id, t.date, t.description, ${decimal("t.amount")} AS amount,
        t.category_id,
        c.name AS category_name,
        c.color AS category_color,
@@ -146,7 +169,7 @@ export async function getRecentTransactions(
 
   return db.select<RecentTransaction[]>(
     `SELECT
-       t.id, t.date, t.description, t.amount,
+       t.id, t.date, t.description, ${decimal("t.amount")} AS amount,
        c.name AS category_name, c.color AS category_color
      FROM transactions t
      LEFT JOIN categories c ON t.category_id = c.id
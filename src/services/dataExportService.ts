@@ -1,7 +1,15 @@
-import { getDb } from "./db";
+import { invoke } from "@tauri-apps/api/core";
+import { getDb, toCents, decimal } from "./db";
+import { refreshBaseAmounts } from "./currencyService";
+import { parseImportDates } from "./dateService";
 import Papa from "papaparse";
 import type { Category, Supplier, Keyword } from "../shared/types";
 
+/** This installation's age public key (`age1...`), to give to whoever sends us encrypted exports. */
+export async function getExportPublicKey(): Promise<string> {
+  return invoke<string>("get_export_public_key");
+}
+
 // --- Export types ---
 
 export type ExportMode =
@@ -67,7 +75,7 @@ export async function getExportKeywords(): Promise<Keyword[]> {
 export async function getExportTransactions(): Promise<ExportTransaction[]> {
   const db = await getDb();
   return db.select<ExportTransaction[]>(
-    `SELECT t.id, t.date, t.description, t.amount, t.category_id,
+    `SELECT t.id, t.date, t.description, ${decimal("t.amount")} AS amount, t.category_id,
             c.name AS category_name, t.original_description, t.notes,
             t.is_manually_categorized, t.is_split, t.parent_transaction_id
      FROM transactions t
@@ -253,13 +261,34 @@ export async function importCategoriesOnly(data: ExportEnvelope["data"]): Promis
   }
 }
 
+/**
+ * Transactions with their dates as YYYY-MM-DD; throws, before anything is wiped, when a
+ * date is not a valid one.
+ */
+async function withIsoDates(
+  transactions: ExportTransaction[] | undefined
+): Promise<ExportTransaction[] | undefined> {
+  if (!transactions) return transactions;
+  const { dates } = await parseImportDates(
+    transactions.map((tx) => tx.date),
+    "YYYY-MM-DD"
+  );
+  const invalid = dates.findIndex((date) => !date);
+  if (invalid >= 0) {
+    throw new Error(`Invalid date for transaction ${invalid + 1}: ${transactions[invalid].date}`);
+  }
+  return transactions.map((tx, i) => ({ ...tx, date: dates[i]! }));
+}
+
 export async function importTransactionsWithCategories(
   data: ExportEnvelope["data"],
   filename: string
 ): Promise<void> {
+  data = { ...data, transactions: await withIsoDates(data.transactions) };
   const db = await getDb();
 
-  // Wipe everything
+  // Wipe everything (reconciled transactions are unlocked first)
+  await db.execute("UPDATE transactions SET reconciliation_id = NULL");
   await db.execute("DELETE FROM transactions");
   await db.execute("DELETE FROM imported_files");
   await db.execute("DELETE FROM import_sources");
@@ -335,7 +364,7 @@ export async function importTransactionsWithCategories(
         [
           tx.date,
           tx.description,
-          tx.amount,
+          toCents(tx.amount),
           tx.category_id,
           tx.original_description,
           tx.notes,
@@ -348,15 +377,18 @@ export async function importTransactionsWithCategories(
       );
     }
   }
+  await refreshBaseAmounts(true);
 }
 
 export async function importTransactionsOnly(
   data: ExportEnvelope["data"],
   filename: string
 ): Promise<void> {
+  data = { ...data, transactions: await withIsoDates(data.transactions) };
   const db = await getDb();
 
-  // Wipe transactions and import history
+  // Wipe transactions and import history (reconciled transactions are unlocked first)
+  await db.execute("UPDATE transactions SET reconciliation_id = NULL");
   await db.execute("DELETE FROM transactions");
   await db.execute("DELETE FROM imported_files");
   await db.execute("DELETE FROM import_sources");
@@ -386,7 +418,7 @@ export async function importTransactionsOnly(
         [
           tx.date,
           tx.description,
-          tx.amount,
+          toCents(tx.amount),
           tx.category_id,
           tx.original_description,
           tx.notes,
@@ -399,4 +431,5 @@ export async function importTransactionsOnly(
       );
     }
   }
+  await refreshBaseAmounts(true);
 }
@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { DateAuditReport, ImportDates } from "../shared/types";
+
+/**
+ * Reads imported date values as ISO dates, with a format in either notation
+ * (`DD/MM/YYYY` or `%d/%m/%Y`). Impossible dates such as 31/02 are rejected.
+ */
+export async function parseImportDates(values: string[], format: string): Promise<ImportDates> {
+  return invoke<ImportDates>("parse_import_dates", { values, format });
+}
+
+/**
+ * Finds the profile's dates not stored as YYYY-MM-DD and, unless dryRun, rewrites them
+ * (after a snapshot). Ambiguous and impossible dates are reported and left unchanged.
+ */
+export async function auditProfileDates(dryRun: boolean): Promise<DateAuditReport> {
+  return invoke<DateAuditReport>("audit_profile_dates", { dryRun });
+}
@@ -1,7 +1,32 @@
 import Database from "@tauri-apps/plugin-sql";
+import { migrateProfileDb, type MigrationReport } from "./profileService";
 
 let dbInstance: Database | null = null;
 
+/** Amounts are stored as integer cents; services exchange decimal amounts (`-12.34`). */
+export function toCents(amount: number): number {
+  return Math.round(amount * 100);
+}
+
+export function fromCents(cents: number): number {
+  return cents / 100;
+}
+
+/** Rows read with `SELECT *` from a table with an `amount` column. */
+export function withDecimalAmounts<T extends { amount: number }>(rows: T[]): T[] {
+  return rows.map((row) => ({ ...row, amount: fromCents(row.amount) }));
+}
+
+/** SQL reading a cents expression (a column or an aggregate) back as a decimal amount. */
+export function decimal(expression: string): string {
+  return `(${expression}) / 100.0`;
+}
+
+/** Transaction amount in cents in the base currency, or as recorded when no exchange rate covers it. */
+export const BASE_AMOUNT = "COALESCE(base_amount, amount)";
+/** BASE_AMOUNT for queries aliasing transactions as `t`. */
+export const BASE_AMOUNT_T = "COALESCE(t.base_amount, t.amount)";
+
 export async function getDb(): Promise<Database> {
   if (!dbInstance) {
     throw new Error("No database connection. Call connectToProfile() first.");
@@ -9,12 +34,23 @@ export async function getDb(): Promise<Database> {
   return dbInstance;
 }
 
-export async function connectToProfile(dbFilename: string): Promise<void> {
+/** Applies pending migrations to a profile database, failing if one of them did not apply. */
+async function migrate(dbFilename: string): Promise<MigrationReport> {
+  const report = await migrateProfileDb(dbFilename);
+  if (report.error) {
+    throw new Error(report.error);
+  }
+  return report;
+}
+
+export async function connectToProfile(dbFilename: string): Promise<MigrationReport> {
   if (dbInstance) {
     await dbInstance.close();
     dbInstance = null;
   }
+  const report = await migrate(dbFilename);
   dbInstance = await Database.load(`sqlite:${dbFilename}`);
+  return report;
 }
 
 export async function initializeNewProfileDb(dbFilename: string, sqlStatements: string[]): Promise<void> {
@@ -26,6 +62,8 @@ export async function initializeNewProfileDb(dbFilename: string, sqlStatements:
   for (const sql of sqlStatements) {
     await dbInstance.execute(sql);
   }
+  // The init SQL stops at the consolidated schema; bring the new profile up to date
+  await migrate(dbFilename);
 }
 
 export async function closeDb(): Promise<void> {
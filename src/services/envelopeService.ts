@@ -0,0 +1,44 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BudgetTransfer, Envelope } from "../shared/types";
+
+/**
+ * What is left to spend in each expense category, month by month for a year: budget,
+ * money moved in or out, spending and, for categories that roll over, what the
+ * previous month left (or overspent).
+ */
+export async function getBudgetEnvelopes(year: number): Promise<Envelope[]> {
+  return invoke<Envelope[]>("get_budget_envelopes", { year });
+}
+
+/** Moves money between two envelopes for a month; refused beyond what the source has left. */
+export async function moveBudgetMoney(
+  year: number,
+  month: number,
+  fromCategoryId: number,
+  toCategoryId: number,
+  amount: number,
+  notes?: string
+): Promise<BudgetTransfer> {
+  return invoke<BudgetTransfer>("move_budget_money", {
+    year,
+    month,
+    fromCategoryId,
+    toCategoryId,
+    amount,
+    notes: notes ?? null,
+  });
+}
+
+/** Transfers made for months of a year, newest first. */
+export async function getBudgetTransfers(year: number): Promise<BudgetTransfer[]> {
+  return invoke<BudgetTransfer[]>("get_budget_transfers", { year });
+}
+
+/** Moves a transfer's money back with a new transfer linked to it; the history keeps both. */
+export async function reverseBudgetTransfer(id: number): Promise<BudgetTransfer> {
+  return invoke<BudgetTransfer>("reverse_budget_transfer", { id });
+}
+
+export async function setBudgetRollover(categoryId: number, enabled: boolean): Promise<void> {
+  return invoke("set_budget_rollover", { categoryId, enabled });
+}
@@ -0,0 +1,15 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BudgetForecast, ForecastMethod } from "../shared/types";
+
+/**
+ * Budget proposed for a year from past transactions, category by category, with the
+ * figures behind each line. Nothing is saved: accepted lines are written with
+ * `upsertBudgetEntriesForYear`.
+ */
+export async function proposeBudget(
+  year: number,
+  method: ForecastMethod,
+  historyMonths: number
+): Promise<BudgetForecast> {
+  return invoke<BudgetForecast>("propose_budget", { year, method, historyMonths });
+}
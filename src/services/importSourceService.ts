@@ -1,9 +1,16 @@
-import { getDb } from "./db";
-import type { ImportSource } from "../shared/types";
+import { getDb, toCents, fromCents } from "./db";
+import { refreshBaseAmounts } from "./currencyService";
+import type { AccountMetadata, ImportSource } from "../shared/types";
+
+/** The opening balance is stored in cents. */
+function readSource(row: ImportSource): ImportSource {
+  return { ...row, opening_balance: fromCents(row.opening_balance) };
+}
 
 export async function getAllSources(): Promise<ImportSource[]> {
   const db = await getDb();
-  return db.select<ImportSource[]>("SELECT * FROM import_sources ORDER BY name");
+  const rows = await db.select<ImportSource[]>("SELECT * FROM import_sources ORDER BY name");
+  return rows.map(readSource);
 }
 
 export async function getSourceByName(
@@ -14,7 +21,7 @@ export async function getSourceByName(
     "SELECT * FROM import_sources WHERE name = $1",
     [name]
   );
-  return rows.length > 0 ? rows[0] : null;
+  return rows.length > 0 ? readSource(rows[0]) : null;
 }
 
 export async function getSourceById(
@@ -25,11 +32,11 @@ export async function getSourceById(
     "SELECT * FROM import_sources WHERE id = $1",
     [id]
   );
-  return rows.length > 0 ? rows[0] : null;
+  return rows.length > 0 ? readSource(rows[0]) : null;
 }
 
 export async function createSource(
-  source: Omit<ImportSource, "id" | "created_at" | "updated_at">
+  source: Omit<ImportSource, "id" | "created_at" | "updated_at" | keyof AccountMetadata>
 ): Promise<number> {
   const db = await getDb();
   const result = await db.execute(
@@ -102,6 +109,26 @@ export async function updateSource(
     fields.push(`has_header = $${paramIndex++}`);
     values.push(source.has_header ? 1 : 0);
   }
+  if (source.account_type !== undefined) {
+    fields.push(`account_type = $${paramIndex++}`);
+    values.push(source.account_type);
+  }
+  if (source.opening_balance !== undefined) {
+    fields.push(`opening_balance = $${paramIndex++}`);
+    values.push(toCents(source.opening_balance));
+  }
+  if (source.opening_balance_date !== undefined) {
+    fields.push(`opening_balance_date = $${paramIndex++}`);
+    values.push(source.opening_balance_date || null);
+  }
+  if (source.currency !== undefined) {
+    fields.push(`currency = $${paramIndex++}`);
+    values.push(source.currency);
+  }
+  if (source.is_closed !== undefined) {
+    fields.push(`is_closed = $${paramIndex++}`);
+    values.push(source.is_closed ? 1 : 0);
+  }
 
   if (fields.length === 0) return;
 
@@ -112,6 +139,10 @@ export async function updateSource(
     `UPDATE import_sources SET ${fields.join(", ")} WHERE id = $${paramIndex}`,
     values
   );
+  // The account's transactions are now in another currency
+  if (source.currency !== undefined) {
+    await refreshBaseAmounts();
+  }
 }
 
 export async function deleteSource(id: number): Promise<void> {
@@ -116,6 +116,8 @@ export async function deleteImportWithTransactions(
 
 export async function deleteAllImportsWithTransactions(): Promise<number> {
   const db = await getDb();
+  // Reconciled transactions are locked against deletion; wiping everything unlocks them
+  await db.execute("UPDATE transactions SET reconciliation_id = NULL");
   const result = await db.execute("DELETE FROM transactions");
   await db.execute("DELETE FROM imported_files");
   await db.execute("DELETE FROM import_sources");
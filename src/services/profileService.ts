@@ -7,6 +7,53 @@ export interface Profile {
   pin_hash: string | null;
   db_filename: string;
   created_at: string;
+  db_key_salt?: string | null;
+}
+
+export interface PinVerification {
+  valid: boolean;
+  failed_attempts: number;
+  retry_after_secs: number | null;
+}
+
+export interface ProfileDbEncryption {
+  encrypted: boolean;
+  unlocked: boolean;
+}
+
+export interface MigrationReport {
+  db_filename: string;
+  from_version: number;
+  to_version: number;
+  latest_version: number;
+  baselined: boolean;
+  applied: { version: number; description: string }[];
+  error: string | null;
+}
+
+export interface SchemaCheckReport {
+  ok: boolean;
+  latest_version: number;
+  consolidated_drift: string[];
+  profiles: {
+    profile_id: string;
+    profile_name: string;
+    db_filename: string;
+    schema_version: number;
+    checksum_issues: { version: number; description: string; kind: "mismatch" | "unknown" | "failed" }[];
+    drift: string[];
+    locked: boolean;
+    error: string | null;
+  }[];
+}
+
+export interface RollbackReport {
+  db_filename: string;
+  from_version: number;
+  target_version: number;
+  dry_run: boolean;
+  steps: { version: number; description: string; data_loss: string[] }[];
+  applied: boolean;
 }
 
 export interface ProfilesConfig {
@@ -22,18 +69,189 @@ export async function saveProfiles(config: ProfilesConfig): Promise<void> {
   return invoke("save_profiles", { config });
 }
 
-export async function deleteProfileDb(dbFilename: string): Promise<void> {
-  return invoke("delete_profile_db", { dbFilename });
+export interface ProfilesRebuildResult {
+  config: ProfilesConfig;
+  encrypted_skipped: string[];
+}
+
+/** Recreates profiles.json from the database files when it and its backup are unreadable. */
+export async function rebuildProfiles(): Promise<ProfilesRebuildResult> {
+  return invoke<ProfilesRebuildResult>("rebuild_profiles");
+}
+
+/**
+ * Moves the database of an inactive profile to the trash (kept 30 days) and removes the
+ * profile from profiles.json. `secureOverwrite` deletes it for good instead.
+ */
+export async function deleteProfileDb(dbFilename: string, secureOverwrite = false): Promise<void> {
+  return invoke("delete_profile_db", { dbFilename, secureOverwrite });
+}
+
+export interface DeletedProfile {
+  trash_id: string;
+  profile: Profile;
+  deleted_at: string;
+  expires_at: string;
+}
+
+export async function listDeletedProfiles(): Promise<DeletedProfile[]> {
+  return invoke<DeletedProfile[]>("list_deleted_profiles");
+}
+
+export async function restoreDeletedProfile(trashId: string): Promise<Profile> {
+  return invoke<Profile>("restore_deleted_profile", { trashId });
+}
+
+export async function purgeDeletedProfile(trashId: string): Promise<void> {
+  return invoke("purge_deleted_profile", { trashId });
+}
+
+/** `full` copies the whole database; `structure` keeps the setup but no transactions. */
+export async function cloneProfile(
+  sourceProfileId: string,
+  name: string,
+  color: string,
+  mode: "full" | "structure"
+): Promise<Profile> {
+  return invoke<Profile>("clone_profile", { sourceProfileId, name, color, mode });
 }
 
 export async function getNewProfileInitSql(): Promise<string[]> {
   return invoke<string[]>("get_new_profile_init_sql");
 }
 
+export async function migrateProfileDb(dbFilename: string): Promise<MigrationReport> {
+  return invoke<MigrationReport>("migrate_profile_db", { dbFilename });
+}
+
+export async function checkSchema(): Promise<SchemaCheckReport> {
+  return invoke<SchemaCheckReport>("check_schema");
+}
+
+/** Report of the check run at startup, `null` while it is still running. */
+export async function getStartupSchemaReport(): Promise<SchemaCheckReport | null> {
+  return invoke<SchemaCheckReport | null>("get_startup_schema_report");
+}
+
+/** Not applied when a step would lose data; the database must be closed first (`closeDb`). */
+export async function rollbackProfileDb(
+  profileId: string,
+  targetVersion: number,
+  dryRun: boolean
+): Promise<RollbackReport> {
+  return invoke<RollbackReport>("rollback_profile_db", { profileId, targetVersion, dryRun });
+}
+
 export async function hashPin(pin: string): Promise<string> {
   return invoke<string>("hash_pin", { pin });
 }
 
-export async function verifyPin(pin: string, storedHash: string): Promise<boolean> {
-  return invoke<boolean>("verify_pin", { pin, storedHash });
+export async function verifyProfilePin(profileId: string, pin: string): Promise<PinVerification> {
+  return invoke<PinVerification>("verify_profile_pin", { profileId, pin });
+}
+
+export async function getProfileDbEncryption(profileId: string): Promise<ProfileDbEncryption> {
+  return invoke<ProfileDbEncryption>("get_profile_db_encryption", { profileId });
+}
+
+export async function unlockProfileDb(profileId: string, password: string): Promise<void> {
+  return invoke("unlock_profile_db", { profileId, password });
+}
+
+export async function lockProfileDb(profileId: string): Promise<void> {
+  return invoke("lock_profile_db", { profileId });
+}
+
+export async function encryptProfileDb(profileId: string, password: string): Promise<void> {
+  return invoke("encrypt_profile_db", { profileId, password });
+}
+
+export async function decryptProfileDb(profileId: string, password: string): Promise<void> {
+  return invoke("decrypt_profile_db", { profileId, password });
+}
+
+export async function changeProfileDbPassword(
+  profileId: string,
+  oldPassword: string,
+  newPassword: string
+): Promise<void> {
+  return invoke("change_profile_db_password", { profileId, oldPassword, newPassword });
+}
+
+export interface BundleProfile {
+  id: string;
+  name: string;
+  color: string;
+  pin_hash: string | null;
+  db_filename: string;
+  created_at: string;
+}
+
+export interface BundleImportResult {
+  profile: Profile;
+  renamed: boolean;
+}
+
+/**
+ * Writes the profile and a snapshot of its database to one file, SREF-encrypted with
+ * `password`. The password is required when the profile's database is encrypted.
+ */
+export async function exportProfileBundle(
+  profileId: string,
+  filePath: string,
+  password?: string | null
+): Promise<BundleProfile> {
+  return invoke<BundleProfile>("export_profile_bundle", {
+    profileId,
+    filePath,
+    password: password ?? null,
+  });
+}
+
+export async function importProfileBundle(
+  filePath: string,
+  password?: string | null
+): Promise<BundleImportResult> {
+  return invoke<BundleImportResult>("import_profile_bundle", {
+    filePath,
+    password: password ?? null,
+  });
+}
+
+export interface MergeCounts {
+  matched: number;
+  created: number;
+}
+
+export interface MergeConflict {
+  kind:
+    | "category_type"
+    | "supplier_category"
+    | "source_config"
+    | "file_hash"
+    | "keyword_category"
+    | "budget_amount";
+  item: string;
+  detail: string;
+}
+
+export interface MergeReport {
+  dry_run: boolean;
+  categories: MergeCounts;
+  suppliers: MergeCounts;
+  import_sources: MergeCounts;
+  imported_files: MergeCounts;
+  keywords: MergeCounts;
+  transactions: MergeCounts;
+  adjustments: MergeCounts;
+  budget_entries: MergeCounts;
+  budget_templates: MergeCounts;
+  import_config_templates: MergeCounts;
+  conflicts: MergeConflict[];
+  applied: boolean;
+}
+
+/** Merges another profile into the active one; run with `dryRun` first to review conflicts. */
+export async function mergeProfile(sourceProfileId: string, dryRun: boolean): Promise<MergeReport> {
+  return invoke<MergeReport>("merge_profile", { sourceProfileId, dryRun });
 }
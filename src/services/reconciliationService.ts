@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Reconciliation, ReconciliationPreview } from "../shared/types";
+
+/**
+ * Compares a statement's closing balance with the books and proposes duplicate or
+ * late transactions that explain the difference. Nothing is saved.
+ */
+export async function previewReconciliation(
+  sourceId: number,
+  statementDate: string,
+  statementBalance: number
+): Promise<ReconciliationPreview> {
+  return invoke<ReconciliationPreview>("preview_reconciliation", {
+    sourceId,
+    statementDate,
+    statementBalance,
+  });
+}
+
+/** Saves the reconciliation and locks the account's transactions up to statementDate. */
+export async function completeReconciliation(
+  sourceId: number,
+  statementDate: string,
+  statementBalance: number,
+  notes?: string
+): Promise<Reconciliation> {
+  return invoke<Reconciliation>("complete_reconciliation", {
+    sourceId,
+    statementDate,
+    statementBalance,
+    notes: notes ?? null,
+  });
+}
+
+export async function getReconciliations(sourceId: number): Promise<Reconciliation[]> {
+  return invoke<Reconciliation[]>("get_reconciliations", { sourceId });
+}
+
+/** Undoes the latest reconciliation of an account and unlocks its transactions. */
+export async function undoReconciliation(id: number): Promise<void> {
+  return invoke("undo_reconciliation", { id });
+}
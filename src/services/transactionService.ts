@@ -1,5 +1,6 @@
-import { getDb } from "./db";
+import { getDb, toCents, fromCents, decimal } from "./db";
 import { categorizeBatch } from "./categorizationService";
+import { refreshBaseAmounts } from "./currencyService";
 import type {
   Transaction,
   TransactionRow,
@@ -34,7 +35,7 @@ export async function insertBatch(
       [
         tx.date,
         tx.description,
-        tx.amount,
+        toCents(tx.amount),
         tx.source_id,
         tx.file_id,
         tx.original_description,
@@ -52,6 +53,7 @@ export async function insertBatch(
     onProgress(insertedCount);
   }
 
+  await refreshBaseAmounts(true);
   return insertedCount;
 }
 
@@ -79,7 +81,7 @@ export async function findDuplicates(
     const row = rows[i];
     const existing = await db.select<Transaction[]>(
       `SELECT id FROM transactions WHERE date = $1 AND description = $2 AND amount = $3 LIMIT 1`,
-      [row.date, row.description, row.amount]
+      [row.date, row.description, toCents(row.amount)]
     );
     if (existing.length > 0) {
       duplicates.push({
@@ -157,10 +159,10 @@ export async function getTransactionPage(
 
   // Rows query
   const rowsSQL = `
-    SELECT t.id, t.date, t.description, t.amount, t.category_id,
+    SELECT t.id, t.date, t.description, ${decimal("t.amount")} AS amount, t.category_id,
            c.name AS category_name, c.color AS category_color,
            s.name AS source_name, t.notes, t.is_manually_categorized,
-           t.is_split
+           t.is_split, t.reconciliation_id
     FROM transactions t
     LEFT JOIN categories c ON t.category_id = c.id
     LEFT JOIN import_sources s ON t.source_id = s.id
@@ -173,9 +175,9 @@ export async function getTransactionPage(
   // Totals query
   const totalsSQL = `
     SELECT COUNT(*) AS totalCount,
-           COALESCE(SUM(t.amount), 0) AS totalAmount,
-           COALESCE(SUM(CASE WHEN t.amount > 0 THEN t.amount ELSE 0 END), 0) AS incomeTotal,
-           COALESCE(SUM(CASE WHEN t.amount < 0 THEN t.amount ELSE 0 END), 0) AS expenseTotal
+           ${decimal("COALESCE(SUM(t.amount), 0)")} AS totalAmount,
+           ${decimal("COALESCE(SUM(CASE WHEN t.amount > 0 THEN t.amount ELSE 0 END), 0)")} AS incomeTotal,
+           ${decimal("COALESCE(SUM(CASE WHEN t.amount < 0 THEN t.amount ELSE 0 END), 0)")} AS expenseTotal
     FROM transactions t
     LEFT JOIN categories c ON t.category_id = c.id
     LEFT JOIN import_sources s ON t.source_id = s.id
@@ -233,6 +235,12 @@ export async function updateTransactionNotes(
   );
 }
 
+/** Deletes a transaction (and its split parts). Reconciled transactions are locked. */
+export async function deleteTransaction(txId: number): Promise<void> {
+  const db = await getDb();
+  await db.execute(`DELETE FROM transactions WHERE id = $1`, [txId]);
+}
+
 export async function getAllCategories(): Promise<Category[]> {
   const db = await getDb();
   return db.select<Category[]>(
@@ -242,9 +250,10 @@ export async function getAllCategories(): Promise<Category[]> {
 
 export async function getAllImportSources(): Promise<ImportSource[]> {
   const db = await getDb();
-  return db.select<ImportSource[]>(
+  const sources = await db.select<ImportSource[]>(
     `SELECT * FROM import_sources ORDER BY name`
   );
+  return sources.map((s) => ({ ...s, opening_balance: fromCents(s.opening_balance) }));
 }
 
 export async function autoCategorizeTransactions(): Promise<number> {
@@ -275,10 +284,10 @@ export async function autoCategorizeTransactions(): Promise<number> {
 export async function getSplitParentTransactions(): Promise<TransactionRow[]> {
   const db = await getDb();
   return db.select<TransactionRow[]>(
-    `SELECT t.id, t.date, t.description, t.amount, t.category_id,
+    `SELECT t.id, t.date, t.description, ${decimal("t.amount")} AS amount, t.category_id,
             c.name AS category_name, c.color AS category_color,
             s.name AS source_name, t.notes, t.is_manually_categorized,
-            t.is_split
+            t.is_split, t.reconciliation_id
      FROM transactions t
      LEFT JOIN categories c ON t.category_id = c.id
      LEFT JOIN import_sources s ON t.source_id = s.id
@@ -291,7 +300,7 @@ export async function getSplitChildren(parentId: number): Promise<SplitChild[]>
   const db = await getDb();
   return db.select<SplitChild[]>(
     `SELECT t.id, t.category_id, c.name AS category_name, c.color AS category_color,
-            t.amount, t.description
+            ${decimal("t.amount")} AS amount, t.description
      FROM transactions t
      LEFT JOIN categories c ON t.category_id = c.id
      WHERE t.parent_transaction_id = $1
@@ -319,30 +328,31 @@ export async function saveSplitAdjustment(
   );
   if (!parent) throw new Error("Parent transaction not found");
 
-  // Insert each split child
+  // Insert each split child, summing in cents so the offset cancels them exactly
   let offsetTotal = 0;
   for (const entry of entries) {
     await db.execute(
-      `INSERT INTO transactions (date, description, amount, category_id, source_id, file_id, original_description, parent_transaction_id, is_split)
-       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 1)`,
+      `INSERT INTO transactions (date, description, amount, category_id, source_id, file_id, original_description, parent_transaction_id, is_split, currency)
+       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 1, $9)`,
       [
         parent.date,
         entry.description,
-        entry.amount,
+        toCents(entry.amount),
         entry.category_id,
         parent.source_id ?? null,
         parent.file_id ?? null,
         parent.original_description ?? "",
         parentId,
+        parent.currency ?? null,
       ]
     );
-    offsetTotal += entry.amount;
+    offsetTotal += toCents(entry.amount);
   }
 
   // Insert offset child (cancels the redistributed portion from the original category)
   await db.execute(
-    `INSERT INTO transactions (date, description, amount, category_id, source_id, file_id, original_description, parent_transaction_id, is_split)
-     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 1)`,
+    `INSERT INTO transactions (date, description, amount, category_id, source_id, file_id, original_description, parent_transaction_id, is_split, currency)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 1, $9)`,
     [
       parent.date,
       parent.description,
@@ -352,6 +362,7 @@ export async function saveSplitAdjustment(
       parent.file_id ?? null,
       parent.original_description ?? "",
       parentId,
+      parent.currency ?? null,
     ]
   );
 
@@ -360,6 +371,7 @@ export async function saveSplitAdjustment(
     `UPDATE transactions SET is_split = 1, updated_at = CURRENT_TIMESTAMP WHERE id = $1`,
     [parentId]
   );
+  await refreshBaseAmounts(true);
 }
 
 export async function deleteSplitAdjustment(parentId: number): Promise<void> {
@@ -1,4 +1,22 @@
-export interface ImportSource {
+export type AccountType =
+  | "chequing"
+  | "savings"
+  | "credit_card"
+  | "cash"
+  | "investment"
+  | "loan"
+  | "other";
+
+/** Account settings carried by an import source */
+export interface AccountMetadata {
+  account_type: AccountType;
+  opening_balance: number;
+  opening_balance_date?: string | null; // balance at the start of this day
+  currency: string;
+  is_closed: boolean;
+}
+
+export interface ImportSource extends AccountMetadata {
   id: number;
   name: string;
   description?: string;
@@ -12,6 +30,127 @@ export interface ImportSource {
   updated_at: string;
 }
 
+export type StatementOrigin = "ofx" | "camt" | "manual";
+
+export interface StatementCheck {
+  id: number;
+  source_id: number;
+  file_id: number | null;
+  balance_date: string;
+  origin: StatementOrigin;
+  currency: string | null;
+  statement_balance: number;
+  computed_balance: number;
+  difference: number; // statement minus computed
+  matches: boolean;
+}
+
+export interface AccountBalance extends AccountMetadata {
+  source_id: number;
+  name: string;
+  balance: number;
+  as_of: string;
+  transaction_count: number;
+  last_statement: StatementCheck | null;
+}
+
+export interface RunningBalanceRow {
+  transaction_id: number;
+  date: string;
+  description: string;
+  amount: number;
+  balance: number; // after this transaction
+}
+
+export interface Reconciliation {
+  id: number;
+  source_id: number;
+  statement_date: string;
+  statement_balance: number;
+  computed_balance: number;
+  transaction_count: number; // transactions locked by this reconciliation
+  notes: string | null;
+  created_at: string;
+}
+
+export type ReconciliationProposalKind = "duplicate" | "late_posting" | "missing";
+
+export interface ReconciliationProposal {
+  kind: ReconciliationProposalKind;
+  transaction_id: number | null; // null for "missing"
+  date: string;
+  description: string;
+  amount: number;
+  explains_difference: boolean;
+}
+
+export interface ReconciliationPreview {
+  source_id: number;
+  statement_date: string;
+  statement_balance: number;
+  computed_balance: number;
+  difference: number; // statement minus computed
+  matches: boolean;
+  last_reconciled_date: string | null;
+  unreconciled_count: number;
+  proposals: ReconciliationProposal[];
+}
+
+export interface MissingRate {
+  currency: string;
+  transaction_count: number;
+  first_date: string;
+  last_date: string;
+}
+
+export interface ConversionSummary {
+  base_currency: string;
+  converted: number;
+  missing: MissingRate[]; // transactions reports count unconverted
+}
+
+export interface RateImportReport {
+  rate_count: number;
+  currencies: string[];
+  date_from: string;
+  date_to: string;
+  conversion: ConversionSummary;
+}
+
+export interface RateCoverage {
+  from_currency: string;
+  to_currency: string;
+  rate_count: number;
+  first_date: string;
+  last_date: string;
+}
+
+export interface ImportDates {
+  dates: (string | null)[]; // ISO date per value, null when invalid in the format
+  ambiguous_with: string | null; // day/month-swapped format that also reads every value
+}
+
+export interface ImportAmount {
+  amount: number | null; // signed amount, expenses negative; null when a cell is invalid
+  error: string | null; // which cell could not be read, and why
+}
+
+export interface DateFix {
+  table: string;
+  column: string;
+  row_id: number;
+  value: string;
+  normalized: string | null; // null when left unchanged
+  problem: string | null;
+}
+
+export interface DateAuditReport {
+  dry_run: boolean;
+  checked: number;
+  fixes: DateFix[];
+  applied: boolean;
+}
+
 export interface ImportedFile {
   id: number;
   source_id: number;
@@ -37,6 +176,7 @@ export interface Category {
   is_active: boolean;
   is_inputable: boolean;
   sort_order: number;
+  budget_rollover: boolean;
   created_at: string;
 }
 
@@ -73,6 +213,9 @@ export interface Transaction {
   is_manually_categorized: boolean;
   is_split: boolean;
   parent_transaction_id?: number;
+  reconciliation_id?: number;
+  currency?: string; // own currency; unset follows the account
+  base_amount?: number; // amount in the base currency, unset when no rate covers it
   created_at: string;
   updated_at: string;
 }
@@ -96,6 +239,33 @@ export interface AdjustmentEntry {
   description?: string;
 }
 
+export interface AdjustmentException {
+  id: number;
+  adjustment_id: number;
+  occurrence_date: string;
+  is_cancelled: boolean;
+  override_date?: string;
+  created_at: string;
+}
+
+export interface AdjustmentOccurrenceEntry {
+  category_id: number;
+  category_name: string;
+  category_color: string;
+  category_type: string;
+  amount: number;
+  description?: string | null;
+}
+
+export interface AdjustmentOccurrence {
+  adjustment_id: number;
+  name: string;
+  occurrence_date: string; // date produced by the recurrence rule
+  date: string;            // date it applies to, after an override
+  is_overridden: boolean;
+  entries: AdjustmentOccurrenceEntry[];
+}
+
 export interface BudgetEntry {
   id: number;
   category_id: number;
@@ -107,6 +277,69 @@ export interface BudgetEntry {
   updated_at: string;
 }
 
+export interface EnvelopeMonth {
+  month: number;
+  carried_in: number; // left (negative when overspent) at the end of the previous month
+  budgeted: number;
+  transferred: number; // moved in, less moved out
+  spent: number; // expenses less refunds, adjustments included
+  available: number;
+}
+
+export interface Envelope {
+  category_id: number;
+  category_name: string;
+  category_color: string;
+  rollover: boolean;
+  months: EnvelopeMonth[]; // January to December
+}
+
+export interface BudgetTransfer {
+  id: number;
+  year: number;
+  month: number;
+  from_category_id: number;
+  from_category_name: string;
+  to_category_id: number;
+  to_category_name: string;
+  amount: number;
+  notes: string | null;
+  reversed_transfer_id: number | null; // the transfer this one undoes
+  is_reversed: boolean;
+  created_at: string;
+}
+
+export type ForecastMethod = "auto" | "average" | "median" | "same_month_last_year" | "recurring";
+
+export interface RecurringBill {
+  description: string; // supplier name, or the transaction description
+  amount: number; // latest monthly amount
+  months_seen: number;
+  last_date: string;
+}
+
+export interface ForecastLine {
+  category_id: number;
+  category_name: string;
+  category_color: string;
+  category_type: "expense" | "income";
+  method: Exclude<ForecastMethod, "auto">;
+  months: number[]; // proposed budget, January to December, positive like budget entries
+  current: number[]; // budget already entered, January to December
+  average: number; // monthly, over the history
+  median: number;
+  active_months: number; // months of the history with any activity
+  seasonal: boolean;
+  recurring: RecurringBill[];
+}
+
+export interface BudgetForecast {
+  year: number;
+  history_from: string; // YYYY-MM
+  history_to: string;
+  lines: ForecastLine[];
+}
+
 export interface BudgetTemplate {
   id: number;
   name: string;
@@ -304,6 +537,8 @@ export interface PivotResult {
   rows: PivotResultRow[];
   columnValues: string[];          // distinct values for column dimension
   dimensionLabels: Record<string, string>; // field id → display label
+  subtotals: PivotResultRow[];     // keyed by a row-dimension prefix and the column dimensions
+  grandTotals: PivotResultRow[];   // keyed by the column dimensions
 }
 
 export interface MonthlyTrendItem {
@@ -312,6 +547,17 @@ export interface MonthlyTrendItem {
   expenses: number;
 }
 
+export interface HouseholdTrendItem extends MonthlyTrendItem {
+  profiles: Array<{ profile_id: string; profile_name: string; income: number; expenses: number }>;
+}
+
+export interface HouseholdCategoryItem {
+  category_name: string;
+  category_color: string;
+  total: number;
+  profiles: Array<{ profile_id: string; profile_name: string; total: number }>;
+}
+
 export interface CategoryOverTimeItem {
   month: string;
   [categoryName: string]: number | string;
@@ -390,6 +636,7 @@ export interface TransactionRow {
   notes: string | null;
   is_manually_categorized: boolean;
   is_split: boolean;
+  reconciliation_id: number | null; // set once reconciled: date and amount are locked
 }
 
 export interface SplitChild {
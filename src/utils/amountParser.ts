@@ -1,6 +1,9 @@
 /**
  * Parse a French-formatted amount string to a number.
- * Handles formats like: 1.234,56 / 1234,56 / -1 234.56 / 1 234,56
+ * Handles formats like: 1.234,56 / 1234,56 / -1 234.56 / 1 234,56 / (12.00) / 12,00-
+ *
+ * Only used to recognize amount columns: imported amounts are read by the
+ * `parse_import_amounts` command, which reports an exact error per cell.
  */
 export function parseFrenchAmount(raw: string): number {
   if (!raw || typeof raw !== "string") return NaN;
@@ -10,6 +13,14 @@ export function parseFrenchAmount(raw: string): number {
   // Remove currency symbols and whitespace
   cleaned = cleaned.replace(/[€$£\s\u00A0]/g, "");
 
+  // Parenthesized and trailing-minus negatives: (12.00) / 12,00-
+  const parenthesized = /^\((.*)\)$/.exec(cleaned);
+  if (parenthesized) {
+    cleaned = "-" + parenthesized[1];
+  } else if (/^[^-].*-$/.test(cleaned)) {
+    cleaned = "-" + cleaned.slice(0, -1);
+  }
+
   // Detect if comma is decimal separator (French style)
   // Pattern: digits followed by comma followed by exactly 1-2 digits at end
   const frenchPattern = /,\d{1,2}$/;
@@ -1,5 +1,12 @@
+/** Whether day d exists in month m (1-12) of year y, e.g. not February 30th. */
+function isValidDay(y: number, m: number, d: number): boolean {
+  return m >= 1 && m <= 12 && d >= 1 && d <= new Date(y, m, 0).getDate();
+}
+
 /**
- * Parse a date string with a given format and return ISO YYYY-MM-DD.
+ * Parse a date string with a given format and return ISO YYYY-MM-DD, or "" when it is not
+ * a date that exists. Used to detect the format of a file; imports parse dates in Rust
+ * (`parseImportDates`).
  * Supported formats: DD/MM/YYYY, MM/DD/YYYY, YYYY-MM-DD, DD-MM-YYYY, DD.MM.YYYY, YYYYMMDD
  */
 export function parseDate(raw: string, format: string): string {
@@ -20,7 +27,7 @@ export function parseDate(raw: string, format: string): string {
     const m = parseInt(month, 10);
     const d = parseInt(day, 10);
     if (isNaN(y) || isNaN(m) || isNaN(d)) return "";
-    if (m < 1 || m > 12 || d < 1 || d > 31) return "";
+    if (!isValidDay(y, m, d)) return "";
 
     return `${year}-${month}-${day}`;
   }
@@ -55,10 +62,10 @@ export function parseDate(raw: string, format: string): string {
   const d = parseInt(day, 10);
 
   if (isNaN(y) || isNaN(m) || isNaN(d)) return "";
-  if (m < 1 || m > 12 || d < 1 || d > 31) return "";
 
   // Handle 2-digit years
   const fullYear = y < 100 ? (y > 50 ? 1900 + y : 2000 + y) : y;
+  if (!isValidDay(fullYear, m, d)) return "";
 
   return `${fullYear.toString().padStart(4, "0")}-${m.toString().padStart(2, "0")}-${d.toString().padStart(2, "0")}`;
 }
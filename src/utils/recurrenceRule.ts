@@ -0,0 +1,79 @@
+export type RecurrenceFrequency = "DAILY" | "WEEKLY" | "MONTHLY" | "YEARLY";
+export type RecurrenceEnd = "never" | "until" | "count";
+
+/** The RRULE subset edited by the adjustment form. */
+export interface RecurrenceOptions {
+  frequency: RecurrenceFrequency;
+  interval: number;
+  /** Day of the month (1-31, or -1 for the last day); null keeps the start date's day. */
+  monthDay: number | null;
+  end: RecurrenceEnd;
+  until: string; // YYYY-MM-DD
+  count: number;
+}
+
+export const DEFAULT_RECURRENCE: RecurrenceOptions = {
+  frequency: "MONTHLY",
+  interval: 1,
+  monthDay: null,
+  end: "never",
+  until: "",
+  count: 12,
+};
+
+/**
+ * Build an RFC 5545 RRULE value (without the "RRULE:" prefix), e.g.
+ * "FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=12".
+ */
+export function buildRecurrenceRule(options: RecurrenceOptions): string {
+  const parts = [`FREQ=${options.frequency}`];
+  if (options.interval > 1) parts.push(`INTERVAL=${options.interval}`);
+  if (options.monthDay !== null && (options.frequency === "MONTHLY" || options.frequency === "YEARLY")) {
+    parts.push(`BYMONTHDAY=${options.monthDay}`);
+  }
+  if (options.end === "until" && options.until) {
+    parts.push(`UNTIL=${options.until.replace(/-/g, "")}`);
+  } else if (options.end === "count" && options.count > 0) {
+    parts.push(`COUNT=${options.count}`);
+  }
+  return parts.join(";");
+}
+
+/**
+ * Read back a rule written by buildRecurrenceRule. Unknown parts are ignored; the
+ * backend validates the full rule.
+ */
+export function parseRecurrenceRule(rule: string | null | undefined): RecurrenceOptions {
+  const options = { ...DEFAULT_RECURRENCE };
+  if (!rule) return options;
+
+  for (const part of rule.replace(/^RRULE:/i, "").split(";")) {
+    const [name, value = ""] = part.split("=");
+    switch (name.toUpperCase()) {
+      case "FREQ":
+        if (["DAILY", "WEEKLY", "MONTHLY", "YEARLY"].includes(value.toUpperCase())) {
+          options.frequency = value.toUpperCase() as RecurrenceFrequency;
+        }
+        break;
+      case "INTERVAL":
+        options.interval = Math.max(1, parseInt(value, 10) || 1);
+        break;
+      case "BYMONTHDAY": {
+        const day = parseInt(value.split(",")[0], 10);
+        options.monthDay = isNaN(day) ? null : day;
+        break;
+      }
+      case "UNTIL":
+        if (/^\d{8}/.test(value)) {
+          options.end = "until";
+          options.until = `${value.slice(0, 4)}-${value.slice(4, 6)}-${value.slice(6, 8)}`;
+        }
+        break;
+      case "COUNT":
+        options.end = "count";
+        options.count = parseInt(value, 10) || DEFAULT_RECURRENCE.count;
+        break;
+    }
+  }
+  return options;
+}